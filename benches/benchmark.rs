@@ -1,31 +1,149 @@
-use ark_bls12_381::{G1Projective, G2Projective};
-use ark_ec::ProjectiveCurve;
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ark_bls12_381::Fr;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use rand::thread_rng;
-use small_powers_of_tau::{keypair::PrivateKey, serialisation::SubgroupCheck, srs::SRS};
+use small_powers_of_tau::{
+    keypair::PrivateKey,
+    serialisation::SubgroupCheck,
+    simulate::Simulation,
+    srs::{Parameters, SRS},
+};
 
-fn update_algo() {
-    use small_powers_of_tau::srs::*;
+// The number of G1 elements in each benchmarked SRS, from 2^12 to 2^20
+const LOG_SIZES: [u32; 5] = [12, 14, 16, 18, 20];
+// The number of G2 elements in each benchmarked SRS, as in the KZG ceremony
+const NUM_G2_ELEMENTS: usize = 65;
+// The number of update proofs in each benchmarked chain
+const CHAIN_LENGTHS: [usize; 3] = [16, 64, 256];
 
-    let params = Parameters {
-        num_g1_elements_needed: 2usize.pow(16),
-        num_g2_elements_needed: 2,
-    };
+fn parameters(log_size: u32) -> Parameters {
+    Parameters::new(1 << log_size, NUM_G2_ELEMENTS)
+}
+
+// An SRS which has been updated once, so that the points are not all the generator
+fn updated_srs(parameters: Parameters) -> SRS {
+    let mut srs = SRS::new(parameters).unwrap();
+    srs.update(PrivateKey::rand(&mut thread_rng()));
+    srs
+}
+
+fn update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update");
+    group.sample_size(10);
+
+    for log_size in LOG_SIZES {
+        let parameters = parameters(log_size);
+        let srs = SRS::new(parameters).unwrap();
+
+        group.throughput(Throughput::Elements(1 << log_size));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(1 << log_size),
+            &srs,
+            |b, srs| {
+                b.iter_batched(
+                    || (srs.clone(), PrivateKey::rand(&mut thread_rng())),
+                    |(mut srs, private_key)| black_box(srs.update(private_key)),
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn structure_check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("structure check");
+    group.sample_size(10);
+
+    for log_size in LOG_SIZES {
+        let srs = updated_srs(parameters(log_size));
 
-    // Simulate deserialisation
-    let acc = SRS::new(params);
-    let bytes = acc.serialise();
-    let mut acc = SRS::deserialise(&bytes, params);
+        group.throughput(Throughput::Elements(1 << log_size));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(1 << log_size),
+            &srs,
+            |b, srs| b.iter(|| black_box(srs.structure_check_opt(Fr::from(123456789u64)))),
+        );
+    }
+    group.finish();
+}
+
+fn chain_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chain verification");
+    group.sample_size(10);
+
+    for chain_length in CHAIN_LENGTHS {
+        // The SRS is kept small, so that only the chain of update proofs is measured
+        let transcript = Simulation::new(Parameters::new(2, 2), chain_length)
+            .run()
+            .unwrap();
 
-    let mut rng = &mut thread_rng();
-    let priv_key = PrivateKey::rand(rng);
-    acc.update(priv_key);
-    let bytes = acc.serialise();
+        group.throughput(Throughput::Elements(chain_length as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(chain_length),
+            &transcript,
+            |b, transcript| {
+                b.iter(|| {
+                    black_box(SRS::verify_updates(
+                        &transcript.starting_srs,
+                        &transcript.final_srs,
+                        &transcript.update_proofs,
+                        Fr::from(123456789u64),
+                    ))
+                })
+            },
+        );
+    }
+    group.finish();
 }
 
-fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("update algo", |b| b.iter(|| black_box(update_algo())));
+fn serialisation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialisation");
+    group.sample_size(10);
+
+    for log_size in LOG_SIZES {
+        let srs = updated_srs(parameters(log_size));
+
+        group.throughput(Throughput::Elements(1 << log_size));
+        group.bench_with_input(BenchmarkId::new("bytes", 1 << log_size), &srs, |b, srs| {
+            b.iter(|| black_box(srs.serialise_bytes()))
+        });
+        group.bench_with_input(BenchmarkId::new("hex", 1 << log_size), &srs, |b, srs| {
+            b.iter(|| black_box(srs.serialise()))
+        });
+    }
+    group.finish();
+}
+
+fn deserialisation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deserialisation");
+    group.sample_size(10);
+
+    for log_size in LOG_SIZES {
+        let parameters = parameters(log_size);
+        let bytes = updated_srs(parameters).serialise_bytes();
+
+        group.throughput(Throughput::Elements(1 << log_size));
+        for (name, subgroup_check) in [
+            ("no subgroup check", SubgroupCheck::None),
+            ("batched subgroup check", SubgroupCheck::Batched),
+            ("full subgroup check", SubgroupCheck::Full),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, 1 << log_size), &bytes, |b, bytes| {
+                b.iter(|| {
+                    black_box(SRS::deserialise_bytes(bytes, parameters, subgroup_check).unwrap())
+                })
+            });
+        }
+    }
+    group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(
+    benches,
+    update,
+    structure_check,
+    chain_verification,
+    serialisation,
+    deserialisation
+);
 criterion_main!(benches);
@@ -0,0 +1,16 @@
+fn main() {
+    // The scaffolding for the mobile bindings is generated from the UDL file
+    #[cfg(feature = "uniffi")]
+    uniffi_build::generate_scaffolding("./src/small_powers_of_tau.udl").unwrap();
+
+    // Sets up the linker arguments needed for the node.js addon
+    #[cfg(feature = "napi")]
+    napi_build::setup();
+
+    // The protobuf messages are generated from the proto file, along with the
+    // gRPC client and server when they are needed
+    #[cfg(all(feature = "proto", not(feature = "grpc")))]
+    prost_build::compile_protos(&["proto/ceremony.proto"], &["proto/"]).unwrap();
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/ceremony.proto").unwrap();
+}
@@ -0,0 +1,19 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use small_powers_of_tau::{
+    serialisation::SubgroupCheck,
+    srs::{Parameters, SRS},
+};
+
+// The parameters are kept small, so that the fuzzer can find inputs of the right length
+fuzz_target!(|input: (u8, u8, &[u8])| {
+    let (num_g1, num_g2, bytes) = input;
+    let parameters = Parameters::new(num_g1 as usize, num_g2 as usize);
+
+    if let Ok(srs) = SRS::deserialise_bytes(bytes, parameters, SubgroupCheck::Full) {
+        // Every point is checked, so the encoding must be canonical
+        assert_eq!(srs.serialise_bytes(), bytes);
+    }
+    let _ = SRS::deserialise_bytes(bytes, parameters, SubgroupCheck::Batched);
+    let _ = SRS::read_from(&mut &bytes[..], parameters, SubgroupCheck::Full);
+});
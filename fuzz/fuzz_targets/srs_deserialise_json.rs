@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use small_powers_of_tau::{
+    serialisation::SubgroupCheck,
+    srs::{Parameters, SRS},
+};
+
+fuzz_target!(|input: (u8, u8, Vec<String>, Vec<String>)| {
+    let (num_g1, num_g2, g1_powers, g2_powers) = input;
+    let parameters = Parameters::new(num_g1 as usize, num_g2 as usize);
+
+    let _ = SRS::deserialise((&g1_powers, &g2_powers), parameters, SubgroupCheck::Full);
+});
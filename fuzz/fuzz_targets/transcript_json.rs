@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use small_powers_of_tau::sdk::{
+    contribution::{Contribution, ContributionJSON},
+    transcript::{Transcript, TranscriptJSON},
+};
+
+// The json is sent to the sequencer and browser by untrusted parties
+fuzz_target!(|bytes: &[u8]| {
+    if let Ok(transcript_json) = serde_json::from_slice::<TranscriptJSON>(bytes) {
+        let _ = Transcript::from_json(&transcript_json);
+    }
+    if let Ok(contribution_json) = serde_json::from_slice::<ContributionJSON>(bytes) {
+        if let Ok(contribution) = Contribution::from_json(&contribution_json) {
+            let _ = contribution.check_parameters();
+        }
+    }
+});
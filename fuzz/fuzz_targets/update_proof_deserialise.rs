@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use small_powers_of_tau::{serialisation::UPDATE_PROOF_SERIALISED_SIZE, update_proof::UpdateProof};
+
+fuzz_target!(|input: ([u8; UPDATE_PROOF_SERIALISED_SIZE], [String; 2])| {
+    let (bytes, json_array) = input;
+
+    if let Ok(update_proof) = UpdateProof::deserialise_bytes(&bytes) {
+        assert_eq!(update_proof.serialise_bytes(), bytes);
+    }
+    let _ = UpdateProof::deserialise(json_array);
+});
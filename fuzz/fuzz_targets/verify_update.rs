@@ -0,0 +1,26 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use small_powers_of_tau::{
+    serialisation::{SubgroupCheck, UPDATE_PROOF_SERIALISED_SIZE},
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+
+// Verification of an update which was deserialised from untrusted bytes should
+// return an error rather than panic
+fuzz_target!(|input: (&[u8], [u8; UPDATE_PROOF_SERIALISED_SIZE], u64)| {
+    let (srs_bytes, proof_bytes, random_element) = input;
+    let parameters = Parameters::new(4, 2);
+
+    let before = SRS::new(parameters).unwrap();
+    let after = match SRS::deserialise_bytes(srs_bytes, parameters, SubgroupCheck::Full) {
+        Ok(srs) => srs,
+        Err(_) => return,
+    };
+    let update_proof = match UpdateProof::deserialise_bytes(&proof_bytes) {
+        Ok(update_proof) => update_proof,
+        Err(_) => return,
+    };
+
+    let _ = SRS::verify_update(&before, &after, &update_proof, random_element.into());
+});
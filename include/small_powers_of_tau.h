@@ -0,0 +1,68 @@
+/*
+ * C bindings for small-powers-of-tau, enabled with the `ffi` feature.
+ *
+ * SRS buffers hold the compressed G1 points followed by the compressed G2
+ * points, their length is given by `srs_serialised_size`. Update proofs are
+ * SPOT_UPDATE_PROOF_SIZE bytes; the compressed commitment to the secret
+ * followed by the compressed new accumulated point.
+ *
+ * Unless stated otherwise, every pointer must be non-null and point to a
+ * buffer of the stated length, which stays valid for the duration of the call.
+ * Output buffers must not overlap with input buffers.
+ */
+#ifndef SMALL_POWERS_OF_TAU_H
+#define SMALL_POWERS_OF_TAU_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define SPOT_UPDATE_PROOF_SIZE 144
+
+#define SPOT_OK 0
+#define SPOT_ERR_NULL_POINTER 1
+#define SPOT_ERR_INVALID_LENGTH 2
+#define SPOT_ERR_DESERIALISATION 3
+#define SPOT_ERR_INVALID_UPDATE 4
+#define SPOT_ERR_PANIC 5
+#define SPOT_ERR_INVALID_SECRET 6
+
+/* Returns the serialised length of an SRS, or 0 if it does not fit into a size_t */
+size_t srs_serialised_size(size_t num_g1, size_t num_g2);
+
+/* Writes the starting SRS of a ceremony into `srs_out`, which is `srs_len` bytes */
+int32_t srs_new(size_t num_g1, size_t num_g2, uint8_t *srs_out, size_t srs_len);
+
+/*
+ * Updates the SRS in `srs_in` using the secret, writing the updated SRS into
+ * `srs_out` and the update proof into `proof_out`.
+ *
+ * `srs_in` and `srs_out` are both `srs_len` bytes, `proof_out` is
+ * SPOT_UPDATE_PROOF_SIZE bytes and `secret` is `secret_len` bytes. Returns
+ * SPOT_ERR_INVALID_SECRET if the secret is zero or one modulo the group order.
+ */
+int32_t srs_contribute(const uint8_t *srs_in, uint8_t *srs_out, size_t srs_len,
+                       size_t num_g1, size_t num_g2,
+                       const uint8_t *secret, size_t secret_len,
+                       uint8_t *proof_out);
+
+/*
+ * Verifies that `srs_after` was obtained by updating `srs_before` using the
+ * update proof. Returns SPOT_OK if the update is valid and
+ * SPOT_ERR_INVALID_UPDATE if it is not.
+ *
+ * `srs_before` and `srs_after` are both `srs_len` bytes and `proof` is
+ * SPOT_UPDATE_PROOF_SIZE bytes.
+ */
+int32_t srs_verify_update(const uint8_t *srs_before, const uint8_t *srs_after,
+                          size_t srs_len, size_t num_g1, size_t num_g2,
+                          const uint8_t *proof);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SMALL_POWERS_OF_TAU_H */
@@ -0,0 +1,24 @@
+// The same as `ContributionPhase` in the typings generated from `src/wasm.rs`,
+// which this needs to be kept in sync with
+export type ContributionPhase = "deserialise" | "validate" | "update" | "serialise";
+
+export interface ContributeInWorkerOptions {
+    // The url of `contribution_worker.js`
+    workerUrl: string;
+    // The urls of the files built with `wasm-pack build --target no-modules`
+    scriptUrl: string;
+    wasmUrl: string;
+    // Signed with each secret, see `contribute_json`
+    identity?: string;
+    // Entropy collected on the page, for example from mouse movements. The
+    // worker has its own wasm instance, so entropy passed to `add_entropy` on
+    // the page is not used; it is added to the worker's instance instead
+    entropy?: Uint8Array;
+    onProgress?: (phase: ContributionPhase, percentage: number) => void;
+}
+
+// Resolves with the json returned by `contribute_json`
+export function contributeInWorker(
+    contribution: string,
+    options: ContributeInWorkerOptions
+): Promise<string>;
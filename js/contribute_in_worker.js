@@ -0,0 +1,60 @@
+// Runs a contribution in a dedicated Web Worker, see `contribution_worker.js`.
+//
+// const response = await contributeInWorker(batchContributionJson, {
+//     workerUrl: "/contribution_worker.js",
+//     scriptUrl: "/pkg/small_powers_of_tau.js",
+//     wasmUrl: "/pkg/small_powers_of_tau_bg.wasm",
+//     identity: "eth|0x...",
+//     entropy: collectedEntropy,
+//     onProgress: (phase, percentage) => updateProgressBar(phase, percentage),
+// });
+//
+// Resolves with the json returned by `contribute_json` and rejects with the
+// error thrown by it. The urls are resolved against the page, and the worker
+// is terminated once the contribution has finished.
+//
+// The worker runs its own instance of the wasm module, so entropy which the
+// page passed to `add_entropy` does not reach it. Pass the entropy collected on
+// the page as `entropy` instead, and the worker adds it before contributing.
+export function contributeInWorker(contribution, options) {
+    const { workerUrl, scriptUrl, wasmUrl, identity, entropy, onProgress } = options;
+
+    return new Promise((resolve, reject) => {
+        const worker = new Worker(new URL(workerUrl, location.href));
+        const finish = (settle, value) => {
+            worker.terminate();
+            settle(value);
+        };
+
+        worker.onmessage = (event) => {
+            const message = event.data;
+            switch (message.type) {
+                case "progress":
+                    if (onProgress) {
+                        onProgress(message.phase, message.percentage);
+                    }
+                    break;
+                case "result":
+                    finish(resolve, message.response);
+                    break;
+                case "error":
+                    finish(reject, message.error);
+                    break;
+            }
+        };
+        // The worker script could not be loaded, or threw outside of the contribution
+        worker.onerror = (event) => {
+            event.preventDefault();
+            finish(reject, event.message || "the contribution worker failed");
+        };
+
+        worker.postMessage({
+            type: "contribute",
+            scriptUrl: new URL(scriptUrl, location.href).href,
+            wasmUrl: new URL(wasmUrl, location.href).href,
+            contribution,
+            identity,
+            entropy,
+        });
+    });
+}
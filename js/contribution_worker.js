@@ -0,0 +1,51 @@
+// A dedicated Web Worker which computes a contribution, so that the page stays
+// responsive while the SRS are updated. Use it through `contributeInWorker`
+// in `contribute_in_worker.js` rather than posting messages to it directly.
+//
+// The worker receives a single message
+//
+//   { type: "contribute", scriptUrl, wasmUrl, contribution, identity, entropy }
+//
+// where `scriptUrl` and `wasmUrl` point to the javascript and wasm files built
+// with `wasm-pack build --target no-modules`, and `entropy` is an optional
+// Uint8Array which is passed to `add_entropy` before contributing. It replies with any number of
+//
+//   { type: "progress", phase, percentage }
+//
+// followed by either `{ type: "result", response }`, where `response` is the
+// json returned by `contribute_json`, or `{ type: "error", error }`.
+"use strict";
+
+self.onmessage = async (event) => {
+    const message = event.data;
+    if (message.type !== "contribute") {
+        self.postMessage({ type: "error", error: `unexpected message type ${message.type}` });
+        return;
+    }
+
+    try {
+        importScripts(message.scriptUrl);
+        await wasm_bindgen(message.wasmUrl);
+
+        // Only the `wasm-threads` build exports the thread pool
+        if (typeof wasm_bindgen.initThreadPool === "function") {
+            await wasm_bindgen.initThreadPool(navigator.hardwareConcurrency);
+        }
+
+        // The entropy which the page collected was added to the page's own
+        // instance, so it has to be added to this one as well
+        if (message.entropy) {
+            wasm_bindgen.add_entropy(message.entropy);
+        }
+
+        const response = wasm_bindgen.contribute_json(
+            message.contribution,
+            message.identity,
+            (phase, percentage) => self.postMessage({ type: "progress", phase, percentage })
+        );
+        self.postMessage({ type: "result", response });
+    } catch (error) {
+        // The bindings throw strings, anything else is an error of the worker itself
+        self.postMessage({ type: "error", error: String(error) });
+    }
+};
@@ -0,0 +1,662 @@
+// Storage for the powers of tau, so that ceremonies which are far larger
+// than the available memory can be created, updated and verified.
+//
+// An `Accumulator` reads and writes ranges of the powers as sections, see the
+// section module. `SRS` is the in-memory accumulator, and `FileAccumulator`
+// keeps the powers on disk in checksummed chunks.
+use crate::{
+    error::{DegenerateSecret, Error, ParameterError, SerialisationError, VerificationError},
+    interop_point_encoding::{G1_SERIALISED_SIZE, G2_SERIALISED_SIZE},
+    keypair::PrivateKey,
+    section::{G1Section, G2Section, Section},
+    serialisation::{check_bounds, g1s_from_bytes, g1s_to_bytes, g2s_from_bytes, g2s_to_bytes},
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+    verification::{self, PreparedG2Tau},
+};
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_ec::AffineCurve;
+use ark_ff::Zero;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+pub trait Accumulator {
+    fn parameters(&self) -> Parameters;
+
+    // Reads the powers in `range`, which is truncated to the number of powers in the group
+    fn read_g1(&mut self, range: Range<usize>) -> Result<G1Section, Error>;
+    fn read_g2(&mut self, range: Range<usize>) -> Result<G2Section, Error>;
+
+    // Replaces the powers in the range of the section
+    fn write_g1(&mut self, section: &G1Section) -> Result<(), Error>;
+    fn write_g2(&mut self, section: &G2Section) -> Result<(), Error>;
+
+    // Checks whether this accumulator and `other` were created using the same
+    // tau, for example to confirm that an SRS converted from another format
+    // matches the original.
+    //
+    // Only the degree-0 and degree-1 powers are compared, so both accumulators
+    // should also be checked using `verify`
+    fn same_secret_as(&mut self, other: &mut impl Accumulator) -> Result<bool, Error>
+    where
+        Self: Sized,
+    {
+        let (g1_tau, g2_tau) = first_powers(self)?;
+        let (other_g1_tau, other_g2_tau) = first_powers(other)?;
+
+        Ok(verification::same_ratio(g1_tau, other_g2_tau)
+            && verification::same_ratio(other_g1_tau, g2_tau))
+    }
+
+    // Iterates over the G1 powers in order, reading `chunk_size` powers at a
+    // time, so that only one chunk is held in memory
+    fn iter_g1_powers(&mut self, chunk_size: usize) -> Powers<'_, Self, G1Affine>
+    where
+        Self: Sized,
+    {
+        let len = self.parameters().num_g1_elements();
+        Powers::new(self, len, chunk_size, |accumulator, range| {
+            Ok(accumulator.read_g1(range)?.into_points())
+        })
+    }
+    fn iter_g2_powers(&mut self, chunk_size: usize) -> Powers<'_, Self, G2Affine>
+    where
+        Self: Sized,
+    {
+        let len = self.parameters().num_g2_elements();
+        Powers::new(self, len, chunk_size, |accumulator, range| {
+            Ok(accumulator.read_g2(range)?.into_points())
+        })
+    }
+}
+
+// The powers of one group of an accumulator, see `Accumulator::iter_g1_powers`.
+//
+// Iteration stops after the first error, for example a chunk of a
+// `FileAccumulator` which does not match its checksum
+pub struct Powers<'a, A, P> {
+    accumulator: &'a mut A,
+    read: fn(&mut A, Range<usize>) -> Result<Vec<P>, Error>,
+    chunk: std::vec::IntoIter<P>,
+    // The degree of the first power which has not been read
+    next: usize,
+    len: usize,
+    chunk_size: usize,
+}
+
+impl<'a, A, P> Powers<'a, A, P> {
+    fn new(
+        accumulator: &'a mut A,
+        len: usize,
+        chunk_size: usize,
+        read: fn(&mut A, Range<usize>) -> Result<Vec<P>, Error>,
+    ) -> Self {
+        Powers {
+            accumulator,
+            read,
+            chunk: Vec::new().into_iter(),
+            next: 0,
+            len,
+            chunk_size: std::cmp::max(chunk_size, 1),
+        }
+    }
+}
+
+impl<'a, A, P> Iterator for Powers<'a, A, P> {
+    type Item = Result<P, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(point) = self.chunk.next() {
+            return Some(Ok(point));
+        }
+        if self.next >= self.len {
+            return None;
+        }
+
+        let range = self.next..std::cmp::min(self.next.saturating_add(self.chunk_size), self.len);
+        match (self.read)(self.accumulator, range.clone()) {
+            Ok(points) => {
+                self.next = range.end;
+                self.chunk = points.into_iter();
+                self.chunk.next().map(Ok)
+            }
+            Err(error) => {
+                self.next = self.len;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl Accumulator for SRS {
+    fn parameters(&self) -> Parameters {
+        Parameters::new(self.g1_elements().len(), self.g2_elements().len())
+    }
+
+    fn read_g1(&mut self, range: Range<usize>) -> Result<G1Section, Error> {
+        let range = truncate(range, self.g1_elements().len());
+        Ok(Section::new(
+            range.start,
+            self.g1_elements()[range].to_vec(),
+        ))
+    }
+    fn read_g2(&mut self, range: Range<usize>) -> Result<G2Section, Error> {
+        let range = truncate(range, self.g2_elements().len());
+        Ok(Section::new(
+            range.start,
+            self.g2_elements()[range].to_vec(),
+        ))
+    }
+
+    fn write_g1(&mut self, section: &G1Section) -> Result<(), Error> {
+        let num_g1 = self.g1_elements().len();
+        let points = self.g1_elements_mut().get_mut(section.range()).ok_or(
+            ParameterError::WrongNumberOfG1Elements {
+                expected: num_g1,
+                got: section.range().end,
+            },
+        )?;
+        points.copy_from_slice(section.points());
+        Ok(())
+    }
+    fn write_g2(&mut self, section: &G2Section) -> Result<(), Error> {
+        let num_g2 = self.g2_elements().len();
+        let points = self.g2_elements_mut().get_mut(section.range()).ok_or(
+            ParameterError::WrongNumberOfG2Elements {
+                expected: num_g2,
+                got: section.range().end,
+            },
+        )?;
+        points.copy_from_slice(section.points());
+        Ok(())
+    }
+}
+
+// Identifies a file as an accumulator, followed by the version of its layout
+const MAGIC: [u8; 8] = *b"SPOTACC\x01";
+// The magic bytes, followed by the number of G1 powers, the number of G2
+// powers and the number of powers in each chunk, as little endian u64s
+const HEADER_SIZE: usize = 32;
+// The size of the sha256 checksum at the end of each chunk
+const CHECKSUM_SIZE: usize = 32;
+
+// An accumulator which keeps the powers in a file, or anything else which can be seeked.
+//
+// The file starts with a header, followed by the compressed G1 powers and then
+// the compressed G2 powers. The powers in each group are split into chunks of
+// `chunk_size` powers, and each chunk is followed by its sha256 checksum. The
+// checksum is checked whenever a chunk is read, so that a file which was
+// corrupted on disk is not mistaken for an invalid contribution
+pub struct FileAccumulator<F> {
+    file: F,
+    parameters: Parameters,
+    chunk_size: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Group {
+    G1,
+    G2,
+}
+
+impl<F: Read + Write + Seek> FileAccumulator<F> {
+    // Creates an accumulator where every power is the generator, see `SRS::new`
+    pub fn create(file: F, parameters: Parameters, chunk_size: usize) -> Result<Self, Error> {
+        if parameters.num_g1_elements() < 2 || parameters.num_g2_elements() < 2 {
+            return Err(ParameterError::TooFewElements.into());
+        }
+        check_bounds(parameters)?;
+
+        let mut accumulator = FileAccumulator {
+            file,
+            parameters,
+            // A chunk never needs to hold more than every power in a group
+            chunk_size: chunk_size.clamp(
+                1,
+                std::cmp::max(parameters.num_g1_elements(), parameters.num_g2_elements()),
+            ),
+        };
+
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend_from_slice(&MAGIC);
+        header.extend_from_slice(&(parameters.num_g1_elements() as u64).to_le_bytes());
+        header.extend_from_slice(&(parameters.num_g2_elements() as u64).to_le_bytes());
+        header.extend_from_slice(&(accumulator.chunk_size as u64).to_le_bytes());
+        accumulator.write_at(0, &header)?;
+
+        for chunk in 0..accumulator.num_chunks(Group::G1) {
+            let num_points = accumulator.chunk_range(Group::G1, chunk).len();
+            let points = vec![G1Affine::prime_subgroup_generator(); num_points];
+            accumulator.write_chunk(Group::G1, chunk, &g1s_to_bytes(&points))?;
+        }
+        for chunk in 0..accumulator.num_chunks(Group::G2) {
+            let num_points = accumulator.chunk_range(Group::G2, chunk).len();
+            let points = vec![G2Affine::prime_subgroup_generator(); num_points];
+            accumulator.write_chunk(Group::G2, chunk, &g2s_to_bytes(&points))?;
+        }
+        accumulator.file.flush().map_err(SerialisationError::Io)?;
+
+        Ok(accumulator)
+    }
+
+    // Opens an accumulator which was created using `create`
+    pub fn open(mut file: F) -> Result<Self, Error> {
+        let mut header = [0u8; HEADER_SIZE];
+        file.seek(SeekFrom::Start(0))
+            .and_then(|_| file.read_exact(&mut header))
+            .map_err(SerialisationError::Io)?;
+
+        let read_u64 = |offset: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&header[offset..offset + 8]);
+            usize::try_from(u64::from_le_bytes(bytes))
+        };
+        let (num_g1, num_g2, chunk_size) = match (read_u64(8), read_u64(16), read_u64(24)) {
+            (Ok(num_g1), Ok(num_g2), Ok(chunk_size)) => (num_g1, num_g2, chunk_size),
+            _ => return Err(SerialisationError::InvalidHeader.into()),
+        };
+        if header[..8] != MAGIC
+            || chunk_size == 0
+            || chunk_size > std::cmp::max(num_g1, num_g2)
+            || num_g1 < 2
+            || num_g2 < 2
+        {
+            return Err(SerialisationError::InvalidHeader.into());
+        }
+
+        let parameters = Parameters::new(num_g1, num_g2);
+        check_bounds(parameters)?;
+
+        Ok(FileAccumulator {
+            file,
+            parameters,
+            chunk_size,
+        })
+    }
+
+    pub fn into_inner(self) -> F {
+        self.file
+    }
+
+    fn point_size(group: Group) -> usize {
+        match group {
+            Group::G1 => G1_SERIALISED_SIZE,
+            Group::G2 => G2_SERIALISED_SIZE,
+        }
+    }
+    fn num_points(&self, group: Group) -> usize {
+        match group {
+            Group::G1 => self.parameters.num_g1_elements(),
+            Group::G2 => self.parameters.num_g2_elements(),
+        }
+    }
+    fn num_chunks(&self, group: Group) -> usize {
+        (self.num_points(group) + self.chunk_size - 1) / self.chunk_size
+    }
+    // The powers which are stored in a chunk
+    fn chunk_range(&self, group: Group, chunk: usize) -> Range<usize> {
+        let start = chunk * self.chunk_size;
+        start..std::cmp::min(start + self.chunk_size, self.num_points(group))
+    }
+    // The position of a chunk in the file
+    fn chunk_offset(&self, group: Group, chunk: usize) -> u64 {
+        let group_offset = match group {
+            Group::G1 => HEADER_SIZE,
+            Group::G2 => {
+                HEADER_SIZE
+                    + self.num_points(Group::G1) * G1_SERIALISED_SIZE
+                    + self.num_chunks(Group::G1) * CHECKSUM_SIZE
+            }
+        };
+        let chunk_offset = chunk * (self.chunk_size * Self::point_size(group) + CHECKSUM_SIZE);
+        (group_offset + chunk_offset) as u64
+    }
+
+    fn write_at(&mut self, offset: u64, bytes: &[u8]) -> Result<(), Error> {
+        self.file
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| self.file.write_all(bytes))
+            .map_err(SerialisationError::Io)?;
+        Ok(())
+    }
+
+    // Reads the compressed points in a chunk, checking them against the checksum
+    fn read_chunk(&mut self, group: Group, chunk: usize) -> Result<Vec<u8>, Error> {
+        let len = self.chunk_range(group, chunk).len() * Self::point_size(group);
+        let mut bytes = vec![0u8; len + CHECKSUM_SIZE];
+
+        let offset = self.chunk_offset(group, chunk);
+        self.file
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| self.file.read_exact(&mut bytes))
+            .map_err(SerialisationError::Io)?;
+
+        let checksum = bytes.split_off(len);
+        if Sha256::digest(&bytes).as_slice() != checksum.as_slice() {
+            return Err(SerialisationError::ChecksumMismatch.into());
+        }
+        Ok(bytes)
+    }
+    fn write_chunk(&mut self, group: Group, chunk: usize, points: &[u8]) -> Result<(), Error> {
+        let mut bytes = Vec::with_capacity(points.len() + CHECKSUM_SIZE);
+        bytes.extend_from_slice(points);
+        bytes.extend_from_slice(&Sha256::digest(points));
+
+        let offset = self.chunk_offset(group, chunk);
+        self.write_at(offset, &bytes)
+    }
+
+    // Reads the compressed points in `range`
+    fn read_range(&mut self, group: Group, range: Range<usize>) -> Result<Vec<u8>, Error> {
+        let point_size = Self::point_size(group);
+        let mut bytes = Vec::with_capacity(range.len() * point_size);
+
+        for chunk in self.chunks_in(range.clone()) {
+            let chunk_range = self.chunk_range(group, chunk);
+            let chunk_bytes = self.read_chunk(group, chunk)?;
+
+            let start = std::cmp::max(range.start, chunk_range.start) - chunk_range.start;
+            let end = std::cmp::min(range.end, chunk_range.end) - chunk_range.start;
+            bytes.extend_from_slice(&chunk_bytes[start * point_size..end * point_size]);
+        }
+        Ok(bytes)
+    }
+    // Replaces the compressed points starting at `start`. Chunks which are only
+    // partly replaced are read first, so that their checksums can be recomputed
+    fn write_range(&mut self, group: Group, start: usize, points: &[u8]) -> Result<(), Error> {
+        let point_size = Self::point_size(group);
+        let range = start..start + points.len() / point_size;
+        if range.end > self.num_points(group) {
+            return Err(SerialisationError::InvalidLength {
+                expected: (self.num_points(group) - std::cmp::min(start, self.num_points(group)))
+                    * point_size,
+                got: points.len(),
+            }
+            .into());
+        }
+
+        for chunk in self.chunks_in(range.clone()) {
+            let chunk_range = self.chunk_range(group, chunk);
+            let overlap_start = std::cmp::max(range.start, chunk_range.start);
+            let overlap_end = std::cmp::min(range.end, chunk_range.end);
+            let new_points =
+                &points[(overlap_start - start) * point_size..(overlap_end - start) * point_size];
+
+            if overlap_start == chunk_range.start && overlap_end == chunk_range.end {
+                self.write_chunk(group, chunk, new_points)?;
+            } else {
+                let mut chunk_bytes = self.read_chunk(group, chunk)?;
+                chunk_bytes[(overlap_start - chunk_range.start) * point_size
+                    ..(overlap_end - chunk_range.start) * point_size]
+                    .copy_from_slice(new_points);
+                self.write_chunk(group, chunk, &chunk_bytes)?;
+            }
+        }
+        Ok(())
+    }
+    fn chunks_in(&self, range: Range<usize>) -> Range<usize> {
+        if range.is_empty() {
+            return 0..0;
+        }
+        range.start / self.chunk_size..(range.end - 1) / self.chunk_size + 1
+    }
+}
+
+impl<F: Read + Write + Seek> Accumulator for FileAccumulator<F> {
+    fn parameters(&self) -> Parameters {
+        self.parameters
+    }
+
+    fn read_g1(&mut self, range: Range<usize>) -> Result<G1Section, Error> {
+        let range = truncate(range, self.num_points(Group::G1));
+        let bytes = self.read_range(Group::G1, range.clone())?;
+        Ok(Section::new(range.start, g1s_from_bytes(&bytes)?))
+    }
+    fn read_g2(&mut self, range: Range<usize>) -> Result<G2Section, Error> {
+        let range = truncate(range, self.num_points(Group::G2));
+        let bytes = self.read_range(Group::G2, range.clone())?;
+        Ok(Section::new(range.start, g2s_from_bytes(&bytes)?))
+    }
+
+    fn write_g1(&mut self, section: &G1Section) -> Result<(), Error> {
+        self.write_range(
+            Group::G1,
+            section.range().start,
+            &g1s_to_bytes(section.points()),
+        )
+    }
+    fn write_g2(&mut self, section: &G2Section) -> Result<(), Error> {
+        self.write_range(
+            Group::G2,
+            section.range().start,
+            &g2s_to_bytes(section.points()),
+        )
+    }
+}
+
+// Updates the accumulator `chunk_size` powers at a time, as `SRS::update` would.
+//
+// Secrets of zero and one are refused before anything is written, since the
+// update would otherwise only fail once it is verified
+pub fn update(
+    accumulator: &mut impl Accumulator,
+    private_key: PrivateKey,
+    chunk_size: usize,
+) -> Result<UpdateProof, Error> {
+    if private_key.is_degenerate() {
+        return Err(DegenerateSecret.into());
+    }
+    let chunk_size = std::cmp::max(chunk_size, 1);
+    let parameters = accumulator.parameters();
+
+    for start in (0..parameters.num_g1_elements()).step_by(chunk_size) {
+        let mut section = accumulator.read_g1(start..start.saturating_add(chunk_size))?;
+        section.update(&private_key);
+        accumulator.write_g1(&section)?;
+    }
+    for start in (0..parameters.num_g2_elements()).step_by(chunk_size) {
+        let mut section = accumulator.read_g2(start..start.saturating_add(chunk_size))?;
+        section.update(&private_key);
+        accumulator.write_g2(&section)?;
+    }
+
+    let degree_1 = accumulator.read_g1(1..2)?;
+    degree_1
+        .update_proof(private_key)
+        .ok_or_else(|| ParameterError::TooFewElements.into())
+}
+
+// Checks the structure of the accumulator, and that its powers are in the
+// prime order subgroup, `chunk_size` powers at a time
+pub fn verify(
+    accumulator: &mut impl Accumulator,
+    random_element: Fr,
+    chunk_size: usize,
+) -> Result<(), Error> {
+    let chunk_size = std::cmp::max(chunk_size, 1);
+    let parameters = accumulator.parameters();
+    let (g1_tau, g2_tau) = first_powers(accumulator)?;
+    let prepared_g2_tau = PreparedG2Tau::new(g2_tau);
+
+    // Each section overlaps the next section by one power, so that every
+    // pair of consecutive powers is checked
+    for start in (0..parameters.num_g1_elements()).step_by(chunk_size) {
+        let section =
+            accumulator.read_g1(start..start.saturating_add(chunk_size.saturating_add(1)))?;
+        section.subgroup_check()?;
+        section.verify_prepared(&prepared_g2_tau, random_element)?;
+    }
+    for start in (0..parameters.num_g2_elements()).step_by(chunk_size) {
+        let section =
+            accumulator.read_g2(start..start.saturating_add(chunk_size.saturating_add(1)))?;
+        section.subgroup_check_g2()?;
+        section.verify_g2(g1_tau, random_element)?;
+    }
+    Ok(())
+}
+
+// Verifies the transition from one accumulator to the other in the same way
+// as `SRS::verify_updates`, along with a subgroup check of `after`
+pub fn verify_updates(
+    before: &mut impl Accumulator,
+    after: &mut impl Accumulator,
+    update_proofs: &[UpdateProof],
+    random_element: Fr,
+    chunk_size: usize,
+) -> Result<(), Error> {
+    let last_update = update_proofs
+        .last()
+        .ok_or(VerificationError::NoUpdateProofs)?;
+
+    let (before_g1_tau, _) = first_powers(before)?;
+    let (after_g1_tau, after_g2_tau) = first_powers(after)?;
+
+    if after_g1_tau[1].into_projective() != last_update.new_accumulated_point {
+        return Err(VerificationError::UpdateDoesNotEndAtSRS.into());
+    }
+    if UpdateProof::first_no_op(before_g1_tau[1].into_projective(), update_proofs).is_some() {
+        return Err(VerificationError::NoOpUpdate.into());
+    }
+    if !UpdateProof::verify_chain(before_g1_tau[1].into_projective(), update_proofs) {
+        return Err(VerificationError::InvalidUpdateChain.into());
+    }
+    if after_g1_tau[1].is_zero() || after_g2_tau[1].is_zero() {
+        return Err(VerificationError::IdentityElement.into());
+    }
+
+    verify(after, random_element, chunk_size)
+}
+
+// The degree-0 and degree-1 powers in each group
+fn first_powers(
+    accumulator: &mut impl Accumulator,
+) -> Result<([G1Affine; 2], [G2Affine; 2]), Error> {
+    let g1 = accumulator.read_g1(0..2)?;
+    let g2 = accumulator.read_g2(0..2)?;
+
+    match (g1.points(), g2.points()) {
+        ([g1_0, g1_1], [g2_0, g2_1]) => Ok(([*g1_0, *g1_1], [*g2_0, *g2_1])),
+        _ => Err(ParameterError::TooFewElements.into()),
+    }
+}
+
+fn truncate(range: Range<usize>, len: usize) -> Range<usize> {
+    let end = std::cmp::min(range.end, len);
+    std::cmp::min(range.start, end)..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn file_accumulator_update_and_verify() {
+        let parameters = Parameters::new(100, 10);
+        let mut starting_srs = SRS::new(parameters).unwrap();
+
+        let mut expected_srs = starting_srs.clone();
+        let expected_proof = expected_srs.update(PrivateKey::from_u64(252));
+
+        let mut accumulator =
+            FileAccumulator::create(Cursor::new(Vec::new()), parameters, 16).unwrap();
+        let update_proof = update(&mut accumulator, PrivateKey::from_u64(252), 7).unwrap();
+        assert_eq!(update_proof, expected_proof);
+
+        // The accumulator can be reopened from the file
+        let mut accumulator = FileAccumulator::open(accumulator.into_inner()).unwrap();
+        assert_eq!(accumulator.parameters(), parameters);
+        assert_eq!(
+            accumulator.read_g1(0..100).unwrap().points(),
+            expected_srs.g1_elements()
+        );
+        assert_eq!(
+            accumulator.read_g2(0..10).unwrap().points(),
+            expected_srs.g2_elements()
+        );
+        assert!(verify_updates(
+            &mut starting_srs,
+            &mut accumulator,
+            &[update_proof],
+            Fr::from(123456789u64),
+            16
+        )
+        .is_ok());
+
+        // The in-memory accumulator gives the same result
+        let mut srs = SRS::new(parameters).unwrap();
+        assert_eq!(
+            update(&mut srs, PrivateKey::from_u64(252), 7).unwrap(),
+            expected_proof
+        );
+        assert_eq!(srs, expected_srs);
+
+        // A secret of one would leave the accumulator unchanged
+        assert!(matches!(
+            update(&mut srs, PrivateKey::from_u64(1), 7),
+            Err(Error::DegenerateSecret(DegenerateSecret))
+        ));
+        assert_eq!(srs, expected_srs);
+
+        // Both accumulators were updated using the same secret
+        assert!(accumulator.same_secret_as(&mut srs).unwrap());
+        let mut other_srs = SRS::new(parameters).unwrap();
+        other_srs.update(PrivateKey::from_u64(2));
+        assert!(!accumulator.same_secret_as(&mut other_srs).unwrap());
+
+        // A corrupted chunk is detected when it is read
+        let mut file = accumulator.into_inner();
+        let position = file.get_ref().len() - 1;
+        file.get_mut()[position] ^= 1;
+        let mut accumulator = FileAccumulator::open(file).unwrap();
+        assert!(matches!(
+            accumulator.read_g2(9..10),
+            Err(Error::Serialisation(SerialisationError::ChecksumMismatch))
+        ));
+    }
+
+    #[test]
+    fn iterate_over_powers() {
+        let parameters = Parameters::new(20, 6);
+        let mut srs = SRS::new(parameters).unwrap();
+        srs.update(PrivateKey::from_u64(252));
+
+        let mut accumulator =
+            FileAccumulator::create(Cursor::new(Vec::new()), parameters, 4).unwrap();
+        accumulator
+            .write_g1(&G1Section::new(0, srs.g1_powers().to_vec()))
+            .unwrap();
+        accumulator
+            .write_g2(&G2Section::new(0, srs.g2_powers().to_vec()))
+            .unwrap();
+
+        let g1: Result<Vec<_>, _> = accumulator.iter_g1_powers(3).collect();
+        assert_eq!(g1.unwrap(), srs.g1_elements());
+        let g2: Result<Vec<_>, _> = srs.clone().iter_g2_powers(4).collect();
+        assert_eq!(g2.unwrap(), srs.g2_elements());
+
+        // Corrupt the last chunk, which holds the degree-4 and degree-5 G2
+        // powers. Iteration stops at the first power which cannot be read
+        let mut file = accumulator.into_inner();
+        let position = file.get_ref().len() - 1;
+        file.get_mut()[position] ^= 1;
+        let mut accumulator = FileAccumulator::open(file).unwrap();
+        let mut powers = accumulator.iter_g2_powers(4);
+        assert_eq!(
+            powers
+                .by_ref()
+                .take(4)
+                .filter(|power| power.is_ok())
+                .count(),
+            4
+        );
+        assert!(matches!(
+            powers.next(),
+            Some(Err(Error::Serialisation(
+                SerialisationError::ChecksumMismatch
+            )))
+        ));
+        assert!(powers.next().is_none());
+    }
+}
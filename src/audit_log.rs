@@ -0,0 +1,209 @@
+// An append-only log of the operations of a ceremony, such as accepting or
+// rejecting a contribution, so that disputes after the ceremony can be
+// settled from a record which cannot be changed without it being noticed.
+//
+// Each entry includes the hash of the entry before it, so changing, removing
+// or reordering entries changes the hash of every later entry. Publishing the
+// hash of the latest entry, for example in the receipts given to participants,
+// commits to the whole log. The log can be saved in any serde format and
+// checked offline using `AuditLog::from_entries`.
+//
+// Entries are hashed using their own encoding rather than a serde format, so
+// the hashes do not depend on how the log was saved.
+use crate::error::VerificationError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditEvent {
+    // The contribution with this digest, see `contribution_digest`, was added to the transcript
+    ContributionAccepted {
+        participant: String,
+        digest: [u8; 32],
+    },
+    ContributionRejected {
+        participant: String,
+        reason: String,
+    },
+    // An action taken by an operator of the ceremony
+    AdminAction {
+        action: String,
+    },
+    // A random beacon was applied to the transcript, giving the SRS with this digest
+    BeaconApplied {
+        beacon: String,
+        digest: [u8; 32],
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub index: u64,
+    // The time of the event, in seconds since the unix epoch
+    pub timestamp: u64,
+    pub event: AuditEvent,
+    pub previous_hash: [u8; 32],
+    pub hash: [u8; 32],
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        AuditLog::default()
+    }
+
+    // Checks a saved log, returning an error if any entry does not follow on
+    // from the one before it
+    pub fn from_entries(entries: Vec<AuditEntry>) -> Result<Self, VerificationError> {
+        let mut previous_hash = [0u8; 32];
+        let mut previous_timestamp = 0;
+
+        for (index, entry) in entries.iter().enumerate() {
+            if entry.index != index as u64
+                || entry.previous_hash != previous_hash
+                || entry.timestamp < previous_timestamp
+                || entry.hash != entry_hash(entry)
+            {
+                return Err(VerificationError::InvalidAuditLog);
+            }
+            previous_hash = entry.hash;
+            previous_timestamp = entry.timestamp;
+        }
+
+        Ok(AuditLog { entries })
+    }
+
+    // Adds an event to the end of the log. A timestamp which is earlier than
+    // the latest entry is raised to the time of that entry
+    pub fn append(&mut self, timestamp: u64, event: AuditEvent) -> &AuditEntry {
+        let (previous_hash, previous_timestamp) = match self.entries.last() {
+            Some(last) => (last.hash, last.timestamp),
+            None => ([0u8; 32], 0),
+        };
+
+        let mut entry = AuditEntry {
+            index: self.entries.len() as u64,
+            timestamp: std::cmp::max(timestamp, previous_timestamp),
+            event,
+            previous_hash,
+            hash: [0u8; 32],
+        };
+        entry.hash = entry_hash(&entry);
+
+        self.entries.push(entry);
+        self.entries.last().expect("an entry was just added")
+    }
+
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+    // The hash of the latest entry, which commits to the whole log
+    pub fn head(&self) -> [u8; 32] {
+        self.entries
+            .last()
+            .map(|entry| entry.hash)
+            .unwrap_or([0u8; 32])
+    }
+}
+
+fn entry_hash(entry: &AuditEntry) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(entry.previous_hash);
+    hasher.update(entry.index.to_le_bytes());
+    hasher.update(entry.timestamp.to_le_bytes());
+
+    // Each event is tagged, and strings are prefixed with their length, so
+    // that two different events cannot have the same encoding
+    match &entry.event {
+        AuditEvent::ContributionAccepted {
+            participant,
+            digest,
+        } => {
+            hasher.update([0u8]);
+            absorb_str(&mut hasher, participant);
+            hasher.update(digest);
+        }
+        AuditEvent::ContributionRejected {
+            participant,
+            reason,
+        } => {
+            hasher.update([1u8]);
+            absorb_str(&mut hasher, participant);
+            absorb_str(&mut hasher, reason);
+        }
+        AuditEvent::AdminAction { action } => {
+            hasher.update([2u8]);
+            absorb_str(&mut hasher, action);
+        }
+        AuditEvent::BeaconApplied { beacon, digest } => {
+            hasher.update([3u8]);
+            absorb_str(&mut hasher, beacon);
+            hasher.update(digest);
+        }
+    }
+    hasher.finalize().into()
+}
+
+fn absorb_str(hasher: &mut Sha256, string: &str) {
+    hasher.update((string.len() as u64).to_le_bytes());
+    hasher.update(string.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tampering_is_detected() {
+        let mut log = AuditLog::new();
+        log.append(
+            100,
+            AuditEvent::ContributionAccepted {
+                participant: "alice".to_string(),
+                digest: [1u8; 32],
+            },
+        );
+        log.append(
+            105,
+            AuditEvent::ContributionRejected {
+                participant: "bob".to_string(),
+                reason: "invalid update proof".to_string(),
+            },
+        );
+        log.append(
+            110,
+            AuditEvent::AdminAction {
+                action: "paused the ceremony".to_string(),
+            },
+        );
+
+        let entries = log.entries().to_vec();
+        assert_eq!(AuditLog::from_entries(entries.clone()).unwrap(), log);
+        assert_eq!(log.head(), entries[2].hash);
+
+        // Changing the reason for a rejection
+        let mut changed = entries.clone();
+        changed[1].event = AuditEvent::ContributionRejected {
+            participant: "bob".to_string(),
+            reason: "timed out".to_string(),
+        };
+        assert_eq!(
+            AuditLog::from_entries(changed),
+            Err(VerificationError::InvalidAuditLog)
+        );
+
+        // Removing an entry, even if its hash is recomputed
+        let mut removed = entries;
+        removed.remove(1);
+        removed[1].index = 1;
+        removed[1].hash = entry_hash(&removed[1]);
+        assert_eq!(
+            AuditLog::from_entries(removed),
+            Err(VerificationError::InvalidAuditLog)
+        );
+    }
+}
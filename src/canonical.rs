@@ -0,0 +1,190 @@
+// `CanonicalSerialize` and `CanonicalDeserialize` implementations, so that
+// arkworks based tooling can exchange ceremony objects with this crate.
+//
+// Points use the compressed encoding from the ceremony specs, rather than the
+// arkworks encoding, so that the bytes match `SRS::write_to` and
+// `UpdateProof::serialise_bytes`. Every point is checked to be in the prime
+// order subgroup when it is deserialised.
+use crate::{
+    error::{Error, SerialisationError},
+    interop_point_encoding::{G1_SERIALISED_SIZE, G2_SERIALISED_SIZE},
+    merkle::MerkleTree,
+    sdk::{transcript::Transcript, NUM_CEREMONIES},
+    serialisation::{SubgroupCheck, UPDATE_PROOF_SERIALISED_SIZE},
+    srs::{subgroup_check_points, Parameters, SRS},
+    update_proof::UpdateProof,
+};
+use ark_ec::ProjectiveCurve;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use std::io::{Read, Write};
+
+// An SRS is encoded as the number of G1 and G2 powers, each as a little
+// endian u64, followed by the bytes written by `SRS::write_to`
+impl CanonicalSerialize for SRS {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        (self.g1_elements().len() as u64).serialize(&mut writer)?;
+        (self.g2_elements().len() as u64).serialize(&mut writer)?;
+        self.write_to(&mut writer)?;
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        16 + self.g1_elements().len() * G1_SERIALISED_SIZE
+            + self.g2_elements().len() * G2_SERIALISED_SIZE
+    }
+}
+
+impl CanonicalDeserialize for SRS {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let num_g1 = u64::deserialize(&mut reader)?;
+        let num_g2 = u64::deserialize(&mut reader)?;
+        let parameters = Parameters::new(to_usize(num_g1)?, to_usize(num_g2)?);
+
+        // `read_from` checks the parameters against the maximum number of
+        // elements before allocating
+        SRS::read_from(&mut reader, parameters, SubgroupCheck::Full).map_err(to_ark_error)
+    }
+}
+
+impl CanonicalSerialize for UpdateProof {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        writer.write_all(&self.serialise_bytes())?;
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        UPDATE_PROOF_SERIALISED_SIZE
+    }
+}
+
+impl CanonicalDeserialize for UpdateProof {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; UPDATE_PROOF_SERIALISED_SIZE];
+        reader.read_exact(&mut bytes)?;
+        let update_proof =
+            UpdateProof::deserialise_bytes(&bytes).map_err(|err| to_ark_error(err.into()))?;
+
+        subgroup_check_points(
+            &[update_proof.new_accumulated_point.into_affine()],
+            &[update_proof.commitment_to_secret.into_affine()],
+        )
+        .map_err(|_| SerializationError::InvalidData)?;
+        Ok(update_proof)
+    }
+}
+
+// A transcript is encoded as the SRS of each ceremony, in order, followed by
+// the number of contributions as a little endian u64 and their digests
+impl CanonicalSerialize for Transcript {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        for srs in &self.transcripts {
+            srs.serialize(&mut writer)?;
+        }
+        (self.contributions.len() as u64).serialize(&mut writer)?;
+        for digest in self.contributions.digests() {
+            writer.write_all(digest)?;
+        }
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        let srs_size: usize = self.transcripts.iter().map(SRS::serialized_size).sum();
+        srs_size + 8 + 32 * self.contributions.len()
+    }
+}
+
+impl CanonicalDeserialize for Transcript {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut transcripts = Vec::with_capacity(NUM_CEREMONIES);
+        for _ in 0..NUM_CEREMONIES {
+            transcripts.push(SRS::deserialize(&mut reader)?);
+        }
+
+        // The digests are read one at a time, so that an untrusted length
+        // cannot cause a large allocation
+        let num_contributions = u64::deserialize(&mut reader)?;
+        let mut contributions = MerkleTree::new();
+        for _ in 0..num_contributions {
+            let mut digest = [0u8; 32];
+            reader.read_exact(&mut digest)?;
+            contributions.push(digest);
+        }
+
+        Ok(Transcript {
+            // Exactly one SRS was read for each ceremony
+            transcripts: transcripts.try_into().unwrap(),
+            contributions,
+        })
+    }
+}
+
+fn to_usize(len: u64) -> Result<usize, SerializationError> {
+    usize::try_from(len).map_err(|_| SerializationError::InvalidData)
+}
+
+fn to_ark_error(err: Error) -> SerializationError {
+    match err {
+        Error::Serialisation(SerialisationError::Io(err)) => SerializationError::IoError(err),
+        _ => SerializationError::InvalidData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::PrivateKey;
+    use ark_bls12_381::G1Affine;
+
+    #[test]
+    fn canonical_roundtrip() {
+        let mut srs = SRS::new(Parameters::new(10, 3)).unwrap();
+        let update_proof = srs.update(PrivateKey::from_u64(252));
+
+        let mut bytes = Vec::new();
+        srs.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), srs.serialized_size());
+        assert_eq!(&bytes[16..], srs.serialise_bytes().as_slice());
+        assert_eq!(SRS::deserialize(bytes.as_slice()).unwrap(), srs);
+
+        let mut bytes = Vec::new();
+        update_proof.serialize(&mut bytes).unwrap();
+        assert_eq!(
+            UpdateProof::deserialize(bytes.as_slice()).unwrap(),
+            update_proof
+        );
+
+        // A truncated SRS is an io error, while a point which is not in the
+        // prime order subgroup is invalid data
+        let mut bytes = Vec::new();
+        srs.serialize(&mut bytes).unwrap();
+        assert!(matches!(
+            SRS::deserialize(&bytes[..100]),
+            Err(SerializationError::IoError(_))
+        ));
+
+        bytes[16 + G1_SERIALISED_SIZE..16 + 2 * G1_SERIALISED_SIZE]
+            .copy_from_slice(&not_in_subgroup_g1());
+        assert!(matches!(
+            SRS::deserialize(bytes.as_slice()),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+
+    // The compressed encoding of a point on the G1 curve which is not in the
+    // prime order subgroup
+    fn not_in_subgroup_g1() -> [u8; G1_SERIALISED_SIZE] {
+        use crate::interop_point_encoding::serialize_g1;
+        use ark_bls12_381::Fq;
+        use ark_ff::One;
+
+        let mut x = Fq::one();
+        loop {
+            if let Some(point) = G1Affine::get_point_from_x(x, false) {
+                if !point.is_in_correct_subgroup_assuming_on_curve() {
+                    return serialize_g1(&point);
+                }
+            }
+            x += Fq::one();
+        }
+    }
+}
@@ -0,0 +1,227 @@
+// Files for contributing from a machine which is never connected to a network.
+//
+// The coordinator writes a challenge file, which holds the current SRS and a
+// digest of the ceremony so far. The file is carried to the offline machine,
+// which updates the SRS and writes a response file holding the updated SRS,
+// the update proof and the hash of the challenge it responds to. The response
+// is carried back, checked against the challenge and becomes the next
+// challenge, whose context is the hash of the response. The hashes of the
+// challenges and responses therefore form a chain over the whole ceremony.
+//
+// Both files start with magic bytes, which include the version of the layout,
+// and the number of G1 and G2 powers as little endian u64s. The SRS is
+// encoded as in `SRS::write_to`.
+use crate::{
+    error::{Error, SerialisationError, VerificationError},
+    keypair::PrivateKey,
+    serialisation::{SubgroupCheck, UPDATE_PROOF_SERIALISED_SIZE},
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+use ark_bls12_381::Fr;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+
+const CHALLENGE_MAGIC: [u8; 8] = *b"SPOTCHL\x01";
+const RESPONSE_MAGIC: [u8; 8] = *b"SPOTRSP\x01";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    // A digest of the ceremony before this challenge, for example the hash of
+    // the previous response
+    pub context: [u8; 32],
+    pub srs: SRS,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    // The hash of the challenge that this is a response to
+    pub challenge_hash: [u8; 32],
+    pub update_proof: UpdateProof,
+    pub srs: SRS,
+}
+
+impl Challenge {
+    pub fn new(srs: SRS, context: [u8; 32]) -> Self {
+        Challenge { context, srs }
+    }
+
+    // The sha256 hash of the challenge file
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        self.write_to(&mut hasher)
+            .expect("writing to a hasher cannot fail");
+        hasher.finalize().into()
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&CHALLENGE_MAGIC)?;
+        write_parameters(writer, &self.srs)?;
+        writer.write_all(&self.context)?;
+        self.srs.write_to(writer)
+    }
+
+    pub fn read_from<R: Read>(
+        reader: &mut R,
+        subgroup_check: SubgroupCheck,
+    ) -> Result<Self, Error> {
+        read_magic(reader, CHALLENGE_MAGIC)?;
+        let parameters = read_parameters(reader)?;
+        let mut context = [0u8; 32];
+        reader
+            .read_exact(&mut context)
+            .map_err(SerialisationError::Io)?;
+        let srs = SRS::read_from(reader, parameters, subgroup_check)?;
+
+        Ok(Challenge { context, srs })
+    }
+
+    // Updates the SRS in the challenge, which is done on the offline machine
+    pub fn respond(&self, private_key: PrivateKey) -> Response {
+        let mut srs = self.srs.clone();
+        let update_proof = srs.update(private_key);
+
+        Response {
+            challenge_hash: self.hash(),
+            update_proof,
+            srs,
+        }
+    }
+}
+
+impl Response {
+    // The sha256 hash of the response file
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        self.write_to(&mut hasher)
+            .expect("writing to a hasher cannot fail");
+        hasher.finalize().into()
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&RESPONSE_MAGIC)?;
+        write_parameters(writer, &self.srs)?;
+        writer.write_all(&self.challenge_hash)?;
+        writer.write_all(&self.update_proof.serialise_bytes())?;
+        self.srs.write_to(writer)
+    }
+
+    // Reads a response. The SRS should be subgroup checked, unless this is
+    // done separately, since the response comes from the contributor
+    pub fn read_from<R: Read>(
+        reader: &mut R,
+        subgroup_check: SubgroupCheck,
+    ) -> Result<Self, Error> {
+        read_magic(reader, RESPONSE_MAGIC)?;
+        let parameters = read_parameters(reader)?;
+        let mut challenge_hash = [0u8; 32];
+        reader
+            .read_exact(&mut challenge_hash)
+            .map_err(SerialisationError::Io)?;
+        let mut update_proof = [0u8; UPDATE_PROOF_SERIALISED_SIZE];
+        reader
+            .read_exact(&mut update_proof)
+            .map_err(SerialisationError::Io)?;
+        let update_proof = UpdateProof::deserialise_bytes(&update_proof)?;
+        let srs = SRS::read_from(reader, parameters, subgroup_check)?;
+
+        Ok(Response {
+            challenge_hash,
+            update_proof,
+            srs,
+        })
+    }
+
+    // Checks that this is a response to `challenge`, and that the SRS in the
+    // challenge was correctly updated
+    pub fn verify(
+        &self,
+        challenge: &Challenge,
+        random_element: Fr,
+    ) -> Result<(), VerificationError> {
+        if self.challenge_hash != challenge.hash() {
+            return Err(VerificationError::ChallengeMismatch);
+        }
+        SRS::verify_update(
+            &challenge.srs,
+            &self.srs,
+            &self.update_proof,
+            random_element,
+        )
+    }
+
+    // The challenge for the next contributor, once this response has been verified
+    pub fn next_challenge(&self) -> Challenge {
+        Challenge::new(self.srs.clone(), self.hash())
+    }
+}
+
+fn write_parameters<W: Write>(writer: &mut W, srs: &SRS) -> std::io::Result<()> {
+    writer.write_all(&(srs.g1_elements().len() as u64).to_le_bytes())?;
+    writer.write_all(&(srs.g2_elements().len() as u64).to_le_bytes())
+}
+
+fn read_parameters<R: Read>(reader: &mut R) -> Result<Parameters, SerialisationError> {
+    let mut read_u64 = || -> Result<usize, SerialisationError> {
+        let mut bytes = [0u8; 8];
+        reader.read_exact(&mut bytes)?;
+        usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| SerialisationError::InvalidHeader)
+    };
+    let num_g1 = read_u64()?;
+    let num_g2 = read_u64()?;
+    Ok(Parameters::new(num_g1, num_g2))
+}
+
+fn read_magic<R: Read>(reader: &mut R, magic: [u8; 8]) -> Result<(), SerialisationError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    if bytes != magic {
+        return Err(SerialisationError::InvalidHeader);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_response_roundtrip() {
+        let parameters = Parameters::new(32, 4);
+        let challenge = Challenge::new(SRS::new(parameters).unwrap(), [7u8; 32]);
+
+        // The challenge is carried to the offline machine
+        let mut challenge_file = Vec::new();
+        challenge.write_to(&mut challenge_file).unwrap();
+        let offline_challenge =
+            Challenge::read_from(&mut challenge_file.as_slice(), SubgroupCheck::Full).unwrap();
+        assert_eq!(offline_challenge, challenge);
+
+        // The response is carried back
+        let response = offline_challenge.respond(PrivateKey::from_u64(252));
+        let mut response_file = Vec::new();
+        response.write_to(&mut response_file).unwrap();
+        let response =
+            Response::read_from(&mut response_file.as_slice(), SubgroupCheck::Full).unwrap();
+
+        assert!(response.verify(&challenge, Fr::from(123456789u64)).is_ok());
+        assert_eq!(response.hash(), Sha256::digest(&response_file).as_slice());
+
+        let next_challenge = response.next_challenge();
+        assert_eq!(next_challenge.context, response.hash());
+        assert_eq!(next_challenge.srs, response.srs);
+
+        // A response to a different challenge is rejected
+        let other_challenge = Challenge::new(challenge.srs.clone(), [8u8; 32]);
+        assert_eq!(
+            response.verify(&other_challenge, Fr::from(123456789u64)),
+            Err(VerificationError::ChallengeMismatch)
+        );
+
+        // A challenge is not mistaken for a response
+        assert!(matches!(
+            Response::read_from(&mut challenge_file.as_slice(), SubgroupCheck::Full),
+            Err(Error::Serialisation(SerialisationError::InvalidHeader))
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+// Loading the setup of a ceremony from a configuration file, usually called
+// `ceremony.toml`, so that it can be kept under version control. See the
+// `ceremony.toml` at the root of this repository for an example.
+//
+// Unknown top-level keys and tables are ignored, so that tools which run a
+// ceremony can keep their own settings in the same file.
+use crate::{
+    error::{ConfigError, Error, SerialisationError},
+    serialisation::SubgroupCheck,
+    srs::{Parameters, SRS},
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// The curves which an SRS can be created on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Curve {
+    #[serde(rename = "bls12-381")]
+    Bls12_381,
+}
+
+// The formats that an SRS can be written out in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    // The hex encoded points, see `SRSJson`
+    Json,
+    // The compressed points, see `SRS::write_to`
+    Binary,
+}
+
+// The number of powers in one of the ceremonies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CeremonySize {
+    pub num_g1_elements: usize,
+    pub num_g2_elements: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CeremonyConfig {
+    pub curve: Curve,
+    #[serde(default = "default_subgroup_check")]
+    pub subgroup_check: SubgroupCheck,
+    #[serde(default = "default_output_formats")]
+    pub output_formats: Vec<OutputFormat>,
+    // The ceremonies which are contributed to together
+    pub ceremonies: Vec<CeremonySize>,
+}
+
+fn default_subgroup_check() -> SubgroupCheck {
+    SubgroupCheck::Full
+}
+fn default_output_formats() -> Vec<OutputFormat> {
+    vec![OutputFormat::Json]
+}
+
+impl CeremonyConfig {
+    // Parses a configuration, checking that every ceremony has valid parameters
+    pub fn from_toml(toml_str: &str) -> Result<Self, ConfigError> {
+        let config: CeremonyConfig = toml::from_str(toml_str)?;
+
+        if config.ceremonies.is_empty() {
+            return Err(ConfigError::NoCeremonies);
+        }
+        config.build_parameters()?;
+
+        Ok(config)
+    }
+
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let toml_str = std::fs::read_to_string(path).map_err(SerialisationError::Io)?;
+        Ok(CeremonyConfig::from_toml(&toml_str)?)
+    }
+
+    // The parameters of each ceremony, in the order they appear in the configuration
+    pub fn parameters(&self) -> Vec<Parameters> {
+        self.build_parameters()
+            .expect("the parameters are checked when the configuration is loaded")
+    }
+
+    // Creates the starting SRS of each ceremony
+    pub fn new_srs(&self) -> Vec<SRS> {
+        self.parameters()
+            .into_iter()
+            .map(|parameters| SRS::new(parameters).expect("the parameters have been checked"))
+            .collect()
+    }
+
+    fn build_parameters(&self) -> Result<Vec<Parameters>, ConfigError> {
+        self.ceremonies
+            .iter()
+            .enumerate()
+            .map(|(index, size)| {
+                Parameters::builder()
+                    .custom(size.num_g1_elements, size.num_g2_elements)
+                    .build()
+                    .map_err(|error| ConfigError::InvalidParameters { index, error })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::ParameterError, sdk::CEREMONIES};
+
+    #[test]
+    fn load_example_config() {
+        let config = CeremonyConfig::from_toml(include_str!("../ceremony.toml")).unwrap();
+
+        assert_eq!(config.curve, Curve::Bls12_381);
+        assert_eq!(config.subgroup_check, SubgroupCheck::Full);
+        assert_eq!(
+            config.output_formats,
+            vec![OutputFormat::Json, OutputFormat::Binary]
+        );
+        assert_eq!(config.parameters(), CEREMONIES.to_vec());
+    }
+
+    #[test]
+    fn reject_invalid_config() {
+        // Settings for other tools are ignored, and the defaults are filled in
+        let config = CeremonyConfig::from_toml(
+            r#"
+            curve = "bls12-381"
+
+            [sequencer]
+            port = 8080
+
+            [[ceremonies]]
+            num_g1_elements = 16
+            num_g2_elements = 2
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.subgroup_check, SubgroupCheck::Full);
+        assert_eq!(config.new_srs().len(), 1);
+
+        let unknown_curve = r#"
+            curve = "bn254"
+
+            [[ceremonies]]
+            num_g1_elements = 16
+            num_g2_elements = 2
+            "#;
+        assert!(matches!(
+            CeremonyConfig::from_toml(unknown_curve),
+            Err(ConfigError::Toml(_))
+        ));
+
+        let no_ceremonies = r#"
+            curve = "bls12-381"
+            ceremonies = []
+            "#;
+        assert!(matches!(
+            CeremonyConfig::from_toml(no_ceremonies),
+            Err(ConfigError::NoCeremonies)
+        ));
+
+        let too_few_elements = r#"
+            curve = "bls12-381"
+
+            [[ceremonies]]
+            num_g1_elements = 16
+            num_g2_elements = 2
+
+            [[ceremonies]]
+            num_g1_elements = 16
+            num_g2_elements = 1
+            "#;
+        assert!(matches!(
+            CeremonyConfig::from_toml(too_few_elements),
+            Err(ConfigError::InvalidParameters {
+                index: 1,
+                error: ParameterError::TooFewElements
+            })
+        ));
+    }
+}
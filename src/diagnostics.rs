@@ -0,0 +1,160 @@
+// Detecting SRS shapes which no honest ceremony produces, to quickly triage a
+// corrupted or maliciously crafted file.
+//
+// These are not cryptographic checks, and an SRS without anomalies may still
+// fail verification. They are cheap, only comparing points, and describe what
+// is wrong with the file where verification would only reject it. The
+// exception is an SRS which no one has contributed to, where every point is
+// the generator, see `SRS::new`.
+use crate::srs::SRS;
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_ec::AffineCurve;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Group {
+    G1,
+    G2,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Anomaly {
+    // Every point of the group is the same
+    AllEqual {
+        group: Group,
+    },
+    // The points after the degree-0 power which are the generator. Each of
+    // these reveals a power of tau which is one
+    GeneratorPowers {
+        group: Group,
+        indices: Vec<usize>,
+    },
+    // `length` consecutive points starting at `start` are the same
+    RepeatedRun {
+        group: Group,
+        start: usize,
+        length: usize,
+    },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Anomaly::AllEqual { group } => write!(f, "every {:?} element is the same", group),
+            Anomaly::GeneratorPowers { group, indices } => write!(
+                f,
+                "{} {:?} elements after the first are the generator, starting at {}",
+                indices.len(),
+                group,
+                indices[0]
+            ),
+            Anomaly::RepeatedRun {
+                group,
+                start,
+                length,
+            } => write!(
+                f,
+                "{:?} elements {}..{} are the same",
+                group,
+                start,
+                start + length
+            ),
+        }
+    }
+}
+
+// Returns the anomalies of `srs`, G1 first. An empty list means that none were found
+pub fn diagnose(srs: &SRS) -> Vec<Anomaly> {
+    let mut anomalies = anomalies_of(
+        Group::G1,
+        srs.g1_elements(),
+        G1Affine::prime_subgroup_generator(),
+    );
+    anomalies.extend(anomalies_of(
+        Group::G2,
+        srs.g2_elements(),
+        G2Affine::prime_subgroup_generator(),
+    ));
+    anomalies
+}
+
+fn anomalies_of<P: PartialEq>(group: Group, points: &[P], generator: P) -> Vec<Anomaly> {
+    if points.len() < 2 {
+        return Vec::new();
+    }
+    // The other anomalies would only repeat this one
+    if points.iter().all(|point| *point == points[0]) {
+        return vec![Anomaly::AllEqual { group }];
+    }
+
+    let mut anomalies = Vec::new();
+
+    let indices: Vec<_> = (1..points.len())
+        .filter(|&i| points[i] == generator)
+        .collect();
+    if !indices.is_empty() {
+        anomalies.push(Anomaly::GeneratorPowers { group, indices });
+    }
+
+    let mut start = 0;
+    for end in 1..=points.len() {
+        if end == points.len() || points[end] != points[start] {
+            if end - start > 1 {
+                anomalies.push(Anomaly::RepeatedRun {
+                    group,
+                    start,
+                    length: end - start,
+                });
+            }
+            start = end;
+        }
+    }
+
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keypair::PrivateKey, srs::Parameters};
+
+    #[test]
+    fn detects_anomalies() {
+        let mut srs = SRS::new(Parameters::new(8, 4)).unwrap();
+        assert_eq!(
+            diagnose(&srs),
+            vec![
+                Anomaly::AllEqual { group: Group::G1 },
+                Anomaly::AllEqual { group: Group::G2 }
+            ]
+        );
+
+        srs.update(PrivateKey::from_u64(3));
+        assert!(diagnose(&srs).is_empty());
+
+        // Repeat the degree-2 G1 power, and replace the degree-5 power with
+        // the generator
+        let mut g1 = srs.g1_powers().to_vec();
+        g1[3] = g1[2];
+        g1[4] = g1[2];
+        g1[5] = G1Affine::prime_subgroup_generator();
+        let srs = SRS::from_vectors(g1, srs.g2_powers().to_vec()).unwrap();
+        let anomalies = diagnose(&srs);
+        assert_eq!(
+            anomalies,
+            vec![
+                Anomaly::GeneratorPowers {
+                    group: Group::G1,
+                    indices: vec![5]
+                },
+                Anomaly::RepeatedRun {
+                    group: Group::G1,
+                    start: 2,
+                    length: 3
+                }
+            ]
+        );
+        assert_eq!(anomalies[1].to_string(), "G1 elements 2..5 are the same");
+    }
+}
@@ -0,0 +1,140 @@
+// Finding where two serialised SRS differ, for example when two copies of
+// what should be the same SRS do not have the same digest.
+//
+// Both SRS are streamed a point at a time, so that files which are too large
+// to load can be compared. The points are not decoded, so files which contain
+// invalid points can also be compared. Readers should be buffered, for example
+// using `std::io::BufReader`.
+use crate::{
+    error::{Error, SerialisationError},
+    interop_point_encoding::{G1_SERIALISED_SIZE, G2_SERIALISED_SIZE},
+    serialisation::check_bounds,
+    srs::Parameters,
+};
+use std::fmt;
+use std::io::Read;
+
+// The first point where two SRS differ, along with the 0x prefixed hex
+// encoding of the point in each SRS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    G1 {
+        index: usize,
+        left: String,
+        right: String,
+    },
+    G2 {
+        index: usize,
+        left: String,
+        right: String,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (group, index, left, right) = match self {
+            Difference::G1 { index, left, right } => ("G1", index, left, right),
+            Difference::G2 { index, left, right } => ("G2", index, left, right),
+        };
+        write!(
+            f,
+            "{} element {} differs: {} != {}",
+            group, index, left, right
+        )
+    }
+}
+
+// Compares two SRS which were serialised using `SRS::write_to` with the same
+// parameters, returning the first point where they differ
+pub fn first_difference<L: Read, R: Read>(
+    left: &mut L,
+    right: &mut R,
+    parameters: Parameters,
+) -> Result<Option<Difference>, Error> {
+    check_bounds(parameters)?;
+
+    let mut left_point = [0u8; G1_SERIALISED_SIZE];
+    let mut right_point = [0u8; G1_SERIALISED_SIZE];
+    for index in 0..parameters.num_g1_elements() {
+        read_points(left, right, &mut left_point, &mut right_point)?;
+        if left_point != right_point {
+            return Ok(Some(Difference::G1 {
+                index,
+                left: to_hex(&left_point),
+                right: to_hex(&right_point),
+            }));
+        }
+    }
+
+    let mut left_point = [0u8; G2_SERIALISED_SIZE];
+    let mut right_point = [0u8; G2_SERIALISED_SIZE];
+    for index in 0..parameters.num_g2_elements() {
+        read_points(left, right, &mut left_point, &mut right_point)?;
+        if left_point != right_point {
+            return Ok(Some(Difference::G2 {
+                index,
+                left: to_hex(&left_point),
+                right: to_hex(&right_point),
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+fn read_points<L: Read, R: Read>(
+    left: &mut L,
+    right: &mut R,
+    left_point: &mut [u8],
+    right_point: &mut [u8],
+) -> Result<(), SerialisationError> {
+    left.read_exact(left_point)?;
+    right.read_exact(right_point)?;
+    Ok(())
+}
+
+fn to_hex(point: &[u8]) -> String {
+    let mut point_as_hex = hex::encode(point);
+    point_as_hex.insert_str(0, "0x");
+    point_as_hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keypair::PrivateKey, srs::SRS};
+
+    #[test]
+    fn first_difference_of_two_srs() {
+        let parameters = Parameters::new(20, 5);
+        let mut srs = SRS::new(parameters).unwrap();
+        srs.update(PrivateKey::from_u64(252));
+
+        let left = srs.serialise_bytes();
+        assert_eq!(
+            first_difference(&mut left.as_slice(), &mut left.as_slice(), parameters).unwrap(),
+            None
+        );
+
+        // Corrupt the degree-3 G2 element
+        let mut right = left.clone();
+        let offset = 20 * G1_SERIALISED_SIZE + 3 * G2_SERIALISED_SIZE;
+        right[offset + 10] ^= 1;
+
+        let difference = first_difference(&mut left.as_slice(), &mut right.as_slice(), parameters)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            difference,
+            Difference::G2 {
+                index: 3,
+                left: to_hex(&left[offset..offset + G2_SERIALISED_SIZE]),
+                right: to_hex(&right[offset..offset + G2_SERIALISED_SIZE]),
+            }
+        );
+        assert!(difference.to_string().starts_with("G2 element 3 differs"));
+
+        // A truncated SRS is an error, rather than a difference
+        assert!(first_difference(&mut left.as_slice(), &mut &right[..100], parameters).is_err());
+    }
+}
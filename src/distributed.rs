@@ -0,0 +1,223 @@
+// Splits the verification of a ceremony into tasks which can be run on
+// different machines, and merges their results.
+//
+// The update proofs are split by contribution index, where each task checks
+// a contiguous part of the chain starting from the point before it. The final
+// SRS is split by power, see `SRS::split`. The checks which only involve the
+// degree-1 elements are cheap, so they are done when the plan is created.
+use crate::{error::VerificationError, shard::SRSShard, srs::SRS, update_proof::UpdateProof};
+use ark_bls12_381::{Fr, G1Projective};
+use ark_ec::AffineCurve;
+use ark_ff::Zero;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub enum VerificationTask {
+    // Checks the update proofs in `range`, where `starting_point` is the degree-1
+    // element of the SRS before the first of these updates
+    UpdateProofs {
+        range: Range<usize>,
+        starting_point: G1Projective,
+        update_proofs: Vec<UpdateProof>,
+    },
+    // Checks the structure of a range of powers of the final SRS, and that
+    // they are in the prime order subgroup
+    Powers(Box<SRSShard>),
+}
+
+impl VerificationTask {
+    pub fn run(&self, random_element: Fr) -> Result<(), VerificationError> {
+        match self {
+            VerificationTask::UpdateProofs {
+                starting_point,
+                update_proofs,
+                ..
+            } => {
+                if UpdateProof::first_no_op(*starting_point, update_proofs).is_some() {
+                    return Err(VerificationError::NoOpUpdate);
+                }
+                if !UpdateProof::verify_chain(*starting_point, update_proofs) {
+                    return Err(VerificationError::InvalidUpdateChain);
+                }
+                Ok(())
+            }
+            VerificationTask::Powers(shard) => {
+                shard.subgroup_check()?;
+                shard.verify(random_element)
+            }
+        }
+    }
+}
+
+// The result of running the task with the given index in `VerificationPlan::tasks`.
+//
+// Results can be saved as they arrive, so that a verification which is
+// interrupted only needs to run the remaining tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialResult {
+    pub task: usize,
+    pub result: Result<(), VerificationError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VerificationPlan {
+    tasks: Vec<VerificationTask>,
+}
+
+impl VerificationPlan {
+    // Plans the same checks as `SRS::verify_updates`, along with a subgroup check
+    // of the final SRS. Each task checks at most `proofs_per_task` update proofs
+    // or `powers_per_task` powers
+    pub fn new(
+        before: &SRS,
+        after: &SRS,
+        update_proofs: &[UpdateProof],
+        proofs_per_task: usize,
+        powers_per_task: usize,
+    ) -> Result<Self, VerificationError> {
+        let last_update = update_proofs
+            .last()
+            .ok_or(VerificationError::NoUpdateProofs)?;
+        if after.g1_elements()[1].into_projective() != last_update.new_accumulated_point {
+            return Err(VerificationError::UpdateDoesNotEndAtSRS);
+        }
+        if after.g1_elements()[1].is_zero() || after.g2_elements()[1].is_zero() {
+            return Err(VerificationError::IdentityElement);
+        }
+
+        let proofs_per_task = std::cmp::max(proofs_per_task, 1);
+        let mut tasks = Vec::new();
+
+        for start in (0..update_proofs.len()).step_by(proofs_per_task) {
+            let end = std::cmp::min(start + proofs_per_task, update_proofs.len());
+            let starting_point = match start {
+                0 => before.g1_elements()[1].into_projective(),
+                _ => update_proofs[start - 1].new_accumulated_point,
+            };
+            tasks.push(VerificationTask::UpdateProofs {
+                range: start..end,
+                starting_point,
+                update_proofs: update_proofs[start..end].to_vec(),
+            });
+        }
+        tasks.extend(
+            after
+                .split(powers_per_task)
+                .into_iter()
+                .map(|shard| VerificationTask::Powers(Box::new(shard))),
+        );
+
+        Ok(VerificationPlan { tasks })
+    }
+
+    pub fn tasks(&self) -> &[VerificationTask] {
+        &self.tasks
+    }
+
+    // The indices of the tasks which do not have a result yet
+    pub fn remaining_tasks(&self, results: &[PartialResult]) -> Vec<usize> {
+        let mut has_result = vec![false; self.tasks.len()];
+        for partial_result in results {
+            if let Some(has_result) = has_result.get_mut(partial_result.task) {
+                *has_result = true;
+            }
+        }
+
+        (0..self.tasks.len())
+            .filter(|task| !has_result[*task])
+            .collect()
+    }
+
+    // Merges the results of every task, returning the first error in task order.
+    //
+    // There must be exactly one result for each task, so that a task which was
+    // lost or run twice is not mistaken for a successful verification
+    pub fn merge(&self, results: &[PartialResult]) -> Result<(), VerificationError> {
+        let mut task_results = vec![None; self.tasks.len()];
+
+        for partial_result in results {
+            match task_results.get_mut(partial_result.task) {
+                Some(task_result) if task_result.is_none() => {
+                    *task_result = Some(partial_result.result)
+                }
+                _ => return Err(VerificationError::IncompleteVerification),
+            }
+        }
+
+        for task_result in task_results {
+            task_result.ok_or(VerificationError::IncompleteVerification)??;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        simulate::{Fault, Simulation},
+        srs::Parameters,
+    };
+
+    fn run_all(plan: &VerificationPlan) -> Vec<PartialResult> {
+        plan.tasks()
+            .iter()
+            .enumerate()
+            .map(|(task, verification_task)| PartialResult {
+                task,
+                result: verification_task.run(Fr::from(123456789u64)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn distributed_verification() {
+        let params = Parameters::new(64, 8);
+
+        let transcript = Simulation::new(params, 10).run().unwrap();
+        let plan = VerificationPlan::new(
+            &transcript.starting_srs,
+            &transcript.final_srs,
+            &transcript.update_proofs,
+            3,
+            16,
+        )
+        .unwrap();
+        // 4 tasks for the update proofs and 4 for the powers
+        assert_eq!(plan.tasks().len(), 8);
+
+        let mut results = run_all(&plan);
+        assert_eq!(plan.merge(&results), Ok(()));
+
+        // Every task must have exactly one result
+        let last_result = results.pop().unwrap();
+        assert_eq!(
+            plan.merge(&results),
+            Err(VerificationError::IncompleteVerification)
+        );
+        assert_eq!(plan.remaining_tasks(&results), vec![7]);
+        results.push(last_result);
+        results.push(last_result);
+        assert_eq!(
+            plan.merge(&results),
+            Err(VerificationError::IncompleteVerification)
+        );
+
+        for (fault, expected_error) in [
+            (Fault::BadProof, VerificationError::InvalidUpdateChain),
+            (Fault::WrongPoint, VerificationError::InvalidStructure),
+        ] {
+            let transcript = Simulation::new(params, 10).fault(9, fault).run().unwrap();
+            let plan = VerificationPlan::new(
+                &transcript.starting_srs,
+                &transcript.final_srs,
+                &transcript.update_proofs,
+                3,
+                16,
+            )
+            .unwrap();
+            assert_eq!(plan.merge(&run_all(&plan)), Err(expected_error));
+        }
+    }
+}
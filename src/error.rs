@@ -0,0 +1,266 @@
+// Errors returned by the public API.
+//
+// Each module returns the most specific error that applies, all of which can
+// be converted into `Error` using `?`.
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Serialisation(#[from] SerialisationError),
+    #[error(transparent)]
+    Verification(#[from] VerificationError),
+    #[error(transparent)]
+    Parameter(#[from] ParameterError),
+    #[error(transparent)]
+    Ceremony(#[from] CeremonyError),
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+    #[error(transparent)]
+    DegenerateSecret(#[from] DegenerateSecret),
+    #[error(transparent)]
+    EntropyUnavailable(#[from] EntropyUnavailable),
+    #[error(transparent)]
+    Precheck(#[from] PrecheckError),
+    #[error(transparent)]
+    Dice(#[from] DiceError),
+    #[error(transparent)]
+    Timestamp(#[from] TimestampError),
+    #[error(transparent)]
+    Kzg(#[from] KzgError),
+    #[cfg(feature = "config")]
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+#[derive(Debug, Error)]
+pub enum SerialisationError {
+    #[error("hex string is missing the 0x prefix")]
+    MissingHexPrefix,
+    #[error("invalid hex string")]
+    InvalidHex(#[from] hex::FromHexError),
+    #[error("invalid base64 string")]
+    InvalidBase64(#[from] base64::DecodeError),
+    #[error("expected {expected} bytes, but got {got}")]
+    InvalidLength { expected: usize, got: usize },
+    #[error("bytes do not encode a compressed point on the curve: {0}")]
+    InvalidPoint(#[from] PointEncodingError),
+    #[error("the file does not have a valid header")]
+    InvalidHeader,
+    #[error("the file is not an SRS written by an earlier version of this crate")]
+    UnrecognisedLegacyFile,
+    #[error("a chunk of the accumulator does not match its checksum")]
+    ChecksumMismatch,
+    #[error("the frame belongs to a different file")]
+    FrameMismatch,
+    #[error("not enough frames have been received to recover the file")]
+    MissingFrames,
+    #[error("bytes do not encode a valid secp256k1 key or signature")]
+    InvalidSecp256k1,
+    #[error("the identity is not a valid ethereum address or github handle")]
+    InvalidIdentity,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+// The reason that bytes could not be decoded as a point, see `interop_point_encoding`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum PointEncodingError {
+    #[error("the compression flag is not set")]
+    Uncompressed,
+    #[error("the point at infinity is not encoded canonically")]
+    NonCanonicalInfinity,
+    #[error("the x-coordinate is not a canonical field element")]
+    InvalidFieldElement,
+    #[error("there is no point on the curve with this x-coordinate")]
+    NotOnCurve,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum VerificationError {
+    #[error("no update proofs were supplied")]
+    NoUpdateProofs,
+    #[error("the last update proof does not end at the new SRS")]
+    UpdateDoesNotEndAtSRS,
+    #[error("the update proofs do not form a valid chain")]
+    InvalidUpdateChain,
+    #[error("an update proof does not change the SRS")]
+    NoOpUpdate,
+    #[error("the degree-1 elements of the new SRS are the identity")]
+    IdentityElement,
+    #[error("the new SRS does not have the correct structure")]
+    InvalidStructure,
+    #[error("the SRS does not have the parameters of its ceremony: {0}")]
+    InvalidParameters(ParameterError),
+    #[error("a point is not in the prime order subgroup")]
+    NotInSubgroup,
+    #[error("invalid BLS signature")]
+    InvalidSignature,
+    #[error("the shards do not join up to form an SRS")]
+    InvalidShards,
+    #[error("not every verification task has exactly one result")]
+    IncompleteVerification,
+    #[error("the SRS or private key does not match the checkpoint")]
+    CheckpointMismatch,
+    #[error("the response is not for this challenge")]
+    ChallengeMismatch,
+    #[error("replaying the contribution does not give the recorded result")]
+    ReplayMismatch,
+    #[error("the contribution digests in the transcript do not match the update proofs")]
+    ContributionDigestMismatch,
+    #[error("an entry of the audit log does not follow on from the one before it")]
+    InvalidAuditLog,
+    #[error("the update proofs of a chain do not all use the same proof mode")]
+    MixedProofModes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum ParameterError {
+    #[error("the number of G1 and G2 elements must not be zero")]
+    ZeroElements,
+    #[error("an SRS needs at least two G1 and two G2 elements")]
+    TooFewElements,
+    #[error("at most {max} G1 or G2 elements are allowed, but got {got}")]
+    TooManyElements { max: usize, got: usize },
+    #[error("expected {expected} G1 elements, but got {got}")]
+    WrongNumberOfG1Elements { expected: usize, got: usize },
+    #[error("expected {expected} G2 elements, but got {got}")]
+    WrongNumberOfG2Elements { expected: usize, got: usize },
+    #[error(
+        "the degree bound {degree_bound} must be between one and the maximum degree {max_degree}"
+    )]
+    InvalidDegreeBound {
+        degree_bound: usize,
+        max_degree: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum CeremonyError {
+    #[error("the SRS for ceremony {0} does not have the expected parameters")]
+    UnexpectedParameters(usize),
+    #[error("expected {expected} contributions, but got {got}")]
+    WrongNumberOfContributions { expected: usize, got: usize },
+    #[error("the contribution of participant {participant} does not verify: {error}")]
+    InvalidContribution {
+        participant: usize,
+        error: VerificationError,
+    },
+}
+
+// Returned by `precheck_contribution`, naming the SRS and the power which failed.
+// The SRS are numbered from zero and the groups are 1 or 2
+#[derive(Debug, Error)]
+pub enum PrecheckError {
+    #[error("SRS {ceremony} has {got} G{group} powers, but the ceremony needs {expected}")]
+    WrongNumberOfPowers {
+        ceremony: usize,
+        group: u8,
+        expected: usize,
+        got: usize,
+    },
+    #[error("G{group} power {index} of SRS {ceremony} is not a valid point: {error}")]
+    InvalidPoint {
+        ceremony: usize,
+        group: u8,
+        index: usize,
+        error: SerialisationError,
+    },
+    #[error("the degree-0 G{group} power of SRS {ceremony} is not the generator")]
+    NotGenerator { ceremony: usize, group: u8 },
+    #[error("the degree-1 powers of SRS {ceremony} are the identity or were created using different secrets")]
+    InvalidDegreeOne { ceremony: usize },
+    #[error("G{group} power {index} of SRS {ceremony} is not in the prime order subgroup")]
+    NotInSubgroup {
+        ceremony: usize,
+        group: u8,
+        index: usize,
+    },
+}
+
+// Returned when a long running operation is stopped by its caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("the operation was cancelled")]
+pub struct Cancelled;
+
+// Returned when a private key would be zero or one. Updating an SRS using zero
+// destroys it, and updating it using one leaves it unchanged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("the secret must not be zero or one")]
+pub struct DegenerateSecret;
+
+// Returned when the operating system, or in the browser `crypto.getRandomValues`,
+// cannot provide secure randomness
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("a secure source of randomness is not available: {0}")]
+pub struct EntropyUnavailable(pub String);
+
+// Returned when dice rolls or diceware words cannot be used to derive a
+// private key, see `PrivateKey::from_dice_rolls`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DiceError {
+    #[error("'{0}' is not the face of a six sided die")]
+    InvalidRoll(char),
+    #[error("'{0}' is not in the word list")]
+    UnknownWord(String),
+    #[error("the word list is invalid: {0}")]
+    InvalidWordList(String),
+    #[error("the input has {bits} bits of entropy, but at least {needed} are needed")]
+    NotEnoughEntropy { bits: u32, needed: u32 },
+}
+
+// Returned when an OpenTimestamps proof cannot be read, or does not prove that
+// the digest existed, see `timestamp`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TimestampError {
+    #[error("the file is not an OpenTimestamps proof")]
+    BadMagic,
+    #[error("unsupported OpenTimestamps proof version {0}")]
+    UnsupportedVersion(u64),
+    #[error("the proof ended unexpectedly")]
+    UnexpectedEnd,
+    #[error("the proof has trailing bytes")]
+    TrailingBytes,
+    #[error("unsupported operation 0x{0:02x}")]
+    UnsupportedOperation(u8),
+    #[error("the proof is nested too deeply or a message is too long")]
+    TooLarge,
+    #[error("an attestation in the proof is malformed")]
+    InvalidAttestation,
+    #[error("a calendar response is needed to create a proof")]
+    NoCalendarResponses,
+    #[error("the proof is for a different digest")]
+    DigestMismatch,
+    #[error("no message in the proof matches the commitment")]
+    UnknownCommitment,
+    #[error("the attestation does not match the merkle root of Bitcoin block {height}")]
+    BitcoinMismatch { height: u64 },
+    #[error("the proof has no Bitcoin attestation which could be checked")]
+    NotAttested,
+}
+
+// Returned when a polynomial cannot be committed to or opened using an SRS, see `kzg`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum KzgError {
+    #[error("the SRS supports polynomials with at most {max} coefficients, but got {got}")]
+    TooManyCoefficients { max: usize, got: usize },
+    #[error("the SRS supports opening at most {max} points with one proof, but got {got}")]
+    TooManyPoints { max: usize, got: usize },
+    #[error("expected a value for each of the {expected} points, but got {got}")]
+    ValueCountMismatch { expected: usize, got: usize },
+    #[error("a point is opened more than once")]
+    RepeatedPoint,
+}
+
+// Returned when a ceremony configuration file cannot be loaded, see `config`
+#[cfg(feature = "config")]
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("invalid ceremony configuration: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("the configuration does not describe any ceremonies")]
+    NoCeremonies,
+    #[error("ceremony {index} has invalid parameters: {error}")]
+    InvalidParameters { index: usize, error: ParameterError },
+}
@@ -0,0 +1,269 @@
+// Encodings for verifying update proofs on Ethereum, using the BLS12-381
+// precompiles from EIP-2537.
+//
+// The precompiles encode a base field element as 64 big endian bytes, where
+// the top 16 bytes are zero. A G1 point is its x and y coordinates, a G2 point
+// is x.c0, x.c1, y.c0 and y.c1, and the point at infinity is all zeros.
+use crate::{
+    interop_point_encoding::serialise_fq,
+    update_proof::{G1UpdateProof, UpdateProof},
+};
+use ark_bls12_381::{Fq, G1Affine, G2Affine};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+
+// The size of an encoded base field element
+pub const EVM_FQ_SIZE: usize = 64;
+// The size of an encoded G1 point
+pub const EVM_G1_SIZE: usize = 2 * EVM_FQ_SIZE;
+// The size of an encoded G2 point
+pub const EVM_G2_SIZE: usize = 4 * EVM_FQ_SIZE;
+// The size of one (G1, G2) pair in the input to the pairing precompile
+pub const EVM_PAIR_SIZE: usize = EVM_G1_SIZE + EVM_G2_SIZE;
+
+pub fn encode_g1(point: &G1Affine) -> [u8; EVM_G1_SIZE] {
+    let mut bytes = [0u8; EVM_G1_SIZE];
+    if !point.infinity {
+        encode_fq(point.x, &mut bytes[..EVM_FQ_SIZE]);
+        encode_fq(point.y, &mut bytes[EVM_FQ_SIZE..]);
+    }
+    bytes
+}
+
+pub fn encode_g2(point: &G2Affine) -> [u8; EVM_G2_SIZE] {
+    let mut bytes = [0u8; EVM_G2_SIZE];
+    if !point.infinity {
+        let coordinates = [point.x.c0, point.x.c1, point.y.c0, point.y.c1];
+        for (coordinate, chunk) in coordinates.iter().zip(bytes.chunks_mut(EVM_FQ_SIZE)) {
+            encode_fq(*coordinate, chunk);
+        }
+    }
+    bytes
+}
+
+fn encode_fq(field: Fq, bytes: &mut [u8]) {
+    // The 48 byte encoding is right aligned in the 64 bytes
+    bytes[EVM_FQ_SIZE - 48..].copy_from_slice(&serialise_fq(field));
+}
+
+// Encodes an update proof as the commitment to the secret followed by the
+// new accumulated point, for storing the proof in a contract
+pub fn encode_update_proof(update_proof: &UpdateProof) -> [u8; EVM_G2_SIZE + EVM_G1_SIZE] {
+    let mut bytes = [0u8; EVM_G2_SIZE + EVM_G1_SIZE];
+    bytes[..EVM_G2_SIZE]
+        .copy_from_slice(&encode_g2(&update_proof.commitment_to_secret.into_affine()));
+    bytes[EVM_G2_SIZE..].copy_from_slice(&encode_g1(
+        &update_proof.new_accumulated_point.into_affine(),
+    ));
+    bytes
+}
+
+// The input to the pairing precompile which checks that `update_proof`
+// follows from `previous_point`, the degree-1 G1 element before the update.
+//
+// The check is e(new_accumulated_point, g2) * e(-previous_point, commitment_to_secret) = 1,
+// which is the check made by `SRS::verify_updates` for each link of the chain
+pub fn update_proof_pairing_input(
+    previous_point: &G1Affine,
+    update_proof: &UpdateProof,
+) -> [u8; 2 * EVM_PAIR_SIZE] {
+    let pairs = [
+        (
+            update_proof.new_accumulated_point.into_affine(),
+            G2Affine::prime_subgroup_generator(),
+        ),
+        (
+            -*previous_point,
+            update_proof.commitment_to_secret.into_affine(),
+        ),
+    ];
+
+    let mut bytes = [0u8; 2 * EVM_PAIR_SIZE];
+    for ((g1, g2), pair_bytes) in pairs.iter().zip(bytes.chunks_mut(EVM_PAIR_SIZE)) {
+        pair_bytes[..EVM_G1_SIZE].copy_from_slice(&encode_g1(g1));
+        pair_bytes[EVM_G1_SIZE..].copy_from_slice(&encode_g2(g2));
+    }
+    bytes
+}
+
+// The input to the pairing precompile which checks that a `G1UpdateProof`
+// follows from `previous_point`, the degree-1 G2 element before the update.
+//
+// The check is e(commitment_to_secret, previous_point) * e(-g1, new_accumulated_point) = 1
+pub fn g1_update_proof_pairing_input(
+    previous_point: &G2Affine,
+    update_proof: &G1UpdateProof,
+) -> [u8; 2 * EVM_PAIR_SIZE] {
+    let pairs = [
+        (
+            update_proof.commitment_to_secret.into_affine(),
+            *previous_point,
+        ),
+        (
+            -G1Affine::prime_subgroup_generator(),
+            update_proof.new_accumulated_point.into_affine(),
+        ),
+    ];
+
+    let mut bytes = [0u8; 2 * EVM_PAIR_SIZE];
+    for ((g1, g2), pair_bytes) in pairs.iter().zip(bytes.chunks_mut(EVM_PAIR_SIZE)) {
+        pair_bytes[..EVM_G1_SIZE].copy_from_slice(&encode_g1(g1));
+        pair_bytes[EVM_G1_SIZE..].copy_from_slice(&encode_g2(g2));
+    }
+    bytes
+}
+
+// The address of the pairing check precompile
+pub const PAIRING_PRECOMPILE_ADDRESS: u8 = 0x0f;
+
+// Generates the source of a Solidity contract which records a chain of ceremony
+// checkpoints. Each checkpoint is an update proof, which the contract checks
+// against the degree-1 G1 element of the previous checkpoint using the pairing
+// precompile, which is the check that `update_proof_pairing_input` encodes.
+//
+// The SRS digest of each checkpoint, see `SRS::digest`, cannot be checked on
+// chain, so only the owner of the contract can add checkpoints. The contract is
+// deployed with the encoded degree-1 element of the starting SRS and its digest.
+//
+// `contract_name` must be a valid Solidity identifier
+pub fn checkpoint_contract(contract_name: &str) -> String {
+    let neg_g2_generator = hex::encode(encode_g2(&-G2Affine::prime_subgroup_generator()));
+
+    CHECKPOINT_CONTRACT_TEMPLATE
+        .replace("{CONTRACT_NAME}", contract_name)
+        .replace("{NEG_G2_GENERATOR}", &neg_g2_generator)
+        .replace(
+            "{PAIRING_PRECOMPILE}",
+            &format!("{:#04x}", PAIRING_PRECOMPILE_ADDRESS),
+        )
+}
+
+const CHECKPOINT_CONTRACT_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT
+// Generated by small-powers-of-tau
+pragma solidity ^0.8.0;
+
+contract {CONTRACT_NAME} {
+    // The negation of the G2 generator, encoded for EIP-2537
+    bytes constant NEG_G2_GENERATOR = hex"{NEG_G2_GENERATOR}";
+    address constant PAIRING_PRECOMPILE = address({PAIRING_PRECOMPILE});
+
+    address public immutable owner;
+    // The EIP-2537 encoding of the degree-1 G1 element of the latest checkpoint
+    bytes public lastPoint;
+    bytes32 public lastDigest;
+    uint256 public numCheckpoints;
+
+    event Checkpoint(uint256 indexed index, bytes32 digest, bytes commitmentToSecret, bytes newPoint);
+
+    constructor(bytes memory startingPoint, bytes32 startingDigest) {
+        require(startingPoint.length == 128, "invalid G1 point");
+        owner = msg.sender;
+        lastPoint = startingPoint;
+        lastDigest = startingDigest;
+    }
+
+    // Checks e(newPoint, -g2) * e(lastPoint, commitmentToSecret) == 1, so that
+    // newPoint is lastPoint multiplied by the secret committed to
+    function checkpoint(bytes calldata commitmentToSecret, bytes calldata newPoint, bytes32 digest) external {
+        require(msg.sender == owner, "only the owner can add checkpoints");
+        require(commitmentToSecret.length == 256, "invalid G2 point");
+        require(newPoint.length == 128, "invalid G1 point");
+        // The point at infinity is encoded as zeros, and would pass the pairing
+        // check with an identity commitment while destroying the SRS
+        require(keccak256(commitmentToSecret) != keccak256(new bytes(256)), "the commitment is the identity");
+        require(keccak256(newPoint) != keccak256(new bytes(128)), "the new point is the identity");
+        require(keccak256(newPoint) != keccak256(lastPoint), "the update does not change the SRS");
+
+        bytes memory input = abi.encodePacked(newPoint, NEG_G2_GENERATOR, lastPoint, commitmentToSecret);
+        (bool success, bytes memory output) = PAIRING_PRECOMPILE.staticcall(input);
+        require(success && output.length == 32 && uint256(bytes32(output)) == 1, "invalid update proof");
+
+        lastPoint = newPoint;
+        lastDigest = digest;
+        emit Checkpoint(numCheckpoints, digest, commitmentToSecret, newPoint);
+        numCheckpoints += 1;
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        keypair::PrivateKey,
+        srs::{Parameters, SRS},
+    };
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::PairingEngine;
+    use ark_ff::{One, Zero};
+
+    #[test]
+    fn encode_generators() {
+        let g1 = encode_g1(&G1Affine::prime_subgroup_generator());
+        assert_eq!(g1[..16], [0u8; 16]);
+        assert_eq!(hex::encode(&g1[16..20]), "17f1d3a7");
+        assert_eq!(g1[64..80], [0u8; 16]);
+        assert_eq!(hex::encode(&g1[80..84]), "08b3f481");
+
+        // x.c0 comes before x.c1
+        let g2 = encode_g2(&G2Affine::prime_subgroup_generator());
+        assert_eq!(hex::encode(&g2[16..20]), "024aa2b2");
+        assert_eq!(hex::encode(&g2[80..84]), "13e02b60");
+
+        assert_eq!(encode_g1(&G1Affine::default()), [0u8; EVM_G1_SIZE]);
+        assert_eq!(encode_g2(&G2Affine::default()), [0u8; EVM_G2_SIZE]);
+    }
+
+    #[test]
+    fn pairing_input_is_satisfied() {
+        let mut srs = SRS::new(Parameters::new(4, 2)).unwrap();
+        let previous_point = srs.g1_elements()[1];
+        let update_proof = srs.update(PrivateKey::from_u64(252));
+
+        // The pairs which were encoded multiply to one
+        let p1 = Bls12_381::pairing(
+            update_proof.new_accumulated_point,
+            G2Affine::prime_subgroup_generator(),
+        );
+        let p2 = Bls12_381::pairing(-previous_point, update_proof.commitment_to_secret);
+        assert!((p1 * p2).is_one());
+
+        let input = update_proof_pairing_input(&previous_point, &update_proof);
+        assert_eq!(
+            input[..EVM_G1_SIZE],
+            encode_g1(&update_proof.new_accumulated_point.into_affine())
+        );
+        assert_eq!(
+            input[EVM_PAIR_SIZE..EVM_PAIR_SIZE + EVM_G1_SIZE],
+            encode_g1(&-previous_point)
+        );
+    }
+
+    #[test]
+    fn checkpoint_contract_has_constants() {
+        let contract = checkpoint_contract("Checkpoints");
+        assert!(contract.contains("contract Checkpoints {"));
+        assert!(contract.contains("address(0x0f)"));
+        assert!(!contract.contains("{NEG_G2_GENERATOR}"));
+
+        // The negated generator has the same x-coordinate as the generator
+        let neg_g2_generator = encode_g2(&-G2Affine::prime_subgroup_generator());
+        assert_eq!(
+            neg_g2_generator[..2 * EVM_FQ_SIZE],
+            encode_g2(&G2Affine::prime_subgroup_generator())[..2 * EVM_FQ_SIZE]
+        );
+        assert!(contract.contains(&hex::encode(neg_g2_generator)));
+    }
+
+    #[test]
+    fn checkpoint_contract_rejects_the_identity() {
+        let contract = checkpoint_contract("Checkpoints");
+        assert!(
+            contract.contains("require(keccak256(commitmentToSecret) != keccak256(new bytes(256))")
+        );
+        assert!(contract.contains("require(keccak256(newPoint) != keccak256(new bytes(128))"));
+
+        // The identity is encoded as zeros, which the guards compare against
+        assert_eq!(encode_g1(&G1Affine::zero()), [0u8; EVM_G1_SIZE]);
+        assert_eq!(encode_g2(&G2Affine::zero()), [0u8; EVM_G2_SIZE]);
+    }
+}
@@ -0,0 +1,223 @@
+// C bindings, so that the ceremony logic can be embedded into C, C++ and Go
+// applications without going through wasm. The corresponding header is
+// `include/small_powers_of_tau.h`, which documents the safety requirements
+// of each function.
+//
+// SRS and update proofs cross the boundary using their compressed byte
+// encodings, see `SRS::serialise_bytes` and `UpdateProof::serialise_bytes`.
+#![allow(clippy::missing_safety_doc)]
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+
+use ark_bls12_381::Fr;
+use ark_ff::UniformRand;
+
+use crate::{
+    keypair::PrivateKey,
+    serialisation::{self, Encoding, SubgroupCheck, UPDATE_PROOF_SERIALISED_SIZE},
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+
+pub const SPOT_OK: i32 = 0;
+pub const SPOT_ERR_NULL_POINTER: i32 = 1;
+pub const SPOT_ERR_INVALID_LENGTH: i32 = 2;
+pub const SPOT_ERR_DESERIALISATION: i32 = 3;
+pub const SPOT_ERR_INVALID_UPDATE: i32 = 4;
+pub const SPOT_ERR_PANIC: i32 = 5;
+pub const SPOT_ERR_INVALID_SECRET: i32 = 6;
+
+// Panics must not unwind across the C boundary, so they are converted into an error code
+fn ffi_boundary<F: FnOnce() -> Result<(), i32>>(f: F) -> i32 {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => SPOT_OK,
+        Ok(Err(code)) => code,
+        Err(_) => SPOT_ERR_PANIC,
+    }
+}
+
+fn serialised_size(num_g1: usize, num_g2: usize) -> Option<usize> {
+    serialisation::serialised_size(Parameters::new(num_g1, num_g2), Encoding::Bytes)
+}
+
+unsafe fn bytes_from_raw<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], i32> {
+    if ptr.is_null() {
+        return Err(SPOT_ERR_NULL_POINTER);
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn bytes_from_raw_mut<'a>(ptr: *mut u8, len: usize) -> Result<&'a mut [u8], i32> {
+    if ptr.is_null() {
+        return Err(SPOT_ERR_NULL_POINTER);
+    }
+    Ok(slice::from_raw_parts_mut(ptr, len))
+}
+
+unsafe fn srs_from_raw(
+    srs_ptr: *const u8,
+    srs_len: usize,
+    parameters: Parameters,
+) -> Result<SRS, i32> {
+    let srs_bytes = bytes_from_raw(srs_ptr, srs_len)?;
+    SRS::deserialise_bytes(srs_bytes, parameters, SubgroupCheck::Full)
+        .map_err(|_| SPOT_ERR_DESERIALISATION)
+}
+
+// Checks that the SRS buffers have the length implied by the parameters
+fn check_srs_len(srs_len: usize, num_g1: usize, num_g2: usize) -> Result<(), i32> {
+    match serialised_size(num_g1, num_g2) {
+        Some(expected_len) if expected_len == srs_len => Ok(()),
+        _ => Err(SPOT_ERR_INVALID_LENGTH),
+    }
+}
+
+// Returns the length of an SRS with the given parameters when serialised,
+// or zero if the length does not fit into a `size_t`
+#[no_mangle]
+pub extern "C" fn srs_serialised_size(num_g1: usize, num_g2: usize) -> usize {
+    serialised_size(num_g1, num_g2).unwrap_or(0)
+}
+
+// Writes the starting SRS for a ceremony with the given parameters into `srs_out`
+#[no_mangle]
+pub unsafe extern "C" fn srs_new(
+    num_g1: usize,
+    num_g2: usize,
+    srs_out: *mut u8,
+    srs_len: usize,
+) -> i32 {
+    ffi_boundary(|| {
+        check_srs_len(srs_len, num_g1, num_g2)?;
+        let srs_out = bytes_from_raw_mut(srs_out, srs_len)?;
+
+        let srs = SRS::new(Parameters::new(num_g1, num_g2)).map_err(|_| SPOT_ERR_INVALID_LENGTH)?;
+        srs_out.copy_from_slice(&srs.serialise_bytes());
+
+        Ok(())
+    })
+}
+
+// Updates the SRS in `srs_in` using the secret, writing the updated SRS into
+// `srs_out` and the update proof into `proof_out`. Returns
+// `SPOT_ERR_INVALID_SECRET` if the secret is zero or one modulo the group order
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn srs_contribute(
+    srs_in: *const u8,
+    srs_out: *mut u8,
+    srs_len: usize,
+    num_g1: usize,
+    num_g2: usize,
+    secret: *const u8,
+    secret_len: usize,
+    proof_out: *mut u8,
+) -> i32 {
+    ffi_boundary(|| {
+        check_srs_len(srs_len, num_g1, num_g2)?;
+        let mut srs = srs_from_raw(srs_in, srs_len, Parameters::new(num_g1, num_g2))?;
+        let secret = bytes_from_raw(secret, secret_len)?;
+        let srs_out = bytes_from_raw_mut(srs_out, srs_len)?;
+        let proof_out = bytes_from_raw_mut(proof_out, UPDATE_PROOF_SERIALISED_SIZE)?;
+
+        let private_key = PrivateKey::from_bytes(secret).map_err(|_| SPOT_ERR_INVALID_SECRET)?;
+        let update_proof = srs.update(private_key);
+
+        srs_out.copy_from_slice(&srs.serialise_bytes());
+        proof_out.copy_from_slice(&update_proof.serialise_bytes());
+
+        Ok(())
+    })
+}
+
+// Verifies that `srs_after` was obtained by updating `srs_before` with the
+// update proof. Returns `SPOT_OK` if the update is valid and
+// `SPOT_ERR_INVALID_UPDATE` if it is not.
+#[no_mangle]
+pub unsafe extern "C" fn srs_verify_update(
+    srs_before: *const u8,
+    srs_after: *const u8,
+    srs_len: usize,
+    num_g1: usize,
+    num_g2: usize,
+    proof: *const u8,
+) -> i32 {
+    ffi_boundary(|| {
+        check_srs_len(srs_len, num_g1, num_g2)?;
+        let parameters = Parameters::new(num_g1, num_g2);
+        let before = srs_from_raw(srs_before, srs_len, parameters)?;
+        let after = srs_from_raw(srs_after, srs_len, parameters)?;
+
+        let proof = bytes_from_raw(proof, UPDATE_PROOF_SERIALISED_SIZE)?;
+        let proof = UpdateProof::deserialise_bytes(proof.try_into().unwrap())
+            .map_err(|_| SPOT_ERR_DESERIALISATION)?;
+
+        let random_element = Fr::rand(&mut rand::thread_rng());
+        SRS::verify_update(&before, &after, &proof, random_element)
+            .map_err(|_| SPOT_ERR_INVALID_UPDATE)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ffi_contribute_verify() {
+        let (num_g1, num_g2) = (16, 2);
+        let srs_len = srs_serialised_size(num_g1, num_g2);
+
+        let mut before = vec![0u8; srs_len];
+        let mut after = vec![0u8; srs_len];
+        let mut proof = [0u8; UPDATE_PROOF_SERIALISED_SIZE];
+        let secret = [42u8; 32];
+
+        unsafe {
+            assert_eq!(
+                srs_new(num_g1, num_g2, before.as_mut_ptr(), srs_len),
+                SPOT_OK
+            );
+            assert_eq!(
+                srs_contribute(
+                    before.as_ptr(),
+                    after.as_mut_ptr(),
+                    srs_len,
+                    num_g1,
+                    num_g2,
+                    secret.as_ptr(),
+                    secret.len(),
+                    proof.as_mut_ptr(),
+                ),
+                SPOT_OK
+            );
+            assert_eq!(
+                srs_verify_update(
+                    before.as_ptr(),
+                    after.as_ptr(),
+                    srs_len,
+                    num_g1,
+                    num_g2,
+                    proof.as_ptr()
+                ),
+                SPOT_OK
+            );
+            // The update should not verify when applied the other way around
+            assert_eq!(
+                srs_verify_update(
+                    after.as_ptr(),
+                    before.as_ptr(),
+                    srs_len,
+                    num_g1,
+                    num_g2,
+                    proof.as_ptr()
+                ),
+                SPOT_ERR_INVALID_UPDATE
+            );
+            assert_eq!(
+                srs_new(num_g1, num_g2, std::ptr::null_mut(), srs_len),
+                SPOT_ERR_NULL_POINTER
+            );
+        }
+    }
+}
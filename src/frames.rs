@@ -0,0 +1,332 @@
+// Splitting a challenge or response file into frames which are small enough
+// to be shown as a sequence of QR codes, so that a contribution can be carried
+// to and from an offline machine using a screen and a camera. See the
+// challenge module for the files themselves.
+//
+// Every frame has a checksum, so a misread frame is rejected rather than
+// corrupting the file. Frames can be scanned in any order, and after every
+// `group_size` data frames there is a parity frame which is the XOR of them,
+// so any one missing frame in each group can be recovered without showing
+// the whole sequence again.
+//
+// A frame is laid out as
+//
+// transfer id (8) || file length (u64) || frame size (u32) || group size (u32) || index (u32) || payload || checksum (8)
+//
+// where the integers are little endian, the transfer id is the start of the
+// sha256 hash of the file and the checksum is the start of the sha256 hash of
+// the rest of the frame. The payload of the last data frame is padded with zeros.
+// QR codes should use the byte mode, since the frames are binary.
+use crate::error::SerialisationError;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+const HEADER_SIZE: usize = 8 + 8 + 4 + 4 + 4;
+const CHECKSUM_SIZE: usize = 8;
+
+// The largest payload which fits in a version 40 QR code in byte mode with
+// the lowest error correction level, along with the frame header
+pub const MAX_QR_FRAME_SIZE: usize = 2953 - HEADER_SIZE - CHECKSUM_SIZE;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    transfer_id: [u8; 8],
+    file_len: u64,
+    frame_size: u32,
+    group_size: u32,
+    // Data frames come first, followed by the parity frame of each group
+    index: u32,
+    payload: Vec<u8>,
+}
+
+// Splits `file` into frames of `frame_size` bytes, with a parity frame after
+// every `group_size` data frames
+pub fn encode_frames(file: &[u8], frame_size: usize, group_size: usize) -> Vec<Frame> {
+    assert!(frame_size > 0 && frame_size <= u32::MAX as usize);
+    assert!(group_size > 0 && group_size <= u32::MAX as usize);
+    // The frames are indexed using u32s
+    let num_data_frames = div_round_up(file.len() as u64, frame_size as u64);
+    let num_groups = div_round_up(num_data_frames, group_size as u64);
+    assert!(num_data_frames + num_groups <= u32::MAX as u64);
+
+    let transfer_id = transfer_id(file);
+    let frame = |index: usize, payload: Vec<u8>| Frame {
+        transfer_id,
+        file_len: file.len() as u64,
+        frame_size: frame_size as u32,
+        group_size: group_size as u32,
+        index: index as u32,
+        payload,
+    };
+
+    let mut frames: Vec<Frame> = file
+        .chunks(frame_size)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut payload = chunk.to_vec();
+            payload.resize(frame_size, 0);
+            frame(index, payload)
+        })
+        .collect();
+
+    let num_data_frames = frames.len();
+    for (group, start) in (0..num_data_frames).step_by(group_size).enumerate() {
+        let end = std::cmp::min(start + group_size, num_data_frames);
+        let payloads = frames[start..end].iter().map(|frame| &frame.payload);
+        let parity = xor_payloads(payloads, frame_size);
+        frames.push(frame(num_data_frames + group, parity));
+    }
+    frames
+}
+
+impl Frame {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.payload.len() + CHECKSUM_SIZE);
+        bytes.extend_from_slice(&self.transfer_id);
+        bytes.extend_from_slice(&self.file_len.to_le_bytes());
+        bytes.extend_from_slice(&self.frame_size.to_le_bytes());
+        bytes.extend_from_slice(&self.group_size.to_le_bytes());
+        bytes.extend_from_slice(&self.index.to_le_bytes());
+        bytes.extend_from_slice(&self.payload);
+
+        let checksum = Sha256::digest(&bytes);
+        bytes.extend_from_slice(&checksum[..CHECKSUM_SIZE]);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerialisationError> {
+        if bytes.len() < HEADER_SIZE + CHECKSUM_SIZE {
+            return Err(SerialisationError::InvalidHeader);
+        }
+        let (contents, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if &Sha256::digest(contents)[..CHECKSUM_SIZE] != checksum {
+            return Err(SerialisationError::ChecksumMismatch);
+        }
+
+        let (header, payload) = contents.split_at(HEADER_SIZE);
+        let u32_at = |offset: usize| {
+            let mut int = [0u8; 4];
+            int.copy_from_slice(&header[offset..offset + 4]);
+            u32::from_le_bytes(int)
+        };
+        let mut transfer_id = [0u8; 8];
+        transfer_id.copy_from_slice(&header[..8]);
+        let mut file_len = [0u8; 8];
+        file_len.copy_from_slice(&header[8..16]);
+
+        let frame = Frame {
+            transfer_id,
+            file_len: u64::from_le_bytes(file_len),
+            frame_size: u32_at(16),
+            group_size: u32_at(20),
+            index: u32_at(24),
+            payload: payload.to_vec(),
+        };
+        if frame.frame_size == 0
+            || frame.group_size == 0
+            || frame.payload.len() != frame.frame_size as usize
+        {
+            return Err(SerialisationError::InvalidHeader);
+        }
+        let num_data_frames = div_round_up(frame.file_len, frame.frame_size as u64);
+        let num_frames = num_data_frames + div_round_up(num_data_frames, frame.group_size as u64);
+        if num_frames > u32::MAX as u64 || frame.index as u64 >= num_frames {
+            return Err(SerialisationError::InvalidHeader);
+        }
+        Ok(frame)
+    }
+
+    // The position of this frame in the sequence
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+    // The number of frames in the sequence, including the parity frames
+    pub fn num_frames(&self) -> usize {
+        self.num_data_frames() + self.num_groups()
+    }
+
+    // `from_bytes` checks that the number of frames fits in a u32, so the
+    // counts can be converted to usize
+    fn num_data_frames(&self) -> usize {
+        div_round_up(self.file_len, self.frame_size as u64) as usize
+    }
+    fn num_groups(&self) -> usize {
+        div_round_up(self.num_data_frames() as u64, self.group_size as u64) as usize
+    }
+    fn same_transfer(&self, other: &Frame) -> bool {
+        self.transfer_id == other.transfer_id
+            && self.file_len == other.file_len
+            && self.frame_size == other.frame_size
+            && self.group_size == other.group_size
+    }
+}
+
+// Collects scanned frames until the file can be put back together
+#[derive(Debug, Clone, Default)]
+pub struct FrameDecoder {
+    frames: BTreeMap<usize, Frame>,
+}
+
+impl FrameDecoder {
+    pub fn new() -> Self {
+        FrameDecoder::default()
+    }
+
+    // Adds a scanned frame. Frames which were already scanned are ignored, and
+    // frames from a different file are rejected
+    pub fn push(&mut self, bytes: &[u8]) -> Result<(), SerialisationError> {
+        let frame = Frame::from_bytes(bytes)?;
+        if let Some(first) = self.frames.values().next() {
+            if !first.same_transfer(&frame) {
+                return Err(SerialisationError::FrameMismatch);
+            }
+        }
+        self.frames.entry(frame.index()).or_insert(frame);
+        Ok(())
+    }
+
+    // The indices of the data frames which are still needed, after taking
+    // the recoverable frames into account
+    pub fn missing_frames(&self) -> Vec<usize> {
+        let first = match self.frames.values().next() {
+            Some(first) => first,
+            None => return Vec::new(),
+        };
+        let num_data_frames = first.num_data_frames();
+        let group_size = first.group_size as usize;
+
+        let mut missing = Vec::new();
+        for group in 0..first.num_groups() {
+            let group_range = group * group_size
+                ..std::cmp::min((group + 1).saturating_mul(group_size), num_data_frames);
+            let missing_in_group: Vec<usize> = group_range
+                .filter(|index| !self.frames.contains_key(index))
+                .collect();
+            let has_parity = self.frames.contains_key(&(num_data_frames + group));
+            if missing_in_group.len() > 1 || (missing_in_group.len() == 1 && !has_parity) {
+                missing.extend(missing_in_group);
+            }
+        }
+        missing
+    }
+
+    pub fn is_complete(&self) -> bool {
+        !self.frames.is_empty() && self.missing_frames().is_empty()
+    }
+
+    // Puts the file back together, recovering any missing data frames from
+    // the parity frames
+    pub fn finish(&self) -> Result<Vec<u8>, SerialisationError> {
+        if !self.is_complete() {
+            return Err(SerialisationError::MissingFrames);
+        }
+        let first = self
+            .frames
+            .values()
+            .next()
+            .expect("there is at least one frame");
+        let num_data_frames = first.num_data_frames();
+        let frame_size = first.frame_size as usize;
+        let group_size = first.group_size as usize;
+
+        let mut file = Vec::with_capacity(num_data_frames * frame_size);
+        for index in 0..num_data_frames {
+            match self.frames.get(&index) {
+                Some(frame) => file.extend_from_slice(&frame.payload),
+                None => {
+                    // The only missing frame in its group is the XOR of the
+                    // parity frame and the other data frames
+                    let group = index / group_size;
+                    let group_range = group * group_size
+                        ..std::cmp::min((group + 1).saturating_mul(group_size), num_data_frames);
+                    let payloads = group_range
+                        .filter_map(|other| self.frames.get(&other))
+                        .chain(self.frames.get(&(num_data_frames + group)))
+                        .map(|frame| &frame.payload);
+                    file.extend_from_slice(&xor_payloads(payloads, frame_size));
+                }
+            }
+        }
+        file.truncate(first.file_len as usize);
+
+        if transfer_id(&file) != first.transfer_id {
+            return Err(SerialisationError::ChecksumMismatch);
+        }
+        Ok(file)
+    }
+}
+
+fn div_round_up(numerator: u64, denominator: u64) -> u64 {
+    numerator / denominator + (numerator % denominator != 0) as u64
+}
+
+fn transfer_id(file: &[u8]) -> [u8; 8] {
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&Sha256::digest(file)[..8]);
+    id
+}
+
+fn xor_payloads<'a>(payloads: impl Iterator<Item = &'a Vec<u8>>, frame_size: usize) -> Vec<u8> {
+    let mut result = vec![0u8; frame_size];
+    for payload in payloads {
+        for (byte, other) in result.iter_mut().zip(payload) {
+            *byte ^= other;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{challenge::Challenge, sdk::CEREMONIES, srs::SRS};
+
+    #[test]
+    fn recover_missing_frames() {
+        let challenge = Challenge::new(SRS::new(CEREMONIES[0]).unwrap(), [0u8; 32]);
+        let mut file = Vec::new();
+        challenge.write_to(&mut file).unwrap();
+
+        let frames = encode_frames(&file, MAX_QR_FRAME_SIZE, 8);
+        assert!(frames.len() < 100);
+
+        // Frames can arrive in any order, and one frame in each group can be lost
+        let mut decoder = FrameDecoder::new();
+        let num_data_frames = div_round_up(file.len() as u64, MAX_QR_FRAME_SIZE as u64) as usize;
+        for frame in frames.iter().rev() {
+            if frame.index() >= num_data_frames || frame.index() % 8 != 3 {
+                decoder.push(&frame.to_bytes()).unwrap();
+            }
+        }
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.finish().unwrap(), file);
+
+        // Two frames lost from the same group cannot be recovered
+        let mut decoder = FrameDecoder::new();
+        for frame in &frames {
+            if frame.index() != 3 && frame.index() != 4 {
+                decoder.push(&frame.to_bytes()).unwrap();
+            }
+        }
+        assert_eq!(decoder.missing_frames(), vec![3, 4]);
+        assert!(matches!(
+            decoder.finish(),
+            Err(SerialisationError::MissingFrames)
+        ));
+
+        // A misread frame is rejected
+        let mut misread = frames[5].to_bytes();
+        misread[100] ^= 1;
+        assert!(matches!(
+            decoder.push(&misread),
+            Err(SerialisationError::ChecksumMismatch)
+        ));
+
+        // So is a frame of a different file
+        let other = encode_frames(&file[1..], MAX_QR_FRAME_SIZE, 8);
+        assert!(matches!(
+            decoder.push(&other[0].to_bytes()),
+            Err(SerialisationError::FrameMismatch)
+        ));
+    }
+}
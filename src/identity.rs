@@ -0,0 +1,135 @@
+// The identity of a participant, which they sign using their secret so that
+// their contribution is bound to them, see `PrivateKey::sign_identity`.
+//
+// Identities are signed and compared using their canonical encoding, which
+// follows the Ethereum KZG ceremony; `eth|0x<address>` with the address in lower
+// case, and `git|<id>|<handle>` with the handle in lower case, since GitHub
+// handles are not case sensitive. Any other string is kept as it is.
+use crate::error::SerialisationError;
+use crate::serialisation::{bytes_to_hex_string, hex_string_to_bytes};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+const ETHEREUM_PREFIX: &str = "eth|";
+const GITHUB_PREFIX: &str = "git|";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Identity {
+    Ethereum([u8; 20]),
+    GitHub { id: u64, handle: String },
+    // An identity from any other provider. This must not start with one of
+    // the prefixes above, otherwise its encoding would not parse back to it
+    Other(String),
+}
+
+impl Identity {
+    pub fn github(id: u64, handle: &str) -> Self {
+        Identity::GitHub {
+            id,
+            handle: handle.to_lowercase(),
+        }
+    }
+
+    // The canonical encoding, which is what gets signed
+    pub fn encode(&self) -> String {
+        match self {
+            Identity::Ethereum(address) => {
+                format!("{}{}", ETHEREUM_PREFIX, bytes_to_hex_string(address))
+            }
+            Identity::GitHub { id, handle } => {
+                format!("{}{}|{}", GITHUB_PREFIX, id, handle.to_lowercase())
+            }
+            Identity::Other(identity) => identity.clone(),
+        }
+    }
+
+    // Parses an identity, accepting addresses and handles in any case
+    pub fn parse(identity: &str) -> Result<Self, SerialisationError> {
+        if let Some(address) = identity.strip_prefix(ETHEREUM_PREFIX) {
+            let address = hex_string_to_bytes(address)?;
+            let address = address
+                .as_slice()
+                .try_into()
+                .map_err(|_| SerialisationError::InvalidIdentity)?;
+            return Ok(Identity::Ethereum(address));
+        }
+        if let Some(github) = identity.strip_prefix(GITHUB_PREFIX) {
+            let (id, handle) = github
+                .split_once('|')
+                .ok_or(SerialisationError::InvalidIdentity)?;
+            let id = id
+                .parse()
+                .map_err(|_| SerialisationError::InvalidIdentity)?;
+            if handle.is_empty() {
+                return Err(SerialisationError::InvalidIdentity);
+            }
+            return Ok(Identity::github(id, handle));
+        }
+        Ok(Identity::Other(identity.to_string()))
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+impl FromStr for Identity {
+    type Err = SerialisationError;
+
+    fn from_str(identity: &str) -> Result<Self, Self::Err> {
+        Identity::parse(identity)
+    }
+}
+
+impl TryFrom<String> for Identity {
+    type Error = SerialisationError;
+
+    fn try_from(identity: String) -> Result<Self, Self::Error> {
+        Identity::parse(&identity)
+    }
+}
+
+impl From<Identity> for String {
+    fn from(identity: Identity) -> Self {
+        identity.encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_encoding() {
+        let ethereum = Identity::parse("eth|0xF39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap();
+        assert_eq!(
+            ethereum.encode(),
+            "eth|0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+
+        let github = Identity::parse("git|1234|Alice").unwrap();
+        assert_eq!(github, Identity::github(1234, "alice"));
+        assert_eq!(github.to_string(), "git|1234|alice");
+
+        let other: Identity = "alice@example.com".parse().unwrap();
+        assert_eq!(other, Identity::Other("alice@example.com".to_string()));
+
+        for invalid in [
+            "eth|0x1234",
+            "eth|f39f",
+            "git|alice",
+            "git|12a|alice",
+            "git|1234|",
+        ] {
+            assert!(Identity::parse(invalid).is_err(), "{}", invalid);
+        }
+
+        let json = serde_json::to_string(&github).unwrap();
+        assert_eq!(json, "\"git|1234|alice\"");
+        assert_eq!(serde_json::from_str::<Identity>(&json).unwrap(), github);
+    }
+}
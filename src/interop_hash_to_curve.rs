@@ -0,0 +1,23 @@
+// arkworks does not yet implement the hash to curve algorithms from the IETF
+// draft, so we use the zkcrypto implementation and convert the resulting
+// point using the compressed encoding.
+//
+// This should NOT be audited.
+use crate::interop_point_encoding::deserialize_g1;
+use ark_bls12_381::G1Affine;
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+
+// The domain separation tag used for BLS signatures in the ceremony specs
+pub const BLS_SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+
+// Hashes a message to a point in G1 using the given domain separation tag
+pub fn hash_to_g1(message: &[u8], dst: &[u8]) -> G1Affine {
+    let point = <bls12_381::G1Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(
+        message, dst,
+    );
+    let compressed = bls12_381::G1Affine::from(point).to_compressed();
+
+    // The zkcrypto encoding is the same as the one used in `interop_point_encoding`
+    // and the point is guaranteed to be valid, so this cannot fail
+    deserialize_g1(compressed).expect("hash to curve produced an invalid point")
+}
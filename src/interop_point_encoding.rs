@@ -1,11 +1,17 @@
+use crate::error::{PointEncodingError, SerialisationError};
+use crate::metrics::{self, FailureCategory};
 use std::io::Read;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 //  The problem is that the arkworks encoding is not consistent with the "official" encoding for bls12_381
 // So this wrapper code is needed.
 //
 // Code was adapted from zkcrypto/bls12-381
 // This should NOT be audited.
 use ark_bls12_381::{Fq, G1Affine, G2Affine};
+use ark_ec::AffineCurve;
 use ark_ff::{BigInteger384, Fp2, PrimeField};
 
 // The compressed size of a serialised G1 element
@@ -13,23 +19,19 @@ pub const G1_SERIALISED_SIZE: usize = 48;
 // The compressed size of a serialised G2 element
 pub const G2_SERIALISED_SIZE: usize = 96;
 
-pub fn g1_from_reader<R: Read>(reader: &mut R) -> Option<G1Affine> {
+pub fn g1_from_reader<R: Read>(reader: &mut R) -> Result<G1Affine, SerialisationError> {
     let mut point_bytes = [0u8; G1_SERIALISED_SIZE];
 
-    reader.read_exact(&mut point_bytes).ok()?;
-    match deserialize_g1(point_bytes) {
-        Some(point) => Some(point),
-        None => None,
-    }
+    reader.read_exact(&mut point_bytes)?;
+    metrics::record_bytes_deserialised(G1_SERIALISED_SIZE);
+    Ok(decode_g1(point_bytes).map_err(record_decoding_failure)?)
 }
-pub fn g2_from_reader<R: Read>(reader: &mut R) -> Option<G2Affine> {
+pub fn g2_from_reader<R: Read>(reader: &mut R) -> Result<G2Affine, SerialisationError> {
     let mut point_bytes = [0u8; G2_SERIALISED_SIZE];
 
-    reader.read_exact(&mut point_bytes).ok()?;
-    match deserialize_g2(point_bytes) {
-        Some(point) => Some(point),
-        None => None,
-    }
+    reader.read_exact(&mut point_bytes)?;
+    metrics::record_bytes_deserialised(G2_SERIALISED_SIZE);
+    Ok(decode_g2(point_bytes).map_err(record_decoding_failure)?)
 }
 
 fn serialize_g2_x(p: &G2Affine) -> [u8; G2_SERIALISED_SIZE] {
@@ -37,16 +39,16 @@ fn serialize_g2_x(p: &G2Affine) -> [u8; G2_SERIALISED_SIZE] {
 
     let c1_bytes = serialise_fq(p.x.c1);
     let c0_bytes = serialise_fq(p.x.c0);
-    (&mut result[0..48]).copy_from_slice(&c1_bytes[..]);
-    (&mut result[48..96]).copy_from_slice(&c0_bytes[..]);
+    result[0..48].copy_from_slice(&c1_bytes[..]);
+    result[48..96].copy_from_slice(&c0_bytes[..]);
 
     result
 }
 fn serialize_g1_x(p: &G1Affine) -> [u8; G1_SERIALISED_SIZE] {
-    return serialise_fq(p.x);
+    serialise_fq(p.x)
 }
 
-fn serialise_fq(field: Fq) -> [u8; G1_SERIALISED_SIZE] {
+pub(crate) fn serialise_fq(field: Fq) -> [u8; G1_SERIALISED_SIZE] {
     let mut result = [0u8; G1_SERIALISED_SIZE];
 
     let rep = field.into_repr();
@@ -77,18 +79,20 @@ fn deserialise_fq(bytes: [u8; G1_SERIALISED_SIZE]) -> Option<Fq> {
     Fq::from_repr(tmp)
 }
 
-pub fn deserialize_g1(bytes: [u8; G1_SERIALISED_SIZE]) -> Option<G1Affine> {
+// Decodes a compressed G1 point, reporting why the bytes are not a valid encoding.
+//
+// The point is not checked to be in the prime order subgroup
+pub fn decode_g1(bytes: [u8; G1_SERIALISED_SIZE]) -> Result<G1Affine, PointEncodingError> {
     // Obtain the three flags from the start of the byte sequence
     let flags = EncodingFlags::get_flags(&bytes[..]);
 
     if !flags.is_compressed {
-        return None;
-        // unimplemented!("uncompressed serialisation is not implemented")
+        return Err(PointEncodingError::Uncompressed);
     }
-
     if flags.is_infinity {
-        return Some(G1Affine::default());
+        return decode_infinity(&bytes, &flags);
     }
+
     // Attempt to obtain the x-coordinate
     let x = {
         let mut tmp = [0; G1_SERIALISED_SIZE];
@@ -97,22 +101,25 @@ pub fn deserialize_g1(bytes: [u8; G1_SERIALISED_SIZE]) -> Option<G1Affine> {
         // Mask away the flag bits
         tmp[0] &= 0b0001_1111;
 
-        deserialise_fq(tmp)?
+        deserialise_fq(tmp).ok_or(PointEncodingError::InvalidFieldElement)?
     };
 
     G1Affine::get_point_from_x(x, flags.is_lexographically_largest)
+        .ok_or(PointEncodingError::NotOnCurve)
 }
 
-pub fn deserialize_g2(bytes: [u8; G2_SERIALISED_SIZE]) -> Option<G2Affine> {
+// Decodes a compressed G2 point, reporting why the bytes are not a valid encoding.
+//
+// The point is not checked to be in the prime order subgroup
+pub fn decode_g2(bytes: [u8; G2_SERIALISED_SIZE]) -> Result<G2Affine, PointEncodingError> {
     // Obtain the three flags from the start of the byte sequence
     let flags = EncodingFlags::get_flags(&bytes);
 
-    if flags.is_infinity {
-        return Some(G2Affine::default());
-    }
     if !flags.is_compressed {
-        return None;
-        // unimplemented!("uncompressed serialisation is not implemented")
+        return Err(PointEncodingError::Uncompressed);
+    }
+    if flags.is_infinity {
+        return decode_infinity(&bytes, &flags);
     }
 
     // Attempt to obtain the x-coordinate
@@ -123,18 +130,129 @@ pub fn deserialize_g2(bytes: [u8; G2_SERIALISED_SIZE]) -> Option<G2Affine> {
         // Mask away the flag bits
         tmp[0] &= 0b0001_1111;
 
-        deserialise_fq(tmp)?
+        deserialise_fq(tmp).ok_or(PointEncodingError::InvalidFieldElement)?
     };
     let xc0 = {
         let mut tmp = [0; G1_SERIALISED_SIZE];
         tmp.copy_from_slice(&bytes[48..96]);
 
-        deserialise_fq(tmp)?
+        deserialise_fq(tmp).ok_or(PointEncodingError::InvalidFieldElement)?
     };
 
     let x = Fp2::new(xc0, xc1);
 
     G2Affine::get_point_from_x(x, flags.is_lexographically_largest)
+        .ok_or(PointEncodingError::NotOnCurve)
+}
+
+pub fn deserialize_g1(bytes: [u8; G1_SERIALISED_SIZE]) -> Option<G1Affine> {
+    decode_g1(bytes).ok()
+}
+pub fn deserialize_g2(bytes: [u8; G2_SERIALISED_SIZE]) -> Option<G2Affine> {
+    decode_g2(bytes).ok()
+}
+
+// Decodes a compressed G1 point from a slice, which must be exactly one point long
+pub fn g1_from_slice(bytes: &[u8]) -> Result<G1Affine, SerialisationError> {
+    let point_bytes = bytes
+        .try_into()
+        .map_err(|_| SerialisationError::InvalidLength {
+            expected: G1_SERIALISED_SIZE,
+            got: bytes.len(),
+        })?;
+    Ok(decode_g1(point_bytes)?)
+}
+// Decodes a compressed G2 point from a slice, which must be exactly one point long
+pub fn g2_from_slice(bytes: &[u8]) -> Result<G2Affine, SerialisationError> {
+    let point_bytes = bytes
+        .try_into()
+        .map_err(|_| SerialisationError::InvalidLength {
+            expected: G2_SERIALISED_SIZE,
+            got: bytes.len(),
+        })?;
+    Ok(decode_g2(point_bytes)?)
+}
+
+// Compresses a list of G1 points into consecutive encodings. Each point is
+// compressed into its own part of the output, so that the points can be
+// compressed in parallel
+pub fn encode_g1_batch(points: &[G1Affine]) -> Vec<u8> {
+    let mut bytes = vec![0u8; points.len() * G1_SERIALISED_SIZE];
+
+    ark_std::cfg_chunks_mut!(bytes, G1_SERIALISED_SIZE)
+        .zip(ark_std::cfg_iter!(points))
+        .for_each(|(point_bytes, point)| point_bytes.copy_from_slice(&serialize_g1(point)));
+
+    bytes
+}
+// Compresses a list of G2 points in the same way as `encode_g1_batch`
+pub fn encode_g2_batch(points: &[G2Affine]) -> Vec<u8> {
+    let mut bytes = vec![0u8; points.len() * G2_SERIALISED_SIZE];
+
+    ark_std::cfg_chunks_mut!(bytes, G2_SERIALISED_SIZE)
+        .zip(ark_std::cfg_iter!(points))
+        .for_each(|(point_bytes, point)| point_bytes.copy_from_slice(&serialize_g2(point)));
+
+    bytes
+}
+
+// Decodes consecutive compressed G1 points. Decompression takes a square root
+// per point, which dominates the cost, so the points are decoded in parallel.
+//
+// The points are decompressed one at a time rather than sharing field
+// inversions between them. A square root is an exponentiation, so there is no
+// work to share for G1, and for G2 the one inversion per point is small next to
+// its square roots, which did not seem worth a second copy of the arkworks code.
+//
+// The points are not checked to be in the prime order subgroup
+pub fn decode_g1_batch(bytes: &[u8]) -> Result<Vec<G1Affine>, SerialisationError> {
+    check_batch_len(bytes, G1_SERIALISED_SIZE)?;
+    metrics::record_bytes_deserialised(bytes.len());
+    ark_std::cfg_chunks!(bytes, G1_SERIALISED_SIZE)
+        .map(g1_from_slice)
+        .collect::<Result<_, _>>()
+        .map_err(record_decoding_failure)
+}
+// Decodes consecutive compressed G2 points in the same way as `decode_g1_batch`
+pub fn decode_g2_batch(bytes: &[u8]) -> Result<Vec<G2Affine>, SerialisationError> {
+    check_batch_len(bytes, G2_SERIALISED_SIZE)?;
+    metrics::record_bytes_deserialised(bytes.len());
+    ark_std::cfg_chunks!(bytes, G2_SERIALISED_SIZE)
+        .map(g2_from_slice)
+        .collect::<Result<_, _>>()
+        .map_err(record_decoding_failure)
+}
+
+fn record_decoding_failure<E>(error: E) -> E {
+    metrics::record_failure(FailureCategory::Deserialisation);
+    error
+}
+
+fn check_batch_len(bytes: &[u8], point_size: usize) -> Result<(), SerialisationError> {
+    if bytes.len() % point_size != 0 {
+        // Report the closest length which is a multiple of the point size
+        return Err(SerialisationError::InvalidLength {
+            expected: bytes.len() - bytes.len() % point_size,
+            got: bytes.len(),
+        });
+    }
+    Ok(())
+}
+
+// The point at infinity has a single encoding, where the sort flag and all of
+// the bits after the flags are zero. Other encodings are rejected so that every
+// point has exactly one encoding
+fn decode_infinity<A: AffineCurve>(
+    bytes: &[u8],
+    flags: &EncodingFlags,
+) -> Result<A, PointEncodingError> {
+    let is_canonical = !flags.is_lexographically_largest
+        && bytes[0] & 0b0001_1111 == 0
+        && bytes[1..].iter().all(|byte| *byte == 0);
+    if !is_canonical {
+        return Err(PointEncodingError::NonCanonicalInfinity);
+    }
+    Ok(A::zero())
 }
 
 struct EncodingFlags {
@@ -166,7 +284,6 @@ impl EncodingFlags {
 
         if self.is_compressed && !self.is_infinity && self.is_lexographically_largest {
             bytes[0] |= 1 << 5;
-            return;
         }
     }
 }
@@ -196,7 +313,6 @@ pub fn serialize_g2(p: &G2Affine) -> [u8; G2_SERIALISED_SIZE] {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_ec::AffineCurve;
     #[test]
     fn test_correct_g1() {
         let p = G1Affine::prime_subgroup_generator();
@@ -220,4 +336,84 @@ mod tests {
         let got = deserialize_g2(serialize_g2(&p2)).unwrap();
         assert_eq!(got, p2);
     }
+    #[test]
+    fn reject_non_canonical_infinity() {
+        let mut g1_bytes = serialize_g1(&G1Affine::default());
+        assert_eq!(deserialize_g1(g1_bytes), Some(G1Affine::default()));
+        g1_bytes[47] = 1;
+        assert_eq!(deserialize_g1(g1_bytes), None);
+
+        let mut g2_bytes = serialize_g2(&G2Affine::default());
+        assert_eq!(deserialize_g2(g2_bytes), Some(G2Affine::default()));
+        g2_bytes[0] |= 1 << 5;
+        assert_eq!(deserialize_g2(g2_bytes), None);
+    }
+
+    #[test]
+    fn decode_reports_the_reason() {
+        let mut bytes = serialize_g1(&G1Affine::prime_subgroup_generator());
+        bytes[0] &= 0b0111_1111;
+        assert_eq!(decode_g1(bytes), Err(PointEncodingError::Uncompressed));
+
+        let mut bytes = serialize_g1(&G1Affine::default());
+        bytes[47] = 1;
+        assert_eq!(
+            decode_g1(bytes),
+            Err(PointEncodingError::NonCanonicalInfinity)
+        );
+
+        // The x-coordinate is larger than the field modulus
+        let mut bytes = [0xff; G1_SERIALISED_SIZE];
+        bytes[0] = 0b1001_1111;
+        assert_eq!(
+            decode_g1(bytes),
+            Err(PointEncodingError::InvalidFieldElement)
+        );
+
+        // There is no point with x = 1, since 1 + 4 is not a square
+        let mut bytes = [0u8; G1_SERIALISED_SIZE];
+        bytes[0] = 0b1000_0000;
+        bytes[47] = 1;
+        assert_eq!(decode_g1(bytes), Err(PointEncodingError::NotOnCurve));
+
+        assert!(matches!(
+            g2_from_slice(&[0u8; G1_SERIALISED_SIZE]),
+            Err(SerialisationError::InvalidLength {
+                expected: G2_SERIALISED_SIZE,
+                got: G1_SERIALISED_SIZE
+            })
+        ));
+    }
+
+    #[test]
+    fn batch_roundtrip() {
+        let g1s = vec![
+            G1Affine::prime_subgroup_generator(),
+            G1Affine::default(),
+            -G1Affine::prime_subgroup_generator(),
+        ];
+        let bytes = encode_g1_batch(&g1s);
+        assert_eq!(
+            &bytes[G1_SERIALISED_SIZE..2 * G1_SERIALISED_SIZE],
+            &serialize_g1(&g1s[1])
+        );
+        assert_eq!(decode_g1_batch(&bytes).unwrap(), g1s);
+
+        let g2s = vec![G2Affine::prime_subgroup_generator(), G2Affine::default()];
+        assert_eq!(decode_g2_batch(&encode_g2_batch(&g2s)).unwrap(), g2s);
+
+        // The first invalid point is reported, as is a partial point
+        let mut invalid = bytes.clone();
+        invalid[2 * G1_SERIALISED_SIZE] &= 0b0111_1111;
+        assert!(matches!(
+            decode_g1_batch(&invalid),
+            Err(SerialisationError::InvalidPoint(
+                PointEncodingError::Uncompressed
+            ))
+        ));
+        assert!(matches!(
+            decode_g1_batch(&bytes[1..]),
+            Err(SerialisationError::InvalidLength { .. })
+        ));
+    }
 }
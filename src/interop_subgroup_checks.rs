@@ -21,7 +21,7 @@ pub mod g1 {
     fn endomorphism(p: &GroupAffine<Parameters>) -> GroupAffine<Parameters> {
         // Endomorphism of the points on the curve.
         // endomorphism_p(x,y) = (BETA * x, y) where BETA is a non-trivial cubic root of unity in Fq.
-        let mut res = (*p).clone();
+        let mut res = *p;
         res.x *= BETA;
         res
     }
@@ -109,12 +109,61 @@ pub mod g2 {
         res.x.frobenius_map(1);
         res.y.frobenius_map(1);
 
-        let tmp_x = res.x.clone();
+        let tmp_x = res.x;
 
-        res.x.c0 = -P_POWER_ENDOMORPHISM_COEFF_0.c1 * &tmp_x.c1;
-        res.x.c1 = P_POWER_ENDOMORPHISM_COEFF_0.c1 * &tmp_x.c0;
+        res.x.c0 = -P_POWER_ENDOMORPHISM_COEFF_0.c1 * tmp_x.c1;
+        res.x.c1 = P_POWER_ENDOMORPHISM_COEFF_0.c1 * tmp_x.c0;
         res.y *= P_POWER_ENDOMORPHISM_COEFF_1;
 
         res
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand, Zero};
+
+    // The fast checks should agree with the (slow) arkworks checks, which multiply by the group order
+
+    #[test]
+    fn g1_agrees_with_arkworks() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let point = G1Projective::prime_subgroup_generator()
+                .mul(Fr::rand(&mut rng).into_repr())
+                .into_affine();
+            assert!(super::g1::is_in_correct_subgroup_assuming_on_curve(&point));
+        }
+
+        let points_on_curve =
+            (1u64..50).filter_map(|x| G1Affine::get_point_from_x(Fq::from(x), false));
+        for point in points_on_curve {
+            assert_eq!(
+                super::g1::is_in_correct_subgroup_assuming_on_curve(&point),
+                point.is_in_correct_subgroup_assuming_on_curve()
+            );
+        }
+    }
+
+    #[test]
+    fn g2_agrees_with_arkworks() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let point = G2Projective::prime_subgroup_generator()
+                .mul(Fr::rand(&mut rng).into_repr())
+                .into_affine();
+            assert!(super::g2::is_in_correct_subgroup_assuming_on_curve(&point));
+        }
+
+        let points_on_curve = (1u64..50)
+            .filter_map(|x| G2Affine::get_point_from_x(Fq2::new(Fq::from(x), Fq::zero()), false));
+        for point in points_on_curve {
+            assert_eq!(
+                super::g2::is_in_correct_subgroup_assuming_on_curve(&point),
+                point.is_in_correct_subgroup_assuming_on_curve()
+            );
+        }
+    }
+}
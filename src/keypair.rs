@@ -1,8 +1,38 @@
-use ark_bls12_381::{Fr, G2Projective};
-use ark_ec::ProjectiveCurve;
-use ark_ff::{PrimeField, UniformRand};
+use crate::{
+    error::{DegenerateSecret, EntropyUnavailable, Error, VerificationError},
+    identity::Identity,
+    interop_hash_to_curve::{hash_to_g1, BLS_SIGNATURE_DST},
+    interop_point_encoding::{serialize_g1, serialize_g2},
+    serialisation::hex_string_to_bytes,
+};
+use ark_bls12_381::{Fr, G1Projective, G2Affine, G2Projective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
 use rand::Rng;
-use zeroize::ZeroizeOnDrop;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+
+#[cfg(feature = "secp256k1")]
+mod attestation;
+mod dice;
+#[cfg(feature = "secp256k1")]
+mod identity_key;
+#[cfg(feature = "secp256k1")]
+pub use attestation::{
+    AttestationMessage, ContributionAttestation, Eip712Domain, TypedData, TypedDataField,
+};
+pub use dice::{DicewareList, MIN_DICE_ENTROPY_BITS};
+#[cfg(feature = "secp256k1")]
+pub use identity_key::{
+    recover_address, verify_identity_signature, IdentityKey, IdentitySignature,
+};
+
+// Domain separation tag for deriving a private key from mixed entropy
+const MIXED_ENTROPY_DST: &[u8] = b"SMALL_POWERS_OF_TAU_MIXED_ENTROPY_V1";
+// The number of timings which are taken when collecting timing jitter
+#[cfg(not(target_arch = "wasm32"))]
+const JITTER_SAMPLES: usize = 256;
 
 #[derive(ZeroizeOnDrop)]
 pub struct PrivateKey {
@@ -17,15 +47,101 @@ impl PrivateKey {
     }
     // Creates a private key using entropy from a RNG
     pub fn rand<R: Rng>(mut rand: R) -> Self {
-        PrivateKey {
-            tau: Fr::rand(&mut rand),
+        // Zero and one are only sampled with negligible probability, but
+        // they are still rejected
+        loop {
+            if let Ok(private_key) = PrivateKey::from_fr(Fr::rand(&mut rand)) {
+                return private_key;
+            }
+        }
+    }
+    // Creates a private key using bytes, which are reduced modulo the group order
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DegenerateSecret> {
+        PrivateKey::from_fr(Fr::from_be_bytes_mod_order(bytes))
+    }
+    // Creates a private key using a 0x prefixed hex string
+    pub fn from_hex(hex_str: &str) -> Result<Self, Error> {
+        let bytes = hex_string_to_bytes(hex_str)?;
+        Ok(PrivateKey::from_bytes(&bytes)?)
+    }
+
+    // Creates a private key by hashing randomness from the OS, from `rng`, from
+    // `sources` and from timing jitter together, so that the key is only
+    // predictable if every one of them is.
+    //
+    // `sources` is entropy provided by the user, for example mouse movements or
+    // text which they typed.
+    //
+    // Panics if the OS cannot provide randomness, see `try_from_mixed_entropy`
+    pub fn from_mixed_entropy<R: Rng>(sources: &[&[u8]], rng: R) -> Self {
+        PrivateKey::try_from_mixed_entropy(sources, rng)
+            .expect("the OS could not provide randomness")
+    }
+
+    // Creates a private key in the same way as `from_mixed_entropy`, returning
+    // an error instead of panicking if the OS cannot provide randomness.
+    //
+    // In wasm builds the OS randomness comes from `crypto.getRandomValues`
+    pub fn try_from_mixed_entropy<R: Rng>(
+        sources: &[&[u8]],
+        mut rng: R,
+    ) -> Result<Self, EntropyUnavailable> {
+        let mut hasher = Sha256::new();
+        absorb(&mut hasher, MIXED_ENTROPY_DST);
+
+        let mut os_entropy = Zeroizing::new([0u8; 32]);
+        OsRng
+            .try_fill_bytes(&mut *os_entropy)
+            .map_err(|error| EntropyUnavailable(error.to_string()))?;
+        absorb(&mut hasher, &*os_entropy);
+
+        let mut rng_entropy = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(&mut *rng_entropy);
+        absorb(&mut hasher, &*rng_entropy);
+
+        absorb(&mut hasher, &(sources.len() as u64).to_le_bytes());
+        for source in sources {
+            absorb(&mut hasher, source);
+        }
+        absorb(&mut hasher, &timing_jitter());
+
+        let mut seed = Zeroizing::new([0u8; 32]);
+        seed.copy_from_slice(&hasher.finalize());
+        Ok(PrivateKey::from_seed(&seed))
+    }
+
+    // Derives a private key from a uniformly random seed. The seed is expanded
+    // to 64 bytes, so that reducing it modulo the group order gives a
+    // negligible bias
+    fn from_seed(seed: &[u8; 32]) -> Self {
+        let mut counter = 0u64;
+        loop {
+            let mut wide = Zeroizing::new([0u8; 64]);
+            for (index, half) in wide.chunks_mut(32).enumerate() {
+                let mut hasher = Sha256::new();
+                hasher.update(seed);
+                hasher.update(counter.to_le_bytes());
+                hasher.update([index as u8]);
+                half.copy_from_slice(&hasher.finalize());
+            }
+            if let Ok(private_key) = PrivateKey::from_bytes(&*wide) {
+                return private_key;
+            }
+            counter += 1;
         }
     }
-    // Creates a private key using bytes
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        PrivateKey {
-            tau: Fr::from_be_bytes_mod_order(bytes),
+
+    // Refuses the secrets which would produce an invalid contribution, so that
+    // this is caught before the SRS is updated rather than when it is verified
+    fn from_fr(tau: Fr) -> Result<Self, DegenerateSecret> {
+        let private_key = PrivateKey { tau };
+        if private_key.is_degenerate() {
+            return Err(DegenerateSecret);
         }
+        Ok(private_key)
+    }
+    pub(crate) fn is_degenerate(&self) -> bool {
+        self.tau.is_zero() || self.tau.is_one()
     }
 }
 
@@ -35,4 +151,172 @@ impl PrivateKey {
         let gen_g2 = G2Projective::prime_subgroup_generator();
         gen_g2.mul(self.tau.into_repr())
     }
+    // The public key in G1, which is the commitment of a `G1UpdateProof`
+    pub fn to_public_g1(&self) -> G1Projective {
+        G1Projective::prime_subgroup_generator().mul(self.tau.into_repr())
+    }
+    // Converts a private key into the 0x prefixed hex encoding of its
+    // compressed public key, which the ceremony specs refer to as the pot pubkey
+    pub fn to_pot_pubkey(self) -> String {
+        let mut pot_pubkey = hex::encode(serialize_g2(&self.to_public().into_affine()));
+        pot_pubkey.insert_str(0, "0x");
+        pot_pubkey
+    }
+
+    // Signs a message using the private key as the secret key of a BLS signature.
+    //
+    // In the ceremony, contributors sign their identity using tau so that the
+    // contribution is bound to them
+    pub fn sign(&self, message: &[u8]) -> G1Projective {
+        hash_to_g1(message, BLS_SIGNATURE_DST).mul(self.tau.into_repr())
+    }
+    // Signs a message and returns the 0x prefixed hex encoding of the compressed signature
+    pub fn sign_hex(&self, message: &[u8]) -> String {
+        let mut signature = hex::encode(serialize_g1(&self.sign(message).into_affine()));
+        signature.insert_str(0, "0x");
+        signature
+    }
+    // Signs the canonical encoding of the participant's identity
+    pub fn sign_identity(&self, identity: &Identity) -> G1Projective {
+        self.sign(identity.encode().as_bytes())
+    }
+}
+
+// Generates a private key from `rng` and passes it to `f`, for example
+//
+// let update_proof = with_fresh_secret(rand::thread_rng(), |key| srs.update(key));
+//
+// `PrivateKey` zeroises itself when dropped, so the key is zeroised when `f`
+// drops it or returns without giving it back. If `f` panics, the key is dropped
+// while unwinding, and with `panic = "abort"` the process ends instead. Copies
+// of tau that `f` makes, for example when raising it to the powers needed by an
+// update, are not zeroised, and neither is the state of `rng`
+pub fn with_fresh_secret<R: Rng, T, F: FnOnce(PrivateKey) -> T>(rng: R, f: F) -> T {
+    f(PrivateKey::rand(rng))
+}
+
+// Hashes `bytes` prefixed with their length, so that the boundaries between
+// inputs are unambiguous
+fn absorb(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+// Times a short computation repeatedly. The timings vary with the state of the
+// caches, the scheduler and other processes, which adds entropy that does not
+// come from any RNG
+#[cfg(not(target_arch = "wasm32"))]
+fn timing_jitter() -> Vec<u8> {
+    let mut timings = Vec::with_capacity(JITTER_SAMPLES * 4 + 8);
+    let mut state = 0u64;
+    for _ in 0..JITTER_SAMPLES {
+        let start = std::time::Instant::now();
+        for i in 0..64 {
+            state = state.rotate_left(7).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ i;
+        }
+        timings.extend_from_slice(&start.elapsed().subsec_nanos().to_le_bytes());
+    }
+    timings.extend_from_slice(&state.to_le_bytes());
+    timings
+}
+// `Instant` is not available on wasm32-unknown-unknown, so in the browser the
+// key is derived from the other sources only
+#[cfg(target_arch = "wasm32")]
+fn timing_jitter() -> Vec<u8> {
+    Vec::new()
+}
+
+// Verifies a signature which was created using `PrivateKey::sign_identity`
+pub fn verify_identity(
+    public_key: G2Projective,
+    identity: &Identity,
+    signature: G1Projective,
+) -> Result<(), VerificationError> {
+    verify_signature(public_key, identity.encode().as_bytes(), signature)
+}
+
+// Verifies a BLS signature which was created using `PrivateKey::sign`
+pub fn verify_signature(
+    public_key: G2Projective,
+    message: &[u8],
+    signature: G1Projective,
+) -> Result<(), VerificationError> {
+    let hashed_message = hash_to_g1(message, BLS_SIGNATURE_DST);
+    let gen_g2 = G2Affine::prime_subgroup_generator();
+
+    let p1 = ark_bls12_381::Bls12_381::pairing(signature, gen_g2);
+    let p2 = ark_bls12_381::Bls12_381::pairing(hashed_message, public_key);
+    if p1 != p2 {
+        return Err(VerificationError::InvalidSignature);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SerialisationError;
+
+    #[test]
+    fn pot_pubkey_of_one_is_generator() {
+        let private_key = PrivateKey::from_u64(1);
+        assert_eq!(private_key.to_pot_pubkey(), "0x93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8");
+
+        assert!(matches!(
+            PrivateKey::from_hex("01"),
+            Err(Error::Serialisation(SerialisationError::MissingHexPrefix))
+        ));
+    }
+
+    #[test]
+    fn mixed_entropy_keys_differ() {
+        use rand::SeedableRng;
+
+        // Even with the same user entropy and a fixed RNG, the OS randomness
+        // makes each key different
+        let rng = || rand_chacha::ChaCha20Rng::seed_from_u64(252);
+        let first = PrivateKey::from_mixed_entropy(&[&b"entropy"[..]], rng());
+        let second = PrivateKey::from_mixed_entropy(&[&b"entropy"[..]], rng());
+        assert_ne!(first.tau, second.tau);
+        assert!(!first.is_degenerate());
+    }
+
+    #[test]
+    fn update_with_fresh_secret() {
+        use crate::srs::{Parameters, SRS};
+
+        let starting_srs = SRS::new(Parameters::new(10, 2)).unwrap();
+        let mut srs = starting_srs.clone();
+        let update_proof = with_fresh_secret(rand::thread_rng(), |key| srs.update(key));
+        assert!(SRS::verify_update(&starting_srs, &srs, &update_proof, Fr::from(252u64)).is_ok());
+    }
+
+    #[test]
+    fn reject_degenerate_secrets() {
+        assert!(matches!(PrivateKey::from_bytes(&[]), Err(DegenerateSecret)));
+        assert!(matches!(
+            PrivateKey::from_bytes(&[1]),
+            Err(DegenerateSecret)
+        ));
+        assert!(matches!(
+            PrivateKey::from_hex("0x01"),
+            Err(Error::DegenerateSecret(DegenerateSecret))
+        ));
+        assert!(PrivateKey::from_bytes(&[2]).is_ok());
+    }
+
+    #[test]
+    fn sign_verify_smoke() {
+        let private_key = PrivateKey::from_u64(123456789);
+        let identity = b"eth|0x0000000000000000000000000000000000000000";
+
+        let signature = private_key.sign(identity);
+        let public_key = private_key.to_public();
+
+        assert!(verify_signature(public_key, identity, signature).is_ok());
+        assert_eq!(
+            verify_signature(public_key, b"git|1234|alice", signature),
+            Err(VerificationError::InvalidSignature)
+        );
+    }
 }
@@ -0,0 +1,227 @@
+// EIP-712 typed data for contribution attestations, which a participant signs
+// with their identity key, or with a wallet through `eth_signTypedData_v4`, to
+// attest that they made a contribution.
+//
+// An attestation binds the pot pubkey of the contribution to the digest of the
+// SRS which it produced and its position in the transcript, so that the
+// sequencer and auditors can check who made each contribution by recovering
+// the address which signed it.
+use crate::{
+    error::VerificationError,
+    interop_point_encoding::serialize_g2,
+    keypair::identity_key::{recover_preimage_signer, IdentityKey, IdentitySignature},
+    serialisation::bytes_to_hex_string,
+};
+use ark_bls12_381::G2Projective;
+use ark_ec::ProjectiveCurve;
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+use std::collections::BTreeMap;
+
+const DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId)";
+const ATTESTATION_TYPE: &str =
+    "ContributionAttestation(bytes potPubkey,bytes32 srsDigest,uint64 sequenceNumber)";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    pub name: String,
+    pub version: String,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContributionAttestation {
+    // The commitment to the secret, see `UpdateProof::commitment_to_secret`
+    pub pot_pubkey: G2Projective,
+    // The digest of the SRS after the contribution, see `Contribution::digest`
+    pub srs_digest: [u8; 32],
+    // The position of the contribution in the transcript
+    pub sequence_number: u64,
+}
+
+// The typed data which is passed to `eth_signTypedData_v4`, once serialised as json
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: BTreeMap<&'static str, Vec<TypedDataField>>,
+    pub primary_type: &'static str,
+    pub domain: Eip712Domain,
+    pub message: AttestationMessage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypedDataField {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+}
+
+// The attestation with its fields encoded as wallets expect them
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationMessage {
+    pub pot_pubkey: String,
+    pub srs_digest: String,
+    pub sequence_number: u64,
+}
+
+impl Eip712Domain {
+    pub fn new(name: &str, version: &str, chain_id: u64) -> Self {
+        Eip712Domain {
+            name: name.to_string(),
+            version: version.to_string(),
+            chain_id,
+        }
+    }
+
+    pub fn separator(&self) -> [u8; 32] {
+        let mut encoded = Vec::with_capacity(4 * 32);
+        encoded.extend_from_slice(&keccak256(DOMAIN_TYPE.as_bytes()));
+        encoded.extend_from_slice(&keccak256(self.name.as_bytes()));
+        encoded.extend_from_slice(&keccak256(self.version.as_bytes()));
+        encoded.extend_from_slice(&encode_uint(self.chain_id));
+        keccak256(&encoded)
+    }
+}
+
+impl ContributionAttestation {
+    // `hashStruct` of the attestation. Dynamic `bytes` are encoded as their
+    // hash and integers as 32 big endian bytes
+    pub fn struct_hash(&self) -> [u8; 32] {
+        let mut encoded = Vec::with_capacity(4 * 32);
+        encoded.extend_from_slice(&keccak256(ATTESTATION_TYPE.as_bytes()));
+        encoded.extend_from_slice(&keccak256(&self.pot_pubkey_bytes()));
+        encoded.extend_from_slice(&self.srs_digest);
+        encoded.extend_from_slice(&encode_uint(self.sequence_number));
+        keccak256(&encoded)
+    }
+
+    // The hash which is signed, keccak256(0x19 || 0x01 || domainSeparator || hashStruct)
+    pub fn signing_hash(&self, domain: &Eip712Domain) -> [u8; 32] {
+        keccak256(&self.signing_preimage(domain))
+    }
+
+    pub fn sign(&self, domain: &Eip712Domain, key: &IdentityKey) -> IdentitySignature {
+        key.sign_preimage(&self.signing_preimage(domain))
+    }
+
+    // Returns the address which signed the attestation
+    pub fn recover_signer(
+        &self,
+        domain: &Eip712Domain,
+        signature: &IdentitySignature,
+    ) -> Result<[u8; 20], VerificationError> {
+        recover_preimage_signer(&self.signing_preimage(domain), signature)
+    }
+
+    // Checks that the attestation was signed by the key with the given address
+    pub fn verify(
+        &self,
+        domain: &Eip712Domain,
+        address: &[u8; 20],
+        signature: &IdentitySignature,
+    ) -> Result<(), VerificationError> {
+        if self.recover_signer(domain, signature)? != *address {
+            return Err(VerificationError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    pub fn typed_data(&self, domain: &Eip712Domain) -> TypedData {
+        let field = |name, ty| TypedDataField { name, ty };
+
+        let mut types = BTreeMap::new();
+        types.insert(
+            "EIP712Domain",
+            vec![
+                field("name", "string"),
+                field("version", "string"),
+                field("chainId", "uint256"),
+            ],
+        );
+        types.insert(
+            "ContributionAttestation",
+            vec![
+                field("potPubkey", "bytes"),
+                field("srsDigest", "bytes32"),
+                field("sequenceNumber", "uint64"),
+            ],
+        );
+
+        TypedData {
+            types,
+            primary_type: "ContributionAttestation",
+            domain: domain.clone(),
+            message: AttestationMessage {
+                pot_pubkey: bytes_to_hex_string(&self.pot_pubkey_bytes()),
+                srs_digest: bytes_to_hex_string(&self.srs_digest),
+                sequence_number: self.sequence_number,
+            },
+        }
+    }
+
+    fn pot_pubkey_bytes(&self) -> [u8; 96] {
+        serialize_g2(&self.pot_pubkey.into_affine())
+    }
+
+    // The signer hashes this using keccak256
+    fn signing_preimage(&self, domain: &Eip712Domain) -> [u8; 66] {
+        let mut preimage = [0u8; 66];
+        preimage[..2].copy_from_slice(&[0x19, 0x01]);
+        preimage[2..34].copy_from_slice(&domain.separator());
+        preimage[34..].copy_from_slice(&self.struct_hash());
+        preimage
+    }
+}
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    Keccak256::digest(bytes).into()
+}
+
+fn encode_uint(int: u64) -> [u8; 32] {
+    let mut encoded = [0u8; 32];
+    encoded[24..].copy_from_slice(&int.to_be_bytes());
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::PrivateKey;
+
+    #[test]
+    fn sign_and_verify_attestation() {
+        let domain = Eip712Domain::new("Small Powers of Tau", "1", 1);
+        let key = IdentityKey::generate(rand_core::OsRng);
+        let attestation = ContributionAttestation {
+            pot_pubkey: PrivateKey::from_u64(2).to_public(),
+            srs_digest: [7u8; 32],
+            sequence_number: 3,
+        };
+
+        let signature = attestation.sign(&domain, &key);
+        assert_eq!(
+            attestation.recover_signer(&domain, &signature),
+            Ok(key.address())
+        );
+        assert!(attestation
+            .verify(&domain, &key.address(), &signature)
+            .is_ok());
+
+        // Changing any field or the domain changes the signer
+        let moved = ContributionAttestation {
+            sequence_number: 4,
+            ..attestation
+        };
+        assert!(moved.verify(&domain, &key.address(), &signature).is_err());
+        let other_chain = Eip712Domain::new("Small Powers of Tau", "1", 5);
+        assert!(attestation
+            .verify(&other_chain, &key.address(), &signature)
+            .is_err());
+
+        let typed_data = attestation.typed_data(&domain);
+        assert_eq!(typed_data.primary_type, "ContributionAttestation");
+        assert_eq!(typed_data.message.sequence_number, 3);
+    }
+}
@@ -0,0 +1,191 @@
+// Deriving a private key from physical dice rolls or diceware words, for
+// participants who do not trust the randomness of any computer.
+//
+// Only the dice are used, so the key is exactly as unpredictable as the rolls.
+// Inputs with less than `MIN_DICE_ENTROPY_BITS` of entropy are refused, which
+// is 50 rolls of a six sided die, or 10 words from a list of 7776 words such as
+// the EFF large word list. No word list is bundled, the participant supplies
+// the list which they rolled against.
+use super::{absorb, PrivateKey};
+use crate::error::DiceError;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use zeroize::Zeroizing;
+
+pub const MIN_DICE_ENTROPY_BITS: u32 = 128;
+
+// Domain separation tags, so that dice rolls and diceware words which happen
+// to have the same encoding give different keys
+const DICE_DST: &[u8] = b"SMALL_POWERS_OF_TAU_DICE_V1";
+const DICEWARE_DST: &[u8] = b"SMALL_POWERS_OF_TAU_DICEWARE_V1";
+
+// A diceware word list, which maps each word to its position in the list
+#[derive(Debug, Clone)]
+pub struct DicewareList {
+    indices: HashMap<String, u32>,
+}
+
+impl DicewareList {
+    // Parses a list with one word on each line, optionally preceded by the
+    // rolls which select it, as in "11111\tabacus". Blank lines are skipped,
+    // and words are compared ignoring case
+    pub fn parse(list: &str) -> Result<Self, DiceError> {
+        let mut indices = HashMap::new();
+        for line in list.lines() {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let word = match fields.as_slice() {
+                [] => continue,
+                [word] => word,
+                [rolls, word] if rolls.chars().all(|roll| ('1'..='6').contains(&roll)) => word,
+                _ => {
+                    return Err(DiceError::InvalidWordList(format!(
+                        "unexpected line '{}'",
+                        line
+                    )))
+                }
+            };
+
+            let index = indices.len() as u32;
+            if indices.insert(word.to_lowercase(), index).is_some() {
+                return Err(DiceError::InvalidWordList(format!(
+                    "'{}' appears more than once",
+                    word
+                )));
+            }
+        }
+
+        if indices.len() < 2 {
+            return Err(DiceError::InvalidWordList(
+                "the list needs at least two words".to_string(),
+            ));
+        }
+        Ok(DicewareList { indices })
+    }
+
+    pub fn num_words(&self) -> usize {
+        self.indices.len()
+    }
+    // The entropy of each word which is chosen uniformly from the list, in bits
+    pub fn bits_per_word(&self) -> f64 {
+        (self.num_words() as f64).log2()
+    }
+}
+
+impl PrivateKey {
+    // Derives a private key from the rolls of a six sided die, given as the
+    // digits 1 to 6. Whitespace and commas between the rolls are ignored
+    pub fn from_dice_rolls(rolls: &str) -> Result<Self, DiceError> {
+        let rolls = Zeroizing::new(
+            rolls
+                .chars()
+                .filter(|roll| !roll.is_whitespace() && *roll != ',')
+                .map(|roll| match roll {
+                    '1'..='6' => Ok(roll as u8 - b'0'),
+                    _ => Err(DiceError::InvalidRoll(roll)),
+                })
+                .collect::<Result<Vec<u8>, _>>()?,
+        );
+        check_entropy(rolls.len(), 6f64.log2())?;
+
+        Ok(derive(DICE_DST, &rolls))
+    }
+
+    // Derives a private key from words which were chosen by rolling dice
+    // against `list`, separated by whitespace
+    pub fn from_diceware(words: &str, list: &DicewareList) -> Result<Self, DiceError> {
+        // The size of the list is included, so that the same positions in
+        // lists of different sizes give different keys
+        let mut encoded = Zeroizing::new((list.num_words() as u64).to_le_bytes().to_vec());
+        let mut num_words = 0;
+        for word in words.split_whitespace() {
+            let index = list
+                .indices
+                .get(&word.to_lowercase())
+                .ok_or_else(|| DiceError::UnknownWord(word.to_string()))?;
+            encoded.extend_from_slice(&index.to_le_bytes());
+            num_words += 1;
+        }
+        check_entropy(num_words, list.bits_per_word())?;
+
+        Ok(derive(DICEWARE_DST, &encoded))
+    }
+}
+
+fn check_entropy(num_symbols: usize, bits_per_symbol: f64) -> Result<(), DiceError> {
+    let bits = (num_symbols as f64 * bits_per_symbol).floor() as u32;
+    if bits < MIN_DICE_ENTROPY_BITS {
+        return Err(DiceError::NotEnoughEntropy {
+            bits,
+            needed: MIN_DICE_ENTROPY_BITS,
+        });
+    }
+    Ok(())
+}
+
+fn derive(dst: &[u8], input: &[u8]) -> PrivateKey {
+    let mut hasher = Sha256::new();
+    absorb(&mut hasher, dst);
+    absorb(&mut hasher, input);
+
+    let mut seed = Zeroizing::new([0u8; 32]);
+    seed.copy_from_slice(&hasher.finalize());
+    PrivateKey::from_seed(&seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dice_rolls() {
+        let rolls = "1234561234, 5612345612 3456123456 1234561234 5612345612";
+        let key = PrivateKey::from_dice_rolls(rolls).unwrap();
+        assert_eq!(
+            key.tau,
+            PrivateKey::from_dice_rolls(&rolls.replace([',', ' '], ""))
+                .unwrap()
+                .tau
+        );
+
+        assert_eq!(
+            PrivateKey::from_dice_rolls(&rolls[1..]).err(),
+            Some(DiceError::NotEnoughEntropy {
+                bits: 126,
+                needed: 128
+            })
+        );
+        assert_eq!(
+            PrivateKey::from_dice_rolls(&rolls.replacen('6', "7", 1)).err(),
+            Some(DiceError::InvalidRoll('7'))
+        );
+    }
+
+    #[test]
+    fn diceware_words() {
+        let list: String = (0..7776).map(|i| format!("word{}\n", i)).collect();
+        let list = DicewareList::parse(&list).unwrap();
+        assert_eq!(list.num_words(), 7776);
+
+        let words = "word1 word20 word300 word4000 word5 word60 word700 word7000 word9 word10";
+        let key = PrivateKey::from_diceware(words, &list).unwrap();
+        assert_eq!(
+            key.tau,
+            PrivateKey::from_diceware(&words.to_uppercase(), &list)
+                .unwrap()
+                .tau
+        );
+
+        assert!(matches!(
+            PrivateKey::from_diceware("word1 word2", &list),
+            Err(DiceError::NotEnoughEntropy { .. })
+        ));
+        assert_eq!(
+            PrivateKey::from_diceware("word1 abacus", &list).err(),
+            Some(DiceError::UnknownWord("abacus".to_string()))
+        );
+
+        assert!(DicewareList::parse("11111\tabacus\n11112\tabdomen").is_ok());
+        assert!(DicewareList::parse("abacus\nAbacus").is_err());
+        assert!(DicewareList::parse("11117 abacus\n11112 abdomen").is_err());
+    }
+}
@@ -0,0 +1,178 @@
+// Ethereum style secp256k1 keys, which identify a participant across the
+// ceremony, for example when signing their contribution or a receipt.
+//
+// Unlike the secret tau, an identity key is meant to be kept. Signatures follow
+// `personal_sign` (EIP-191), so the same messages can be signed by a wallet, and
+// are checked against an address rather than a public key, since that is how
+// participants are known on Ethereum.
+use crate::{
+    error::{SerialisationError, VerificationError},
+    identity::Identity,
+    serialisation::{bytes_to_hex_string, hex_string_to_bytes},
+};
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use rand_core::{CryptoRng, RngCore};
+
+pub struct IdentityKey {
+    signing_key: SigningKey,
+}
+
+// A recoverable signature encoded as r || s || v, where v is 27 or 28
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdentitySignature(pub [u8; 65]);
+
+impl IdentityKey {
+    pub fn generate<R: RngCore + CryptoRng>(rng: R) -> Self {
+        IdentityKey {
+            signing_key: SigningKey::random(rng),
+        }
+    }
+    // Imports a 0x prefixed hex encoded secret key, as exported by most wallets
+    pub fn from_hex(hex_str: &str) -> Result<Self, SerialisationError> {
+        let bytes = hex_string_to_bytes(hex_str)?;
+        let signing_key =
+            SigningKey::from_bytes(&bytes).map_err(|_| SerialisationError::InvalidSecp256k1)?;
+        Ok(IdentityKey { signing_key })
+    }
+    pub fn to_hex(&self) -> String {
+        bytes_to_hex_string(&self.signing_key.to_bytes())
+    }
+
+    // The Ethereum address of the key
+    pub fn address(&self) -> [u8; 20] {
+        address_of(&self.signing_key.verifying_key())
+    }
+    pub fn address_hex(&self) -> String {
+        bytes_to_hex_string(&self.address())
+    }
+    pub fn identity(&self) -> Identity {
+        Identity::Ethereum(self.address())
+    }
+
+    // Signs `message` as `personal_sign` would
+    pub fn sign(&self, message: &[u8]) -> IdentitySignature {
+        self.sign_preimage(&eip_191_message(message))
+    }
+    // Signs the keccak256 hash of `preimage`
+    pub(crate) fn sign_preimage(&self, preimage: &[u8]) -> IdentitySignature {
+        let signature: recoverable::Signature = self.signing_key.sign(preimage);
+
+        let mut bytes = [0u8; 65];
+        bytes.copy_from_slice(signature.as_ref());
+        bytes[64] += 27;
+        IdentitySignature(bytes)
+    }
+}
+
+impl IdentitySignature {
+    pub fn to_hex(&self) -> String {
+        bytes_to_hex_string(&self.0)
+    }
+    pub fn from_hex(hex_str: &str) -> Result<Self, SerialisationError> {
+        let bytes = hex_string_to_bytes(hex_str)?;
+        let bytes: [u8; 65] =
+            bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| SerialisationError::InvalidLength {
+                    expected: 65,
+                    got: bytes.len(),
+                })?;
+        Ok(IdentitySignature(bytes))
+    }
+}
+
+// Returns the address of the key which signed `message`
+pub fn recover_address(
+    message: &[u8],
+    signature: &IdentitySignature,
+) -> Result<[u8; 20], VerificationError> {
+    recover_preimage_signer(&eip_191_message(message), signature)
+}
+
+// Returns the address of the key which signed the keccak256 hash of `preimage`
+pub(crate) fn recover_preimage_signer(
+    preimage: &[u8],
+    signature: &IdentitySignature,
+) -> Result<[u8; 20], VerificationError> {
+    // Wallets use either 0 and 1 or 27 and 28 for v
+    let mut bytes = signature.0;
+    if bytes[64] >= 27 {
+        bytes[64] -= 27;
+    }
+
+    let signature = recoverable::Signature::try_from(&bytes[..])
+        .map_err(|_| VerificationError::InvalidSignature)?;
+    let verifying_key = signature
+        .recover_verifying_key(preimage)
+        .map_err(|_| VerificationError::InvalidSignature)?;
+    Ok(address_of(&verifying_key))
+}
+
+// Checks that `message` was signed by the key with the given address
+pub fn verify_identity_signature(
+    address: &[u8; 20],
+    message: &[u8],
+    signature: &IdentitySignature,
+) -> Result<(), VerificationError> {
+    if recover_address(message, signature)? != *address {
+        return Err(VerificationError::InvalidSignature);
+    }
+    Ok(())
+}
+
+// The message which is hashed for `personal_sign`. The signer hashes it using keccak256
+fn eip_191_message(message: &[u8]) -> Vec<u8> {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    prefixed
+}
+
+// The last 20 bytes of the keccak256 hash of the uncompressed public key,
+// without its 0x04 prefix
+fn address_of(verifying_key: &k256::ecdsa::VerifyingKey) -> [u8; 20] {
+    use sha3::{Digest, Keccak256};
+
+    let public_key = verifying_key.to_encoded_point(false);
+    let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_and_recover() {
+        // The first account of the hardhat and anvil test mnemonic
+        let key = IdentityKey::from_hex(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        assert_eq!(
+            key.address_hex(),
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+
+        let message = b"git|1234|alice";
+        let signature = key.sign(message);
+        assert!(matches!(signature.0[64], 27 | 28));
+        assert!(verify_identity_signature(&key.address(), message, &signature).is_ok());
+
+        let signature = IdentitySignature::from_hex(&signature.to_hex()).unwrap();
+        assert_eq!(
+            verify_identity_signature(&key.address(), b"git|1234|bob", &signature),
+            Err(VerificationError::InvalidSignature)
+        );
+
+        let other = IdentityKey::generate(rand_core::OsRng);
+        assert_eq!(
+            verify_identity_signature(&other.address(), message, &signature),
+            Err(VerificationError::InvalidSignature)
+        );
+    }
+}
@@ -0,0 +1,344 @@
+// Committing to polynomials using an SRS, and opening the commitments with the
+// KZG polynomial commitment scheme.
+//
+// Polynomials are given by their coefficients, lowest degree first. Besides
+// opening a polynomial at a single point, a polynomial can be opened at several
+// points using one proof, and many single point openings, for example of the
+// blobs in a block, can be verified together using one pairing check.
+use crate::{error::KzgError, srs::SRS, verification::pairing_product_is_one};
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, Zero};
+
+// A claim that the polynomial committed to by `commitment` evaluates to `value`
+// at `point`, see `open`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opening {
+    pub commitment: G1Projective,
+    pub point: Fr,
+    pub value: Fr,
+    pub proof: G1Projective,
+}
+
+pub fn commit(srs: &SRS, polynomial: &[Fr]) -> Result<G1Projective, KzgError> {
+    check_degree(srs, polynomial)?;
+    Ok(msm(&srs.g1_elements()[..polynomial.len()], polynomial))
+}
+
+// Opens `polynomial` at `point`, returning its value there and the proof, which
+// is a commitment to (p(X) - p(z)) / (X - z)
+pub fn open(srs: &SRS, polynomial: &[Fr], point: Fr) -> Result<(Fr, G1Projective), KzgError> {
+    check_degree(srs, polynomial)?;
+    let (quotient, value) = divide_by_linear(polynomial, point);
+    Ok((value, commit(srs, &quotient)?))
+}
+
+// Checks that e(C - [y] + z * proof, [1]) = e(proof, [tau])
+pub fn verify(srs: &SRS, opening: &Opening) -> bool {
+    let Opening {
+        commitment,
+        point,
+        value,
+        proof,
+    } = *opening;
+
+    let lhs = commitment - G1Affine::prime_subgroup_generator().mul(value.into_repr())
+        + proof.mul(point.into_repr());
+    pairings_equal(
+        lhs,
+        G2Affine::prime_subgroup_generator(),
+        proof,
+        srs.g2_elements()[1],
+    )
+}
+
+// Verifies every opening using a single pairing check, by combining them with
+// the powers of `random_element`. As with the other batched checks, the random
+// element must not be known to whoever created the openings
+pub fn verify_batch(srs: &SRS, openings: &[Opening], random_element: Fr) -> bool {
+    // Every opening after the first is ignored if the random element is zero,
+    // so we return false in this case, and when there is nothing to verify
+    if random_element.is_zero() || openings.is_empty() {
+        return false;
+    }
+
+    let mut powers = Vec::with_capacity(openings.len());
+    let mut power = Fr::one();
+    for _ in openings {
+        powers.push(power);
+        power *= random_element;
+    }
+
+    let commitments: Vec<_> = openings.iter().map(|opening| opening.commitment).collect();
+    let proofs: Vec<_> = openings.iter().map(|opening| opening.proof).collect();
+    let commitments = G1Projective::batch_normalization_into_affine(&commitments);
+    let proofs = G1Projective::batch_normalization_into_affine(&proofs);
+
+    // sum_i r^i * (C_i - [y_i] + z_i * proof_i) and sum_i r^i * proof_i
+    let shifted_powers: Vec<_> = openings
+        .iter()
+        .zip(&powers)
+        .map(|(opening, power)| opening.point * power)
+        .collect();
+    let combined_value: Fr = openings
+        .iter()
+        .zip(&powers)
+        .map(|(opening, power)| opening.value * power)
+        .sum();
+    let lhs = msm(&commitments, &powers) + msm(&proofs, &shifted_powers)
+        - G1Affine::prime_subgroup_generator().mul(combined_value.into_repr());
+    let rhs = msm(&proofs, &powers);
+
+    pairings_equal(
+        lhs,
+        G2Affine::prime_subgroup_generator(),
+        rhs,
+        srs.g2_elements()[1],
+    )
+}
+
+// Opens `polynomial` at every point in `points` using one proof, which is a
+// commitment to (p(X) - I(X)) / Z(X), where Z vanishes on the points and I
+// interpolates the values. Returns the values in the order of the points
+pub fn open_multi(
+    srs: &SRS,
+    polynomial: &[Fr],
+    points: &[Fr],
+) -> Result<(Vec<Fr>, G1Projective), KzgError> {
+    check_degree(srs, polynomial)?;
+    check_num_points(srs, points)?;
+
+    let values: Vec<_> = points
+        .iter()
+        .map(|point| evaluate(polynomial, *point))
+        .collect();
+    let interpolation = interpolate(points, &values)?;
+
+    let mut numerator = polynomial.to_vec();
+    if numerator.len() < interpolation.len() {
+        numerator.resize(interpolation.len(), Fr::zero());
+    }
+    for (coefficient, interpolated) in numerator.iter_mut().zip(&interpolation) {
+        *coefficient -= interpolated;
+    }
+    let quotient = divide_by_monic(&numerator, &vanishing_polynomial(points));
+
+    Ok((values, commit(srs, &quotient)?))
+}
+
+// Checks that e(C - [I(tau)], [1]) = e(proof, [Z(tau)]), see `open_multi`. The
+// SRS needs more G2 powers than there are points
+pub fn verify_multi(
+    srs: &SRS,
+    commitment: G1Projective,
+    points: &[Fr],
+    values: &[Fr],
+    proof: G1Projective,
+) -> Result<bool, KzgError> {
+    check_num_points(srs, points)?;
+    if points.len() != values.len() {
+        return Err(KzgError::ValueCountMismatch {
+            expected: points.len(),
+            got: values.len(),
+        });
+    }
+
+    let interpolation = commit(srs, &interpolate(points, values)?)?;
+    let vanishing = vanishing_polynomial(points);
+    let vanishing = msm(&srs.g2_elements()[..vanishing.len()], &vanishing);
+
+    Ok(pairings_equal(
+        commitment - interpolation,
+        G2Affine::prime_subgroup_generator(),
+        proof,
+        vanishing.into_affine(),
+    ))
+}
+
+fn check_degree(srs: &SRS, polynomial: &[Fr]) -> Result<(), KzgError> {
+    let max = srs.g1_elements().len();
+    if polynomial.len() > max {
+        return Err(KzgError::TooManyCoefficients {
+            max,
+            got: polynomial.len(),
+        });
+    }
+    Ok(())
+}
+
+// The vanishing polynomial has one more coefficient than there are points
+fn check_num_points(srs: &SRS, points: &[Fr]) -> Result<(), KzgError> {
+    let max = srs.g2_elements().len() - 1;
+    if points.len() > max {
+        return Err(KzgError::TooManyPoints {
+            max,
+            got: points.len(),
+        });
+    }
+    Ok(())
+}
+
+// Checks that e(a, b) = e(c, d)
+fn pairings_equal(a: G1Projective, b: G2Affine, c: G1Projective, d: G2Affine) -> bool {
+    pairing_product_is_one([
+        &((-a).into_affine().into(), b.into()),
+        &(c.into_affine().into(), d.into()),
+    ])
+}
+
+fn msm<A: AffineCurve<ScalarField = Fr>>(bases: &[A], scalars: &[Fr]) -> A::Projective {
+    if scalars.is_empty() {
+        return A::Projective::zero();
+    }
+    let scalars: Vec<_> = scalars.iter().map(|scalar| scalar.into_repr()).collect();
+    VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+}
+
+fn evaluate(polynomial: &[Fr], point: Fr) -> Fr {
+    polynomial
+        .iter()
+        .rev()
+        .fold(Fr::zero(), |value, coefficient| value * point + coefficient)
+}
+
+// Divides by X - z, returning the quotient and the remainder, which is p(z)
+fn divide_by_linear(polynomial: &[Fr], point: Fr) -> (Vec<Fr>, Fr) {
+    let mut quotient = vec![Fr::zero(); polynomial.len().saturating_sub(1)];
+    let mut remainder = Fr::zero();
+    for (i, coefficient) in polynomial.iter().enumerate().rev() {
+        remainder = remainder * point + coefficient;
+        if i > 0 {
+            quotient[i - 1] = remainder;
+        }
+    }
+    (quotient, remainder)
+}
+
+// Divides by a polynomial whose leading coefficient is one, discarding the remainder
+fn divide_by_monic(numerator: &[Fr], divisor: &[Fr]) -> Vec<Fr> {
+    let degree = divisor.len() - 1;
+    if numerator.len() <= degree {
+        return Vec::new();
+    }
+
+    let mut remainder = numerator.to_vec();
+    let mut quotient = vec![Fr::zero(); numerator.len() - degree];
+    for i in (0..quotient.len()).rev() {
+        let coefficient = remainder[i + degree];
+        quotient[i] = coefficient;
+        for (j, divisor_coefficient) in divisor.iter().enumerate() {
+            remainder[i + j] -= coefficient * divisor_coefficient;
+        }
+    }
+    quotient
+}
+
+// Returns the product of X - z over the points
+fn vanishing_polynomial(points: &[Fr]) -> Vec<Fr> {
+    let mut vanishing = vec![Fr::one()];
+    for point in points {
+        // Multiply by X, then subtract z times the previous polynomial
+        vanishing.insert(0, Fr::zero());
+        for i in 0..vanishing.len() - 1 {
+            let shifted = vanishing[i + 1] * point;
+            vanishing[i] -= shifted;
+        }
+    }
+    vanishing
+}
+
+// Returns the polynomial of degree less than the number of points which takes
+// each value at its point, using the Lagrange basis
+fn interpolate(points: &[Fr], values: &[Fr]) -> Result<Vec<Fr>, KzgError> {
+    let vanishing = vanishing_polynomial(points);
+    let mut interpolation = vec![Fr::zero(); points.len()];
+    for (point, value) in points.iter().zip(values) {
+        let (basis, _) = divide_by_linear(&vanishing, *point);
+        let scale = evaluate(&basis, *point)
+            .inverse()
+            .ok_or(KzgError::RepeatedPoint)?
+            * value;
+        for (coefficient, basis_coefficient) in interpolation.iter_mut().zip(&basis) {
+            *coefficient += scale * basis_coefficient;
+        }
+    }
+    Ok(interpolation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keypair::PrivateKey, srs::Parameters};
+
+    fn setup() -> (SRS, Vec<Fr>) {
+        let mut srs = SRS::new(Parameters::new(16, 4)).unwrap();
+        srs.update(PrivateKey::from_u64(1234));
+        let polynomial = (1..=10u64).map(Fr::from).collect();
+        (srs, polynomial)
+    }
+
+    #[test]
+    fn open_and_verify() {
+        let (srs, polynomial) = setup();
+        let commitment = commit(&srs, &polynomial).unwrap();
+
+        let mut openings = Vec::new();
+        for point in [3u64, 5, 8] {
+            let point = Fr::from(point);
+            let (value, proof) = open(&srs, &polynomial, point).unwrap();
+            assert_eq!(value, evaluate(&polynomial, point));
+            let opening = Opening {
+                commitment,
+                point,
+                value,
+                proof,
+            };
+            assert!(verify(&srs, &opening));
+            openings.push(opening);
+        }
+        assert!(verify_batch(&srs, &openings, Fr::from(99u64)));
+
+        openings[1].value += Fr::one();
+        assert!(!verify(&srs, &openings[1]));
+        assert!(!verify_batch(&srs, &openings, Fr::from(99u64)));
+        // With a zero random element only the first opening would be checked
+        assert!(!verify_batch(&srs, &openings, Fr::zero()));
+        assert!(!verify_batch(&srs, &[], Fr::from(99u64)));
+
+        assert_eq!(
+            commit(&srs, &[Fr::one(); 17]),
+            Err(KzgError::TooManyCoefficients { max: 16, got: 17 })
+        );
+    }
+
+    #[test]
+    fn open_and_verify_multi() {
+        let (srs, polynomial) = setup();
+        let commitment = commit(&srs, &polynomial).unwrap();
+
+        let points: Vec<_> = [2u64, 7, 11].into_iter().map(Fr::from).collect();
+        let (mut values, proof) = open_multi(&srs, &polynomial, &points).unwrap();
+        assert_eq!(values[1], evaluate(&polynomial, points[1]));
+        assert_eq!(
+            verify_multi(&srs, commitment, &points, &values, proof),
+            Ok(true)
+        );
+
+        values[2] += Fr::one();
+        assert_eq!(
+            verify_multi(&srs, commitment, &points, &values, proof),
+            Ok(false)
+        );
+
+        let repeated = [points[0], points[0]];
+        assert_eq!(
+            open_multi(&srs, &polynomial, &repeated),
+            Err(KzgError::RepeatedPoint)
+        );
+        let too_many: Vec<_> = (0..4u64).map(Fr::from).collect();
+        assert_eq!(
+            open_multi(&srs, &polynomial, &too_many),
+            Err(KzgError::TooManyPoints { max: 3, got: 4 })
+        );
+    }
+}
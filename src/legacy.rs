@@ -0,0 +1,139 @@
+// Reading SRS files which were written by earlier versions of this crate, and
+// migrating them to the versioned layout of `FileAccumulator`.
+//
+// Those files are the output of `SRS::write_to`, the compressed G1 powers
+// followed by the compressed G2 powers, with no header. When the parameters
+// are not known, they are inferred from the position of the G2 generator,
+// which is always the degree-0 G2 power and so directly follows the G1 powers.
+use crate::{
+    accumulator::{self, Accumulator, FileAccumulator},
+    error::{Error, SerialisationError},
+    interop_point_encoding::{serialize_g2, G1_SERIALISED_SIZE, G2_SERIALISED_SIZE},
+    section::{G1Section, G2Section},
+    serialisation::SubgroupCheck,
+    srs::{Parameters, SRS},
+};
+use ark_bls12_381::{Fr, G2Affine};
+use ark_ec::AffineCurve;
+use std::io::{Read, Seek, Write};
+
+// Infers the number of G1 and G2 powers in a legacy file
+pub fn infer_parameters(bytes: &[u8]) -> Result<Parameters, SerialisationError> {
+    let g2_generator = serialize_g2(&G2Affine::prime_subgroup_generator());
+
+    // If every G2 power is the generator, then it is found after every G1
+    // power, so the first match is the start of the G2 powers
+    (1..=bytes.len() / G1_SERIALISED_SIZE)
+        .find(|num_g1| {
+            let g2_bytes = &bytes[num_g1 * G1_SERIALISED_SIZE..];
+            g2_bytes.len() >= G2_SERIALISED_SIZE
+                && g2_bytes.len() % G2_SERIALISED_SIZE == 0
+                && g2_bytes[..G2_SERIALISED_SIZE] == g2_generator
+        })
+        .map(|num_g1| {
+            let num_g2 = (bytes.len() - num_g1 * G1_SERIALISED_SIZE) / G2_SERIALISED_SIZE;
+            Parameters::new(num_g1, num_g2)
+        })
+        .ok_or(SerialisationError::UnrecognisedLegacyFile)
+}
+
+// Reads a legacy file, inferring its parameters if they are not given
+pub fn read_legacy<R: Read>(
+    reader: &mut R,
+    parameters: Option<Parameters>,
+    subgroup_check: SubgroupCheck,
+) -> Result<SRS, Error> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(SerialisationError::Io)?;
+
+    let parameters = match parameters {
+        Some(parameters) => parameters,
+        None => infer_parameters(&bytes)?,
+    };
+    SRS::deserialise_bytes(&bytes, parameters, subgroup_check)
+}
+
+// Rewrites a legacy file into `file` as a `FileAccumulator`. The migrated
+// accumulator is read back and verified, so a legacy file which is not a
+// valid SRS, or a write which did not reach the disk intact, is an error
+pub fn migrate<R: Read, F: Read + Write + Seek>(
+    legacy: &mut R,
+    parameters: Option<Parameters>,
+    file: F,
+    chunk_size: usize,
+    random_element: Fr,
+) -> Result<FileAccumulator<F>, Error> {
+    // The subgroup check is done by `accumulator::verify`
+    let srs = read_legacy(legacy, parameters, SubgroupCheck::None)?;
+
+    let parameters = Parameters::new(srs.g1_elements().len(), srs.g2_elements().len());
+    let mut migrated = FileAccumulator::create(file, parameters, chunk_size)?;
+    migrated.write_g1(&G1Section::new(0, srs.g1_powers().to_vec()))?;
+    migrated.write_g2(&G2Section::new(0, srs.g2_powers().to_vec()))?;
+
+    accumulator::verify(&mut migrated, random_element, chunk_size)?;
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::VerificationError, keypair::PrivateKey};
+    use std::io::Cursor;
+
+    #[test]
+    fn migrate_legacy_file() {
+        let mut srs = SRS::new(Parameters::new(16, 4)).unwrap();
+        let fresh = srs.clone();
+        srs.update(PrivateKey::from_u64(5));
+
+        // Every G2 power of a fresh SRS is the generator
+        for srs in [&fresh, &srs] {
+            let mut legacy = Vec::new();
+            srs.write_to(&mut legacy).unwrap();
+            assert_eq!(infer_parameters(&legacy).unwrap(), Parameters::new(16, 4));
+        }
+
+        let mut legacy = Vec::new();
+        srs.write_to(&mut legacy).unwrap();
+        let migrated = migrate(
+            &mut legacy.as_slice(),
+            None,
+            Cursor::new(Vec::new()),
+            5,
+            Fr::from(7u64),
+        )
+        .unwrap();
+
+        let mut reopened = FileAccumulator::open(migrated.into_inner()).unwrap();
+        assert_eq!(reopened.parameters(), Parameters::new(16, 4));
+        assert_eq!(reopened.read_g1(0..16).unwrap().points(), srs.g1_elements());
+        assert_eq!(reopened.read_g2(0..4).unwrap().points(), srs.g2_elements());
+
+        // Swapping two powers breaks the structure of the SRS
+        let mut g1 = srs.g1_powers().to_vec();
+        g1.swap(2, 3);
+        let mut invalid = Vec::new();
+        SRS::from_vectors(g1, srs.g2_powers().to_vec())
+            .unwrap()
+            .write_to(&mut invalid)
+            .unwrap();
+        assert!(matches!(
+            migrate(
+                &mut invalid.as_slice(),
+                None,
+                Cursor::new(Vec::new()),
+                5,
+                Fr::from(7u64)
+            ),
+            Err(Error::Verification(VerificationError::InvalidStructure))
+        ));
+
+        assert!(matches!(
+            infer_parameters(&legacy[..legacy.len() - 1]),
+            Err(SerialisationError::UnrecognisedLegacyFile)
+        ));
+    }
+}
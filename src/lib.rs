@@ -1,8 +1,93 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(all(feature = "wasm-small", feature = "parallel"))]
+compile_error!(
+    "the `wasm-small` feature is single threaded, build it with `--no-default-features`"
+);
+
+#[cfg(feature = "std")]
+pub mod accumulator;
+#[cfg(feature = "std")]
+pub mod audit_log;
+#[cfg(feature = "ark-serialize")]
+pub mod canonical;
+#[cfg(feature = "std")]
+pub mod challenge;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "std")]
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod distributed;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub mod evm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod frames;
+#[cfg(feature = "std")]
+pub mod identity;
+#[cfg(feature = "std")]
+mod interop_hash_to_curve;
+#[cfg(feature = "std")]
 pub mod interop_point_encoding;
+#[cfg(feature = "std")]
 mod interop_subgroup_checks;
+#[cfg(feature = "std")]
 pub mod keypair;
+#[cfg(feature = "std")]
+pub mod kzg;
+#[cfg(feature = "std")]
+pub mod legacy;
+#[cfg(feature = "std")]
+pub mod memory;
+#[cfg(feature = "std")]
+pub mod merkle;
+pub mod metrics;
+#[cfg(feature = "napi")]
+pub mod node;
+#[cfg(feature = "std")]
+pub mod orchestrator;
+#[cfg(feature = "std")]
+pub mod progress;
+#[cfg(feature = "proto")]
+pub mod proto;
+#[cfg(feature = "replay")]
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod report;
+#[cfg(feature = "std")]
 pub mod sdk;
+#[cfg(feature = "std")]
+pub mod section;
+#[cfg(feature = "std")]
 pub mod serialisation;
+#[cfg(feature = "std")]
+pub mod shard;
+#[cfg(feature = "std")]
 mod shared_secret;
+#[cfg(feature = "std")]
+pub mod simulate;
+#[cfg(feature = "std")]
 pub mod srs;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod threads;
+#[cfg(feature = "std")]
+pub mod timestamp;
+#[cfg(feature = "uniffi")]
+pub mod uniffi_bindings;
+#[cfg(feature = "std")]
 pub mod update_proof;
+pub mod verification;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod witness;
@@ -0,0 +1,128 @@
+// Estimates of the memory needed to update an SRS, so that memory constrained
+// clients, such as browsers on mobile devices, can decide how to process an
+// SRS before they start.
+//
+// An update holds the points in affine form along with a power of the secret
+// for each point. Updating the SRS a section at a time, see the section module,
+// only holds one section of points and powers at once, at the cost of copying
+// the SRS in and out of memory a section at a time.
+//
+// The estimates only count the large allocations, so allocator overhead and
+// the memory of the program itself should be allowed for on top of them.
+use crate::{
+    interop_point_encoding::{G1_SERIALISED_SIZE, G2_SERIALISED_SIZE},
+    srs::{Parameters, UPDATE_CHUNK_SIZE},
+};
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use std::mem::size_of;
+
+// The fewest points that a section can hold
+pub const MIN_SECTION_SIZE: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    // The points which are held in memory at once
+    pub points: usize,
+    // The powers of the secret which the points are multiplied by
+    pub powers_of_secret: usize,
+    // The projective points and serialised bytes which are held while updating
+    pub scratch: usize,
+}
+
+impl MemoryEstimate {
+    // The estimated peak memory, in bytes
+    pub fn total(&self) -> usize {
+        self.points
+            .saturating_add(self.powers_of_secret)
+            .saturating_add(self.scratch)
+    }
+}
+
+// Estimates the memory needed to update the whole SRS at once, as `SRS::update` does.
+// With the `parallel` feature, each thread needs its own scratch space
+pub fn estimate_update(parameters: Parameters) -> MemoryEstimate {
+    let num_g1 = parameters.num_g1_elements();
+    let num_g2 = parameters.num_g2_elements();
+
+    MemoryEstimate {
+        points: num_g1
+            .saturating_mul(size_of::<G1Affine>())
+            .saturating_add(num_g2.saturating_mul(size_of::<G2Affine>())),
+        powers_of_secret: std::cmp::max(num_g1, num_g2).saturating_mul(size_of::<Fr>()),
+        scratch: UPDATE_CHUNK_SIZE * size_of::<G2Projective>(),
+    }
+}
+
+// Estimates the memory needed to update the SRS `section_size` points at a time.
+//
+// Each section is serialised before the next one is read, so the compressed
+// encoding of one section is included
+pub fn estimate_sectioned_update(parameters: Parameters, section_size: usize) -> MemoryEstimate {
+    let section_size = std::cmp::max(section_size, MIN_SECTION_SIZE);
+    let num_g1 = std::cmp::min(parameters.num_g1_elements(), section_size);
+    let num_g2 = std::cmp::min(parameters.num_g2_elements(), section_size);
+
+    // Only one section, in either group, is held at a time
+    let g1_section = section_estimate::<G1Affine, G1Projective>(num_g1, G1_SERIALISED_SIZE);
+    let g2_section = section_estimate::<G2Affine, G2Projective>(num_g2, G2_SERIALISED_SIZE);
+    std::cmp::max_by_key(g1_section, g2_section, MemoryEstimate::total)
+}
+
+// Returns the largest section size whose sectioned update fits in `budget` bytes,
+// or None if even the smallest sections do not fit
+pub fn section_size_for_budget(parameters: Parameters, budget: usize) -> Option<usize> {
+    let fits = |section_size| estimate_sectioned_update(parameters, section_size).total() <= budget;
+    if !fits(MIN_SECTION_SIZE) {
+        return None;
+    }
+
+    // The estimate grows with the section size, up to the size of the larger group
+    let mut low = MIN_SECTION_SIZE;
+    let mut high = std::cmp::max(
+        std::cmp::max(parameters.num_g1_elements(), parameters.num_g2_elements()),
+        MIN_SECTION_SIZE,
+    );
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Some(low)
+}
+
+fn section_estimate<A, P>(num_points: usize, serialised_size: usize) -> MemoryEstimate {
+    let scratch_points = std::cmp::min(num_points, UPDATE_CHUNK_SIZE);
+
+    MemoryEstimate {
+        points: num_points.saturating_mul(size_of::<A>()),
+        powers_of_secret: num_points.saturating_mul(size_of::<Fr>()),
+        scratch: num_points
+            .saturating_mul(serialised_size)
+            .saturating_add(scratch_points * size_of::<P>()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::CEREMONIES;
+
+    #[test]
+    fn sections_fit_the_budget() {
+        let parameters = CEREMONIES[3];
+        let whole = estimate_update(parameters).total();
+
+        let budget = whole / 8;
+        let section_size = section_size_for_budget(parameters, budget).unwrap();
+        assert!(section_size < parameters.num_g1_elements());
+        assert!(estimate_sectioned_update(parameters, section_size).total() <= budget);
+        assert!(estimate_sectioned_update(parameters, section_size + 1).total() > budget);
+
+        // Sections are never larger than the larger group
+        assert_eq!(section_size_for_budget(parameters, usize::MAX), Some(32768));
+        assert_eq!(section_size_for_budget(parameters, 100), None);
+    }
+}
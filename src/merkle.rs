@@ -0,0 +1,175 @@
+// A Merkle tree over the digests of the contributions to a transcript, so that
+// a participant can check that their contribution was included using a proof
+// which is logarithmic in the number of contributions, rather than by
+// downloading the transcript.
+//
+// Leaves and internal nodes are hashed with different prefixes, as in RFC 6962,
+// so that an internal node cannot be passed off as a leaf. When a level has an
+// odd number of nodes, the last node is promoted to the next level unchanged,
+// rather than being paired with itself.
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MerkleTree {
+    // The digests of the leaves, in the order that they were added
+    digests: Vec<[u8; 32]>,
+}
+
+// Shows that a digest is the leaf at `index` of a tree with `num_leaves` leaves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub index: usize,
+    pub num_leaves: usize,
+    // The siblings of the nodes on the path from the leaf to the root, skipping
+    // the levels where the node on the path was promoted
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleTree {
+    pub fn new() -> Self {
+        MerkleTree::default()
+    }
+
+    pub fn push(&mut self, digest: [u8; 32]) {
+        self.digests.push(digest);
+    }
+    pub fn digests(&self) -> &[[u8; 32]] {
+        &self.digests
+    }
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+
+    // The root of the tree. The root of the empty tree is the hash of no bytes
+    pub fn root(&self) -> [u8; 32] {
+        if self.digests.is_empty() {
+            return Sha256::digest(&[]).into();
+        }
+
+        let mut level = self.leaves();
+        while level.len() > 1 {
+            level = next_level(&level);
+        }
+        level[0]
+    }
+
+    // Returns the inclusion proof for the leaf at `index`, or None if there is no such leaf
+    pub fn inclusion_proof(&self, index: usize) -> Option<InclusionProof> {
+        if index >= self.digests.len() {
+            return None;
+        }
+
+        let mut siblings = Vec::new();
+        let mut level = self.leaves();
+        let mut position = index;
+        while level.len() > 1 {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(*sibling);
+            }
+            level = next_level(&level);
+            position /= 2;
+        }
+
+        Some(InclusionProof {
+            index,
+            num_leaves: self.digests.len(),
+            siblings,
+        })
+    }
+
+    fn leaves(&self) -> Vec<[u8; 32]> {
+        self.digests.iter().map(hash_leaf).collect()
+    }
+}
+
+impl InclusionProof {
+    // Checks that `digest` is included in the tree with this root
+    pub fn verify(&self, root: &[u8; 32], digest: &[u8; 32]) -> bool {
+        if self.index >= self.num_leaves {
+            return false;
+        }
+
+        let mut siblings = self.siblings.iter();
+        let mut hash = hash_leaf(digest);
+        let mut position = self.index;
+        let mut level_len = self.num_leaves;
+        while level_len > 1 {
+            // The last node of a level with an odd number of nodes has no sibling
+            if position ^ 1 < level_len {
+                let sibling = match siblings.next() {
+                    Some(sibling) => sibling,
+                    None => return false,
+                };
+                hash = if position % 2 == 0 {
+                    hash_node(&hash, sibling)
+                } else {
+                    hash_node(sibling, &hash)
+                };
+            }
+            position /= 2;
+            level_len = (level_len + 1) / 2;
+        }
+
+        siblings.next().is_none() && &hash == root
+    }
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_node(left, right),
+            [promoted] => *promoted,
+            _ => unreachable!("chunks of two have one or two elements"),
+        })
+        .collect()
+}
+
+fn hash_leaf(digest: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(digest);
+    hasher.finalize().into()
+}
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inclusion_proofs_verify() {
+        let digest = |i: u8| [i; 32];
+
+        let mut tree = MerkleTree::new();
+        for num_leaves in 1..=9u8 {
+            tree.push(digest(num_leaves - 1));
+            let root = tree.root();
+
+            for index in 0..num_leaves {
+                let proof = tree.inclusion_proof(index as usize).unwrap();
+                assert!(proof.verify(&root, &digest(index)));
+                assert!(!proof.verify(&root, &digest(index + 1)));
+
+                // The proof does not verify for a different position
+                let mut moved_proof = proof.clone();
+                moved_proof.index = (moved_proof.index + 1) % num_leaves as usize;
+                assert!(num_leaves == 1 || !moved_proof.verify(&root, &digest(index)));
+            }
+            assert_eq!(tree.inclusion_proof(num_leaves as usize), None);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+// Counters for the long running operations, so that services which embed the
+// verifier can export them to Prometheus and alert on drops in throughput.
+//
+// The counters are only kept when the `metrics` feature is enabled. Otherwise
+// recording is a no-op, so the verification core does not need atomics.
+// Rates, such as pairings per second, are computed from the counters by
+// Prometheus.
+#[cfg(feature = "metrics")]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+const NUM_FAILURE_CATEGORIES: usize = 5;
+
+// The kinds of failure which are counted separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureCategory {
+    Deserialisation,
+    Subgroup,
+    Structure,
+    UpdateProof,
+    Other,
+}
+
+impl FailureCategory {
+    pub const ALL: [FailureCategory; NUM_FAILURE_CATEGORIES] = [
+        FailureCategory::Deserialisation,
+        FailureCategory::Subgroup,
+        FailureCategory::Structure,
+        FailureCategory::UpdateProof,
+        FailureCategory::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureCategory::Deserialisation => "deserialisation",
+            FailureCategory::Subgroup => "subgroup",
+            FailureCategory::Structure => "structure",
+            FailureCategory::UpdateProof => "update_proof",
+            FailureCategory::Other => "other",
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<crate::error::VerificationError> for FailureCategory {
+    fn from(error: crate::error::VerificationError) -> Self {
+        use crate::error::VerificationError::*;
+
+        match error {
+            NotInSubgroup => FailureCategory::Subgroup,
+            InvalidStructure => FailureCategory::Structure,
+            NoUpdateProofs
+            | UpdateDoesNotEndAtSRS
+            | InvalidUpdateChain
+            | NoOpUpdate
+            | IdentityElement
+            | MixedProofModes => FailureCategory::UpdateProof,
+            _ => FailureCategory::Other,
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+static POINTS_VALIDATED: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "metrics")]
+static PAIRINGS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "metrics")]
+static BYTES_DESERIALISED: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "metrics")]
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "metrics")]
+static FAILURES: [AtomicU64; NUM_FAILURE_CATEGORIES] = [ZERO; NUM_FAILURE_CATEGORIES];
+
+// Points which were subgroup checked or checked to be powers of tau
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_points_validated(num_points: usize) {
+    #[cfg(feature = "metrics")]
+    POINTS_VALIDATED.fetch_add(num_points as u64, Ordering::Relaxed);
+}
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_pairings(num_pairings: usize) {
+    #[cfg(feature = "metrics")]
+    PAIRINGS.fetch_add(num_pairings as u64, Ordering::Relaxed);
+}
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_bytes_deserialised(num_bytes: usize) {
+    #[cfg(feature = "metrics")]
+    BYTES_DESERIALISED.fetch_add(num_bytes as u64, Ordering::Relaxed);
+}
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_failure(category: FailureCategory) {
+    #[cfg(feature = "metrics")]
+    FAILURES[category as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+// The values of the counters since the process started
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub points_validated: u64,
+    pub pairings: u64,
+    pub bytes_deserialised: u64,
+    pub failures: [u64; NUM_FAILURE_CATEGORIES],
+}
+
+#[cfg(feature = "metrics")]
+impl MetricsSnapshot {
+    pub fn failures(&self, category: FailureCategory) -> u64 {
+        self.failures[category as usize]
+    }
+
+    // Renders the counters in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut text = String::new();
+        let mut counter = |name: &str, help: &str, values: &[(Option<&str>, u64)]| {
+            text.push_str(&format!(
+                "# HELP {} {}\n# TYPE {} counter\n",
+                name, help, name
+            ));
+            for (category, value) in values {
+                match category {
+                    Some(category) => text.push_str(&format!(
+                        "{}{{category=\"{}\"}} {}\n",
+                        name, category, value
+                    )),
+                    None => text.push_str(&format!("{} {}\n", name, value)),
+                }
+            }
+        };
+
+        counter(
+            "small_powers_of_tau_points_validated_total",
+            "Points which were subgroup checked or checked to be powers of tau",
+            &[(None, self.points_validated)],
+        );
+        counter(
+            "small_powers_of_tau_pairings_total",
+            "Pairings which were computed",
+            &[(None, self.pairings)],
+        );
+        counter(
+            "small_powers_of_tau_bytes_deserialised_total",
+            "Bytes of compressed points which were deserialised",
+            &[(None, self.bytes_deserialised)],
+        );
+        let failures: Vec<_> = FailureCategory::ALL
+            .iter()
+            .map(|category| (Some(category.as_str()), self.failures(*category)))
+            .collect();
+        counter(
+            "small_powers_of_tau_failures_total",
+            "Checks which failed, by category",
+            &failures,
+        );
+        text
+    }
+}
+
+#[cfg(feature = "metrics")]
+pub fn snapshot() -> MetricsSnapshot {
+    let mut failures = [0u64; NUM_FAILURE_CATEGORIES];
+    for (count, counter) in failures.iter_mut().zip(&FAILURES) {
+        *count = counter.load(Ordering::Relaxed);
+    }
+
+    MetricsSnapshot {
+        points_validated: POINTS_VALIDATED.load(Ordering::Relaxed),
+        pairings: PAIRINGS.load(Ordering::Relaxed),
+        bytes_deserialised: BYTES_DESERIALISED.load(Ordering::Relaxed),
+        failures,
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use crate::{
+        keypair::PrivateKey,
+        serialisation::SubgroupCheck,
+        srs::{Parameters, SRS},
+    };
+
+    #[test]
+    fn counters_increase() {
+        let parameters = Parameters::new(16, 2);
+        let before = snapshot();
+
+        let mut srs = SRS::new(parameters).unwrap();
+        srs.update(PrivateKey::from_u64(252));
+        let bytes = srs.serialise_bytes();
+        let srs = SRS::deserialise_bytes(&bytes, parameters, SubgroupCheck::Full).unwrap();
+        assert!(srs.structure_check_opt(123456789u64.into()));
+
+        // Other tests run at the same time, so the counters can increase by more
+        let after = snapshot();
+        assert!(after.bytes_deserialised >= before.bytes_deserialised + bytes.len() as u64);
+        assert!(after.points_validated >= before.points_validated + 2 * 18);
+        assert!(after.pairings >= before.pairings + 4);
+
+        let text = after.to_prometheus();
+        assert!(text.contains("# TYPE small_powers_of_tau_pairings_total counter"));
+        assert!(text.contains("small_powers_of_tau_failures_total{category=\"subgroup\"}"));
+    }
+}
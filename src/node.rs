@@ -0,0 +1,123 @@
+// Native node.js addon, built with napi-rs. This exposes the same surface as
+// the wasm bindings for SRS bytes, but runs on native threads, which matters
+// for node based sequencers verifying large transcripts.
+//
+// Verification runs on the libuv thread pool, so that it does not block the
+// event loop of the sequencer.
+use ark_bls12_381::Fr;
+use ark_ff::UniformRand;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use zeroize::Zeroize;
+
+use crate::{
+    identity::Identity,
+    keypair::PrivateKey,
+    serialisation::{SubgroupCheck, UPDATE_PROOF_SERIALISED_SIZE},
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+
+#[napi(object)]
+pub struct ContributeResult {
+    pub srs: Buffer,
+    pub update_proof: Buffer,
+}
+
+fn deserialise_srs(srs: &[u8], num_g1: u32, num_g2: u32) -> Result<SRS> {
+    let parameters = Parameters::new(num_g1 as usize, num_g2 as usize);
+    SRS::deserialise_bytes(srs, parameters, SubgroupCheck::Full)
+        .map_err(|err| Error::from_reason(format!("could not deserialise the SRS: {}", err)))
+}
+
+fn deserialise_update_proof(update_proof: &[u8]) -> Result<UpdateProof> {
+    let invalid_proof = || Error::from_reason("could not deserialise the update proof".to_string());
+
+    let update_proof: &[u8; UPDATE_PROOF_SERIALISED_SIZE] =
+        update_proof.try_into().map_err(|_| invalid_proof())?;
+    UpdateProof::deserialise_bytes(update_proof).map_err(|_| invalid_proof())
+}
+
+fn private_key_from_buffer(mut secret: Vec<u8>) -> Result<PrivateKey> {
+    let private_key = PrivateKey::from_bytes(&secret);
+    secret.zeroize();
+    private_key.map_err(|err| Error::from_reason(err.to_string()))
+}
+
+// Updates the SRS using the secret, returning the updated SRS and the update proof
+#[napi]
+pub fn contribute_srs(
+    srs: Buffer,
+    num_g1: u32,
+    num_g2: u32,
+    secret: Buffer,
+) -> Result<ContributeResult> {
+    let mut srs = deserialise_srs(&srs, num_g1, num_g2)?;
+
+    let update_proof = srs.update(private_key_from_buffer(secret.into())?);
+
+    Ok(ContributeResult {
+        srs: srs.serialise_bytes().into(),
+        update_proof: update_proof.serialise_bytes().to_vec().into(),
+    })
+}
+
+pub struct VerifyUpdateTask {
+    before: Vec<u8>,
+    after: Vec<u8>,
+    num_g1: u32,
+    num_g2: u32,
+    update_proof: Vec<u8>,
+}
+
+impl Task for VerifyUpdateTask {
+    type Output = bool;
+    type JsValue = bool;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let before = deserialise_srs(&self.before, self.num_g1, self.num_g2)?;
+        let after = deserialise_srs(&self.after, self.num_g1, self.num_g2)?;
+        let update_proof = deserialise_update_proof(&self.update_proof)?;
+
+        let random_element = Fr::rand(&mut rand::thread_rng());
+        Ok(SRS::verify_update(&before, &after, &update_proof, random_element).is_ok())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+// Verifies that `after` was obtained by updating `before` using the update proof.
+// Resolves to whether the update was valid
+#[napi]
+pub fn verify_update(
+    before: Buffer,
+    after: Buffer,
+    num_g1: u32,
+    num_g2: u32,
+    update_proof: Buffer,
+) -> AsyncTask<VerifyUpdateTask> {
+    AsyncTask::new(VerifyUpdateTask {
+        before: before.into(),
+        after: after.into(),
+        num_g1,
+        num_g2,
+        update_proof: update_proof.into(),
+    })
+}
+
+// Returns the hex encoded pot pubkey for the secret
+#[napi]
+pub fn get_pot_pubkey(secret: Buffer) -> Result<String> {
+    Ok(private_key_from_buffer(secret.into())?.to_pot_pubkey())
+}
+
+// Signs the canonical encoding of the participant's identity using the secret,
+// returning the hex encoded BLS signature
+#[napi]
+pub fn sign_identity(secret: Buffer, identity: String) -> Result<String> {
+    let identity = Identity::parse(&identity)
+        .map_err(|err| Error::from_reason(format!("invalid identity: {}", err)))?;
+    Ok(private_key_from_buffer(secret.into())?.sign_hex(identity.encode().as_bytes()))
+}
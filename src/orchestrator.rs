@@ -0,0 +1,242 @@
+// Runs a scripted ceremony on one machine, with a list of named participants
+// who contribute one after another. Every contribution is verified before the
+// next participant contributes, as a sequencer would, and the result contains
+// everything needed to publish the transcript.
+//
+// This is intended for dry-runs of a ceremony and for small internal
+// ceremonies. For rehearsals, a participant can be given a seed so that the
+// whole ceremony can be reproduced. The secrets of participants without a
+// seed come from the operating system.
+use crate::{
+    error::{CeremonyError, Error, VerificationError},
+    keypair::with_fresh_secret,
+    merkle::MerkleTree,
+    sdk::{transcript::contribution_digest, CEREMONIES},
+    serialisation::{bytes_to_hex_string, SRSJson},
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+use ark_bls12_381::Fr;
+use ark_ff::UniformRand;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Participant {
+    pub name: String,
+    // The seed used to generate the secrets of this participant. This must
+    // only be set in rehearsals, since anyone who knows it knows the secrets
+    pub seed: Option<u64>,
+}
+
+impl Participant {
+    pub fn new(name: impl Into<String>) -> Self {
+        Participant {
+            name: name.into(),
+            seed: None,
+        }
+    }
+    pub fn with_seed(name: impl Into<String>, seed: u64) -> Self {
+        Participant {
+            name: name.into(),
+            seed: Some(seed),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Orchestrator {
+    // The parameters of each SRS, which are all updated in every contribution
+    parameters: Vec<Parameters>,
+    participants: Vec<Participant>,
+}
+
+// The contribution of one participant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub participant: String,
+    // The update proof for each SRS
+    pub update_proofs: Vec<UpdateProof>,
+    // The digest of the update proofs, see `contribution_digest`
+    pub digest: [u8; 32],
+}
+
+#[derive(Debug, Clone)]
+pub struct OrchestratedTranscript {
+    pub starting_srs: Vec<SRS>,
+    pub final_srs: Vec<SRS>,
+    pub rounds: Vec<Round>,
+    // A Merkle tree over the digests of the rounds
+    pub contributions: MerkleTree,
+}
+
+impl Orchestrator {
+    pub fn new(parameters: Vec<Parameters>) -> Self {
+        Orchestrator {
+            parameters,
+            participants: Vec::new(),
+        }
+    }
+    // An orchestrator for the EIP-4844 ceremonies, see `sdk::CEREMONIES`
+    pub fn eip_4844() -> Self {
+        Orchestrator::new(CEREMONIES.to_vec())
+    }
+
+    // Adds a participant, who contributes after the participants added before them
+    pub fn participant(mut self, participant: Participant) -> Self {
+        self.participants.push(participant);
+        self
+    }
+
+    // Runs the ceremony, stopping at the first contribution which does not verify
+    pub fn run(&self) -> Result<OrchestratedTranscript, Error> {
+        let starting_srs = self
+            .parameters
+            .iter()
+            .map(|parameters| SRS::new(*parameters))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut srs = starting_srs.clone();
+
+        let mut rounds = Vec::with_capacity(self.participants.len());
+        let mut contributions = MerkleTree::new();
+
+        for (index, participant) in self.participants.iter().enumerate() {
+            let update_proofs = match participant.seed {
+                Some(seed) => contribute(&mut srs, ChaCha20Rng::seed_from_u64(seed)),
+                None => contribute(&mut srs, OsRng),
+            }
+            .map_err(|error| CeremonyError::InvalidContribution {
+                participant: index,
+                error,
+            })?;
+
+            let digest = contribution_digest(&update_proofs);
+            contributions.push(digest);
+            rounds.push(Round {
+                participant: participant.name.clone(),
+                update_proofs,
+                digest,
+            });
+        }
+
+        Ok(OrchestratedTranscript {
+            starting_srs,
+            final_srs: srs,
+            rounds,
+            contributions,
+        })
+    }
+}
+
+// Updates every SRS using a fresh secret, and checks the update against a copy
+// of the SRS from before it
+fn contribute<R: Rng>(srs: &mut [SRS], mut rng: R) -> Result<Vec<UpdateProof>, VerificationError> {
+    let mut update_proofs = Vec::with_capacity(srs.len());
+
+    for srs in srs.iter_mut() {
+        let before = srs.clone();
+        let update_proof = with_fresh_secret(&mut rng, |priv_key| srs.update(priv_key));
+
+        SRS::verify_update(&before, srs, &update_proof, Fr::rand(&mut OsRng))?;
+        update_proofs.push(update_proof);
+    }
+
+    Ok(update_proofs)
+}
+
+impl OrchestratedTranscript {
+    // Checks the whole transcript again, from the starting SRS to the final SRS
+    pub fn verify(&self, random_element: Fr) -> Result<(), VerificationError> {
+        for (i, (before, after)) in self.starting_srs.iter().zip(&self.final_srs).enumerate() {
+            after.subgroup_check()?;
+
+            let update_proofs: Vec<_> = self
+                .rounds
+                .iter()
+                .map(|round| round.update_proofs[i])
+                .collect();
+            SRS::verify_updates(before, after, &update_proofs, random_element)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundJSON {
+    pub participant: String,
+    pub update_proofs: Vec<[String; 2]>,
+    pub digest: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrchestratedTranscriptJSON {
+    pub starting_srs: Vec<SRSJson>,
+    pub final_srs: Vec<SRSJson>,
+    pub rounds: Vec<RoundJSON>,
+    pub contributions_root: String,
+}
+
+impl From<&OrchestratedTranscript> for OrchestratedTranscriptJSON {
+    fn from(transcript: &OrchestratedTranscript) -> Self {
+        let rounds = transcript
+            .rounds
+            .iter()
+            .map(|round| RoundJSON {
+                participant: round.participant.clone(),
+                update_proofs: round
+                    .update_proofs
+                    .iter()
+                    .map(UpdateProof::serialise)
+                    .collect(),
+                digest: bytes_to_hex_string(&round.digest),
+            })
+            .collect();
+
+        OrchestratedTranscriptJSON {
+            starting_srs: transcript.starting_srs.iter().map(SRSJson::from).collect(),
+            final_srs: transcript.final_srs.iter().map(SRSJson::from).collect(),
+            rounds,
+            contributions_root: bytes_to_hex_string(&transcript.contributions.root()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_ceremony() {
+        let parameters = vec![Parameters::new(16, 2), Parameters::new(32, 4)];
+        let orchestrator = Orchestrator::new(parameters)
+            .participant(Participant::with_seed("alice", 1))
+            .participant(Participant::with_seed("bob", 2))
+            .participant(Participant::new("carol"));
+
+        let transcript = orchestrator.run().unwrap();
+        assert_eq!(transcript.rounds.len(), 3);
+        assert_eq!(transcript.rounds[1].participant, "bob");
+        assert_eq!(transcript.rounds[1].update_proofs.len(), 2);
+        assert!(transcript.verify(Fr::from(123456789u64)).is_ok());
+
+        // The seeded participants make the same contributions in a rehearsal
+        let rehearsal = Orchestrator::new(vec![Parameters::new(16, 2), Parameters::new(32, 4)])
+            .participant(Participant::with_seed("alice", 1))
+            .participant(Participant::with_seed("bob", 2))
+            .run()
+            .unwrap();
+        assert_eq!(rehearsal.rounds, transcript.rounds[..2]);
+
+        let json = OrchestratedTranscriptJSON::from(&transcript);
+        assert_eq!(json.rounds.len(), 3);
+        assert_eq!(json.final_srs.len(), 2);
+        assert_eq!(
+            json.contributions_root,
+            bytes_to_hex_string(&transcript.contributions.root())
+        );
+    }
+}
@@ -0,0 +1,49 @@
+// Progress reporting for the long running operations, such as updating,
+// checking the structure of and writing a large SRS.
+//
+// The same sink can be used to drive a progress bar in a native application
+// or a javascript callback in the wasm bindings.
+
+// The phases of a contribution, which are reported along with the progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Deserialise,
+    // Checking that the deserialised points are in the prime order subgroup
+    Validate,
+    Update,
+    Verify,
+    Serialise,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Deserialise => "deserialise",
+            Phase::Validate => "validate",
+            Phase::Update => "update",
+            Phase::Verify => "verify",
+            Phase::Serialise => "serialise",
+        }
+    }
+}
+
+// Receives the number of items which have been processed in a phase.
+//
+// This is called after each chunk of work, so it should return quickly
+pub trait ProgressSink {
+    fn progress(&mut self, phase: Phase, items_done: usize, items_total: usize);
+}
+
+impl<F: FnMut(Phase, usize, usize)> ProgressSink for F {
+    fn progress(&mut self, phase: Phase, items_done: usize, items_total: usize) {
+        self(phase, items_done, items_total)
+    }
+}
+
+// A sink which ignores the progress
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn progress(&mut self, _: Phase, _: usize, _: usize) {}
+}
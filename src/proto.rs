@@ -0,0 +1,212 @@
+// Protobuf messages for the ceremony, generated from `proto/ceremony.proto`
+// by the build script, along with conversions to and from the types in this
+// crate. With the `grpc` feature, the tonic client and server for
+// `CeremonyService` are generated into `ceremony_service_client` and
+// `ceremony_service_server`.
+//
+// Points are checked to be in the prime order subgroup when an SRS or SRS
+// chunk is converted, but not when an update proof is converted, as with
+// `UpdateProof::deserialise_bytes`.
+use crate::{
+    error::{CeremonyError, Error, SerialisationError},
+    interop_point_encoding::{
+        decode_g1_batch, decode_g2_batch, encode_g1_batch, encode_g2_batch, g1_from_slice,
+        g2_from_slice, serialize_g1, serialize_g2, G1_SERIALISED_SIZE, G2_SERIALISED_SIZE,
+    },
+    sdk::{contribution, NUM_CEREMONIES},
+    section::{G1Section, G2Section},
+    serialisation::{check_bounds, SubgroupCheck},
+    srs,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+
+include!(concat!(env!("OUT_DIR"), "/small_powers_of_tau.rs"));
+
+impl From<srs::Parameters> for Parameters {
+    fn from(parameters: srs::Parameters) -> Self {
+        Parameters {
+            num_g1: parameters.num_g1_elements() as u64,
+            num_g2: parameters.num_g2_elements() as u64,
+        }
+    }
+}
+
+impl From<Parameters> for srs::Parameters {
+    // Lengths which do not fit into a usize are saturated, so that they are
+    // rejected as too large when the parameters are checked
+    fn from(parameters: Parameters) -> Self {
+        srs::Parameters::new(
+            usize::try_from(parameters.num_g1).unwrap_or(usize::MAX),
+            usize::try_from(parameters.num_g2).unwrap_or(usize::MAX),
+        )
+    }
+}
+
+impl From<&srs::SRS> for Srs {
+    fn from(srs: &srs::SRS) -> Self {
+        let parameters = srs::Parameters::new(srs.g1_elements().len(), srs.g2_elements().len());
+        Srs {
+            parameters: Some(parameters.into()),
+            points: srs.serialise_bytes(),
+        }
+    }
+}
+
+impl TryFrom<&Srs> for srs::SRS {
+    type Error = Error;
+
+    fn try_from(srs: &Srs) -> Result<Self, Self::Error> {
+        // Missing parameters are treated as an SRS with no elements, which is rejected
+        let parameters = srs.parameters.clone().unwrap_or_default().into();
+        srs::SRS::deserialise_bytes(&srs.points, parameters, SubgroupCheck::Full)
+    }
+}
+
+impl SrsChunk {
+    pub fn from_g1_section(section: &G1Section, parameters: srs::Parameters) -> Self {
+        SrsChunk {
+            parameters: Some(parameters.into()),
+            group: Group::G1 as i32,
+            start: section.range().start as u64,
+            points: encode_g1_batch(section.points()),
+            ceremony: 0,
+        }
+    }
+    pub fn from_g2_section(section: &G2Section, parameters: srs::Parameters) -> Self {
+        SrsChunk {
+            parameters: Some(parameters.into()),
+            group: Group::G2 as i32,
+            start: section.range().start as u64,
+            points: encode_g2_batch(section.points()),
+            ceremony: 0,
+        }
+    }
+
+    // Decodes a chunk of G1 powers, checking that it lies within the parameters
+    pub fn to_g1_section(&self) -> Result<G1Section, Error> {
+        let start = self.check_range(Group::G1, G1_SERIALISED_SIZE)?;
+        let section = G1Section::new(start, decode_g1_batch(&self.points)?);
+        section.subgroup_check()?;
+        Ok(section)
+    }
+    // Decodes a chunk of G2 powers, checking that it lies within the parameters
+    pub fn to_g2_section(&self) -> Result<G2Section, Error> {
+        let start = self.check_range(Group::G2, G2_SERIALISED_SIZE)?;
+        let section = G2Section::new(start, decode_g2_batch(&self.points)?);
+        section.subgroup_check_g2()?;
+        Ok(section)
+    }
+
+    // The start of a chunk is untrusted, so the range of the chunk is checked
+    // against the parameters before the points are decoded. Returns the start
+    fn check_range(&self, group: Group, point_size: usize) -> Result<usize, Error> {
+        let parameters: srs::Parameters = self.parameters.clone().unwrap_or_default().into();
+        check_bounds(parameters)?;
+        let num_elements = match group {
+            Group::G1 => parameters.num_g1_elements(),
+            Group::G2 => parameters.num_g2_elements(),
+        };
+
+        let num_points = self.points.len() / point_size;
+        let end = usize::try_from(self.start)
+            .ok()
+            .and_then(|start| start.checked_add(num_points));
+        let is_valid = self.group == group as i32
+            && self.points.len() % point_size == 0
+            && matches!(end, Some(end) if end <= num_elements);
+        if !is_valid {
+            return Err(SerialisationError::InvalidHeader.into());
+        }
+        Ok(self.start as usize)
+    }
+}
+
+impl From<&crate::update_proof::UpdateProof> for UpdateProof {
+    fn from(update_proof: &crate::update_proof::UpdateProof) -> Self {
+        UpdateProof {
+            commitment_to_secret: serialize_g2(&update_proof.commitment_to_secret.into_affine())
+                .to_vec(),
+            new_accumulated_point: serialize_g1(&update_proof.new_accumulated_point.into_affine())
+                .to_vec(),
+        }
+    }
+}
+
+impl TryFrom<&UpdateProof> for crate::update_proof::UpdateProof {
+    type Error = SerialisationError;
+
+    fn try_from(update_proof: &UpdateProof) -> Result<Self, Self::Error> {
+        Ok(crate::update_proof::UpdateProof {
+            commitment_to_secret: g2_from_slice(&update_proof.commitment_to_secret)?
+                .into_projective(),
+            new_accumulated_point: g1_from_slice(&update_proof.new_accumulated_point)?
+                .into_projective(),
+        })
+    }
+}
+
+impl TryFrom<&BatchContribution> for contribution::Contribution {
+    type Error = Error;
+
+    // Only the SRS of each contribution are converted, and they must have the
+    // parameters of their ceremony
+    fn try_from(batch_contribution: &BatchContribution) -> Result<Self, Self::Error> {
+        if batch_contribution.contributions.len() != NUM_CEREMONIES {
+            return Err(CeremonyError::WrongNumberOfContributions {
+                expected: NUM_CEREMONIES,
+                got: batch_contribution.contributions.len(),
+            }
+            .into());
+        }
+
+        let empty_srs = Srs::default();
+        let mut contributions = Vec::with_capacity(NUM_CEREMONIES);
+        for contribution in &batch_contribution.contributions {
+            let srs = contribution.srs.as_ref().unwrap_or(&empty_srs);
+            contributions.push(srs::SRS::try_from(srs)?);
+        }
+
+        let contribution = contribution::Contribution {
+            // There is exactly one SRS for each ceremony
+            contributions: contributions.try_into().unwrap(),
+        };
+        contribution.check_parameters()?;
+        Ok(contribution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::PrivateKey;
+    use prost::Message;
+
+    #[test]
+    fn srs_and_update_proof_roundtrip() {
+        let parameters = srs::Parameters::new(20, 3);
+        let mut srs = srs::SRS::new(parameters).unwrap();
+        let update_proof = srs.update(PrivateKey::from_u64(252));
+
+        let bytes = Srs::from(&srs).encode_to_vec();
+        let decoded = Srs::decode(bytes.as_slice()).unwrap();
+        assert_eq!(srs::SRS::try_from(&decoded).unwrap(), srs);
+
+        let bytes = UpdateProof::from(&update_proof).encode_to_vec();
+        let decoded = UpdateProof::decode(bytes.as_slice()).unwrap();
+        assert_eq!(
+            crate::update_proof::UpdateProof::try_from(&decoded).unwrap(),
+            update_proof
+        );
+
+        // A chunk of the G1 powers, and a chunk which claims to extend past
+        // the end of the SRS
+        let section = G1Section::new(8, srs.g1_elements()[8..16].to_vec());
+        let mut chunk = SrsChunk::from_g1_section(&section, parameters);
+        assert_eq!(chunk.to_g1_section().unwrap(), section);
+        assert!(chunk.to_g2_section().is_err());
+        chunk.start = 16;
+        assert!(chunk.to_g1_section().is_err());
+        chunk.start = u64::MAX;
+        assert!(chunk.to_g1_section().is_err());
+    }
+}
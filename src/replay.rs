@@ -0,0 +1,115 @@
+// Contributions which can be replayed by an auditor.
+//
+// The secret of a replayable contribution is derived from an explicit seed,
+// and the contribution is recorded along with digests of the SRS before and
+// after it. If the contribution is later disputed, the contributor can publish
+// the record, and anyone holding the SRS it was made to can repeat it and
+// check that every output matches.
+//
+// Anyone who knows the seed knows the secret, so publishing the record
+// removes that contribution from the security of the ceremony. This is only
+// safe once the ceremony has other contributions which are trusted.
+use crate::{
+    error::{Error, SerialisationError, VerificationError},
+    keypair::PrivateKey,
+    serialisation::{bytes_to_hex_string, hex_string_to_bytes},
+    srs::SRS,
+    update_proof::UpdateProof,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+// Everything needed to repeat a contribution. The seed and digests are 0x
+// prefixed hex strings and the update proof is encoded as in `UpdateProof::serialise`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayRecord {
+    pub seed: String,
+    pub num_g1_elements: usize,
+    pub num_g2_elements: usize,
+    pub input_digest: String,
+    pub output_digest: String,
+    pub update_proof: [String; 2],
+}
+
+// Updates `srs` using a secret derived from `seed`, returning the update proof
+// and a record of the contribution
+pub fn replayable_update(srs: &mut SRS, seed: [u8; 32]) -> (UpdateProof, ReplayRecord) {
+    let input_digest = srs.digest().to_string();
+    let update_proof = srs.update(private_key_from_seed(seed));
+
+    let record = ReplayRecord {
+        seed: bytes_to_hex_string(&seed),
+        num_g1_elements: srs.g1_elements().len(),
+        num_g2_elements: srs.g2_elements().len(),
+        input_digest,
+        output_digest: srs.digest().to_string(),
+        update_proof: update_proof.serialise(),
+    };
+    (update_proof, record)
+}
+
+impl ReplayRecord {
+    // Repeats the contribution to `input`, checking that the input, the
+    // updated SRS and the update proof are exactly the recorded ones.
+    //
+    // Returns the updated SRS
+    pub fn replay(&self, input: &SRS) -> Result<SRS, Error> {
+        let seed = hex_string_to_bytes(&self.seed)?;
+        let seed: [u8; 32] =
+            seed.as_slice()
+                .try_into()
+                .map_err(|_| SerialisationError::InvalidLength {
+                    expected: 32,
+                    got: seed.len(),
+                })?;
+
+        let mut output = input.clone();
+        let (_, record) = replayable_update(&mut output, seed);
+        if &record != self {
+            return Err(VerificationError::ReplayMismatch.into());
+        }
+        Ok(output)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a record can always be serialised")
+    }
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+fn private_key_from_seed(seed: [u8; 32]) -> PrivateKey {
+    PrivateKey::rand(ChaCha20Rng::from_seed(seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srs::Parameters;
+
+    #[test]
+    fn replay_contribution() {
+        let input = SRS::new(Parameters::new(32, 4)).unwrap();
+        let mut output = input.clone();
+        let (update_proof, record) = replayable_update(&mut output, [5u8; 32]);
+        assert!(SRS::verify_update(&input, &output, &update_proof, 123456789u64.into()).is_ok());
+
+        let record = ReplayRecord::from_json(&record.to_json()).unwrap();
+        assert_eq!(record.replay(&input).unwrap(), output);
+
+        // Replaying against a different SRS, or with a different seed, is detected
+        assert!(matches!(
+            record.replay(&output),
+            Err(Error::Verification(VerificationError::ReplayMismatch))
+        ));
+        let mut wrong_seed = record;
+        wrong_seed.seed = bytes_to_hex_string(&[6u8; 32]);
+        assert!(matches!(
+            wrong_seed.replay(&input),
+            Err(Error::Verification(VerificationError::ReplayMismatch))
+        ));
+    }
+}
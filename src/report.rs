@@ -0,0 +1,429 @@
+// Verifying a transcript from the start of the ceremony, while recording every
+// check which was performed, so that the result can be published as the audit
+// artifact of the ceremony.
+//
+// The update proofs of every ceremony are checked together, so the time taken
+// to check the chain is reported for each ceremony. The time reported for a
+// contribution covers the checks of that contribution alone, which are its
+// signatures and its digest.
+use crate::{
+    error::{ParameterError, VerificationError},
+    identity::Identity,
+    keypair::verify_identity,
+    sdk::{
+        transcript::{contribution_digest, Transcript},
+        CEREMONIES, NUM_CEREMONIES,
+    },
+    serialisation::bytes_to_hex_string,
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+use ark_bls12_381::{Fr, G1Projective};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+// A contribution to the transcript, as published by the sequencer
+#[derive(Debug, Clone)]
+pub struct ContributionRecord {
+    pub update_proofs: [UpdateProof; NUM_CEREMONIES],
+    // The identity which the contributor signed, if any
+    pub identity: Option<Identity>,
+    // The signature of the identity for each ceremony, see `PrivateKey::sign_identity`
+    pub signatures: [Option<G1Projective>; NUM_CEREMONIES],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckStatus {
+    Passed,
+    Failed(VerificationError),
+    // The check could not be performed, because the data it needs is missing
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckReport {
+    pub name: String,
+    pub ceremony: Option<usize>,
+    pub status: CheckStatus,
+    pub duration_micros: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureStatus {
+    Unsigned,
+    Valid,
+    Invalid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContributionReport {
+    pub index: usize,
+    // The 0x prefixed hex encoding of the pot pubkey for each ceremony
+    pub pubkeys: Vec<String>,
+    pub signatures: Vec<SignatureStatus>,
+    pub digest: String,
+    pub duration_micros: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub checks: Vec<CheckReport>,
+    pub contributions: Vec<ContributionReport>,
+    // The digest of the final SRS of each ceremony, see `SRS::digest`
+    pub final_digests: Vec<String>,
+    pub contributions_root: String,
+}
+
+impl Transcript {
+    // Verifies that this transcript is the result of `contributions`, starting
+    // from the initial transcript
+    pub fn verify(
+        &self,
+        contributions: &[ContributionRecord],
+        random_element: Fr,
+    ) -> Result<(), VerificationError> {
+        self.verify_with_report(contributions, random_element)
+            .result()
+    }
+
+    // Verifies the transcript in the same way as `verify`, returning a report
+    // of every check instead of stopping at the first failure
+    pub fn verify_with_report(
+        &self,
+        contributions: &[ContributionRecord],
+        random_element: Fr,
+    ) -> VerificationReport {
+        let starting_transcript = Transcript::default();
+        let mut checks = Vec::new();
+
+        for (i, (srs, parameters)) in self.transcripts.iter().zip(CEREMONIES).enumerate() {
+            let (status, duration_micros) = timed(|| status_of(check_parameters(srs, parameters)));
+            checks.push(CheckReport {
+                name: "parameters".to_string(),
+                ceremony: Some(i),
+                status,
+                duration_micros,
+            });
+
+            let (status, duration_micros) = timed(|| status_of(srs.subgroup_check()));
+            checks.push(CheckReport {
+                name: "subgroup check".to_string(),
+                ceremony: Some(i),
+                status,
+                duration_micros,
+            });
+
+            let (status, duration_micros) = timed(|| {
+                let update_proofs: Vec<_> = contributions
+                    .iter()
+                    .map(|contribution| contribution.update_proofs[i])
+                    .collect();
+                status_of(SRS::verify_updates(
+                    &starting_transcript.transcripts[i],
+                    srs,
+                    &update_proofs,
+                    random_element,
+                ))
+            });
+            checks.push(CheckReport {
+                name: "update proofs".to_string(),
+                ceremony: Some(i),
+                status,
+                duration_micros,
+            });
+        }
+
+        // The digests are only in transcripts which were created since they were added
+        let recorded_digests = self.contributions.digests();
+        let (status, duration_micros) = timed(|| {
+            if recorded_digests.is_empty() {
+                CheckStatus::Skipped
+            } else if recorded_digests.len() == contributions.len()
+                && contributions
+                    .iter()
+                    .zip(recorded_digests)
+                    .all(|(contribution, digest)| {
+                        contribution_digest(&contribution.update_proofs) == *digest
+                    })
+            {
+                CheckStatus::Passed
+            } else {
+                CheckStatus::Failed(VerificationError::ContributionDigestMismatch)
+            }
+        });
+        checks.push(CheckReport {
+            name: "contribution digests".to_string(),
+            ceremony: None,
+            status,
+            duration_micros,
+        });
+
+        let contributions = contributions
+            .iter()
+            .enumerate()
+            .map(|(index, contribution)| {
+                let ((signatures, digest), duration_micros) = timed(|| {
+                    (
+                        signature_statuses(contribution),
+                        contribution_digest(&contribution.update_proofs),
+                    )
+                });
+                ContributionReport {
+                    index,
+                    pubkeys: contribution
+                        .update_proofs
+                        .iter()
+                        .map(UpdateProof::get_commitment_to_secret)
+                        .collect(),
+                    signatures,
+                    digest: bytes_to_hex_string(&digest),
+                    duration_micros,
+                }
+            })
+            .collect();
+
+        VerificationReport {
+            checks,
+            contributions,
+            final_digests: self
+                .transcripts
+                .iter()
+                .map(|srs| srs.digest().to_string())
+                .collect(),
+            contributions_root: bytes_to_hex_string(&self.contributions_root()),
+        }
+    }
+}
+
+fn signature_statuses(contribution: &ContributionRecord) -> Vec<SignatureStatus> {
+    contribution
+        .update_proofs
+        .iter()
+        .zip(&contribution.signatures)
+        .map(
+            |(update_proof, signature)| match (&contribution.identity, signature) {
+                (Some(identity), Some(signature)) => {
+                    match verify_identity(update_proof.commitment_to_secret, identity, *signature) {
+                        Ok(()) => SignatureStatus::Valid,
+                        Err(_) => SignatureStatus::Invalid,
+                    }
+                }
+                _ => SignatureStatus::Unsigned,
+            },
+        )
+        .collect()
+}
+
+impl VerificationReport {
+    // Whether every check which was performed passed. Invalid signatures do
+    // not fail the transcript, since signing is optional
+    pub fn passed(&self) -> bool {
+        self.result().is_ok()
+    }
+
+    // The first check which failed
+    pub fn result(&self) -> Result<(), VerificationError> {
+        for check in &self.checks {
+            if let CheckStatus::Failed(error) = check.status {
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "report")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a report can always be serialised")
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        // Writing to a String cannot fail
+        let _ = self.write_markdown(&mut markdown);
+        markdown
+    }
+
+    fn write_markdown(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "# Transcript verification report")?;
+        writeln!(out)?;
+        let result = if self.passed() { "passed" } else { "failed" };
+        writeln!(out, "Result: **{}**", result)?;
+        writeln!(out)?;
+
+        writeln!(out, "## Checks")?;
+        writeln!(out)?;
+        writeln!(out, "| Check | Ceremony | Status | Time (ms) |")?;
+        writeln!(out, "| --- | --- | --- | --- |")?;
+        for check in &self.checks {
+            let ceremony = match check.ceremony {
+                Some(ceremony) => ceremony.to_string(),
+                None => "all".to_string(),
+            };
+            let status = match check.status {
+                CheckStatus::Passed => "passed".to_string(),
+                CheckStatus::Failed(error) => format!("failed: {}", error),
+                CheckStatus::Skipped => "skipped".to_string(),
+            };
+            writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                check.name,
+                ceremony,
+                status,
+                to_millis(check.duration_micros)
+            )?;
+        }
+        writeln!(out)?;
+
+        writeln!(out, "## Contributions")?;
+        for contribution in &self.contributions {
+            writeln!(out)?;
+            writeln!(out, "### Contribution {}", contribution.index)?;
+            writeln!(out)?;
+            writeln!(out, "- Digest: `{}`", contribution.digest)?;
+            writeln!(
+                out,
+                "- Time (ms): {}",
+                to_millis(contribution.duration_micros)
+            )?;
+            for (i, (pubkey, signature)) in contribution
+                .pubkeys
+                .iter()
+                .zip(&contribution.signatures)
+                .enumerate()
+            {
+                writeln!(
+                    out,
+                    "- Ceremony {}: pubkey `{}`, signature {:?}",
+                    i, pubkey, signature
+                )?;
+            }
+        }
+        writeln!(out)?;
+
+        writeln!(out, "## Final digests")?;
+        writeln!(out)?;
+        for (i, digest) in self.final_digests.iter().enumerate() {
+            writeln!(out, "- Ceremony {}: `{}`", i, digest)?;
+        }
+        writeln!(out, "- Contributions root: `{}`", self.contributions_root)
+    }
+}
+
+// Checks that the SRS has as many G1 and G2 elements as its ceremony needs
+fn check_parameters(srs: &SRS, parameters: Parameters) -> Result<(), VerificationError> {
+    if srs.g1_elements().len() != parameters.num_g1_elements() {
+        return Err(VerificationError::InvalidParameters(
+            ParameterError::WrongNumberOfG1Elements {
+                expected: parameters.num_g1_elements(),
+                got: srs.g1_elements().len(),
+            },
+        ));
+    }
+    if srs.g2_elements().len() != parameters.num_g2_elements() {
+        return Err(VerificationError::InvalidParameters(
+            ParameterError::WrongNumberOfG2Elements {
+                expected: parameters.num_g2_elements(),
+                got: srs.g2_elements().len(),
+            },
+        ));
+    }
+    Ok(())
+}
+
+fn status_of(result: Result<(), VerificationError>) -> CheckStatus {
+    match result {
+        Ok(()) => CheckStatus::Passed,
+        Err(error) => CheckStatus::Failed(error),
+    }
+}
+
+fn to_millis(duration_micros: u64) -> String {
+    format!("{:.3}", duration_micros as f64 / 1000.0)
+}
+
+// Runs `f`, returning its result and how long it took in microseconds
+#[cfg(not(target_arch = "wasm32"))]
+fn timed<T>(f: impl FnOnce() -> T) -> (T, u64) {
+    let start = std::time::Instant::now();
+    let result = f();
+    (result, start.elapsed().as_micros() as u64)
+}
+// `Instant` is not available on wasm32-unknown-unknown, so the times are zero
+#[cfg(target_arch = "wasm32")]
+fn timed<T>(f: impl FnOnce() -> T) -> (T, u64) {
+    (f(), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keypair::PrivateKey, sdk::transcript::update_transcript};
+
+    #[test]
+    fn report_of_transcript() {
+        let secrets = [
+            "0x0101".to_string(),
+            "0x0202".to_string(),
+            "0x0303".to_string(),
+            "0x0404".to_string(),
+        ];
+        let (transcript, update_proofs) =
+            update_transcript(Transcript::default(), secrets.clone()).unwrap();
+
+        let identity = Identity::github(1234, "contributor");
+        let mut signatures = [None; NUM_CEREMONIES];
+        for (signature, secret) in signatures.iter_mut().zip(&secrets).take(2) {
+            *signature = Some(
+                PrivateKey::from_hex(secret)
+                    .unwrap()
+                    .sign_identity(&identity),
+            );
+        }
+        // The signature of the third ceremony uses the wrong key
+        signatures[2] = Some(PrivateKey::from_u64(5).sign_identity(&identity));
+        let contributions = vec![ContributionRecord {
+            update_proofs,
+            identity: Some(identity),
+            signatures,
+        }];
+
+        let report = transcript.verify_with_report(&contributions, Fr::from(123456789u64));
+        assert!(report.passed());
+        assert!(transcript
+            .verify(&contributions, Fr::from(123456789u64))
+            .is_ok());
+        assert_eq!(report.checks.len(), 3 * NUM_CEREMONIES + 1);
+        assert_eq!(
+            report.contributions[0].signatures,
+            vec![
+                SignatureStatus::Valid,
+                SignatureStatus::Valid,
+                SignatureStatus::Invalid,
+                SignatureStatus::Unsigned
+            ]
+        );
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("Result: **passed**"));
+        assert!(markdown.contains(&report.final_digests[3]));
+
+        // Without the contribution, the update proofs are missing
+        let report = transcript.verify_with_report(&[], Fr::from(123456789u64));
+        assert_eq!(report.result(), Err(VerificationError::NoUpdateProofs));
+        assert!(report.to_markdown().contains("Result: **failed**"));
+
+        // An SRS with the wrong number of powers fails the parameters check
+        let mut transcript = transcript;
+        transcript.transcripts[1] = SRS::new(Parameters::new(4, 2)).unwrap();
+        let report = transcript.verify_with_report(&contributions, Fr::from(123456789u64));
+        assert_eq!(
+            report.checks[3].status,
+            CheckStatus::Failed(VerificationError::InvalidParameters(
+                ParameterError::WrongNumberOfG1Elements {
+                    expected: CEREMONIES[1].num_g1_elements(),
+                    got: 4
+                }
+            ))
+        );
+    }
+}
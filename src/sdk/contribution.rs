@@ -1,13 +1,17 @@
 use ark_bls12_381::Fr;
 use ark_ff::PrimeField;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::{
-    keypair::PrivateKey,
-    srs::SRS,
+    error::{CeremonyError, Error, SerialisationError, VerificationError},
+    keypair::{with_fresh_secret, PrivateKey},
+    progress::{NoProgress, ProgressSink},
+    sdk::{check_each_ceremony, CEREMONIES, NUM_CEREMONIES},
+    serialisation::{hex_string_to_bytes, serialised_size, Encoding, SRSJson, SubgroupCheck},
+    srs::{VerificationLevel, SRS},
     update_proof::UpdateProof,
-    serialisation::SRSJson,
-    sdk::{NUM_CEREMONIES, CEREMONIES},
 };
 
 pub struct Contribution {
@@ -27,46 +31,156 @@ impl Default for Contribution {
     }
 }
 
-pub fn update_contribution(
-    mut contribution: Contribution,
-    secrets: [String; NUM_CEREMONIES],
-) -> Option<(Contribution, [UpdateProof; NUM_CEREMONIES])> {
-    // Check that the parameters for each SRS is correct
-    for (srs, params) in contribution.contributions.iter().zip(CEREMONIES.into_iter()) {
-        if srs.g1_elements().len() != params.num_g1_elements_needed {
-            return None;
+impl Contribution {
+    // Converts the json representation into a contribution, returning an error
+    // if any of the SRS could not be deserialised
+    pub fn from_json(contribution_json: &ContributionJSON) -> Result<Self, Error> {
+        Contribution::from_json_with(contribution_json, SubgroupCheck::Full)
+    }
+    // Converts the json representation into a contribution, checking the
+    // points are in the prime order subgroup as `subgroup_check` specifies
+    pub fn from_json_with(
+        contribution_json: &ContributionJSON,
+        subgroup_check: SubgroupCheck,
+    ) -> Result<Self, Error> {
+        let mut contributions = Vec::with_capacity(NUM_CEREMONIES);
+
+        for srs_json in &contribution_json.contributions {
+            contributions.push(SRS::deserialise(
+                (
+                    &srs_json.powers_of_tau.g1_powers,
+                    &srs_json.powers_of_tau.g2_powers,
+                ),
+                srs_json.parameters(),
+                subgroup_check,
+            )?);
+        }
+
+        Ok(Self {
+            // There is exactly one SRS for each ceremony in the json representation
+            contributions: contributions.try_into().unwrap(),
+        })
+    }
+
+    // Deserialises a contribution which was serialised using `serialise_bytes`
+    pub fn deserialise_bytes(bytes: &[u8], subgroup_check: SubgroupCheck) -> Result<Self, Error> {
+        let expected: usize = CEREMONIES
+            .iter()
+            .map(|parameters| serialised_size(*parameters, Encoding::Bytes).unwrap())
+            .sum();
+        if bytes.len() != expected {
+            return Err(SerialisationError::InvalidLength {
+                expected,
+                got: bytes.len(),
+            }
+            .into());
         }
-        if srs.g2_elements().len() != params.num_g2_elements_needed {
-            return None;
+
+        let mut contributions = Vec::with_capacity(NUM_CEREMONIES);
+        let mut rest = bytes;
+        for parameters in CEREMONIES {
+            let (srs_bytes, remaining) =
+                rest.split_at(serialised_size(parameters, Encoding::Bytes).unwrap());
+            contributions.push(SRS::deserialise_bytes(
+                srs_bytes,
+                parameters,
+                subgroup_check,
+            )?);
+            rest = remaining;
         }
+
+        Ok(Self {
+            contributions: contributions.try_into().unwrap(),
+        })
+    }
+    // Serialises each SRS using `SRS::serialise_bytes`, in the order of the ceremonies
+    pub fn serialise_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for srs in &self.contributions {
+            bytes.extend(srs.serialise_bytes());
+        }
+        bytes
     }
 
+    // The SHA-256 hash of `serialise_bytes`. This is what a participant signs
+    // to attest to their contribution, so it only depends on the points and
+    // not on how the contribution was encoded when it was received
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        for srs in &self.contributions {
+            srs.write_to(&mut hasher)
+                .expect("writing to a hasher cannot fail");
+        }
+        hasher.finalize().into()
+    }
+
+    // Check that the parameters for each SRS is correct
+    pub fn check_parameters(&self) -> Result<(), CeremonyError> {
+        for (i, (srs, params)) in self.contributions.iter().zip(CEREMONIES).enumerate() {
+            if srs.g1_elements().len() != params.num_g1_elements_needed {
+                return Err(CeremonyError::UnexpectedParameters(i));
+            }
+            if srs.g2_elements().len() != params.num_g2_elements_needed {
+                return Err(CeremonyError::UnexpectedParameters(i));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn update_contribution(
+    mut contribution: Contribution,
+    secrets: [String; NUM_CEREMONIES],
+) -> Result<(Contribution, [UpdateProof; NUM_CEREMONIES]), Error> {
+    contribution.check_parameters()?;
+
     let mut update_proofs = Vec::with_capacity(NUM_CEREMONIES);
 
     for (i, secret_hex) in secrets.into_iter().enumerate() {
-        if let Some(stripped_point_json) = secret_hex.strip_prefix("0x") {
-            let bytes = hex::decode(stripped_point_json).ok()?;
-            let priv_key = PrivateKey::from_bytes(&bytes);
-
-            let update_proof = contribution.contributions[i].update(priv_key);
-            update_proofs.push(update_proof);
-        } else {
-            return None;
-        }
+        let priv_key = PrivateKey::from_hex(&secret_hex)?;
+
+        let update_proof = contribution.contributions[i].update(priv_key);
+        update_proofs.push(update_proof);
     }
 
     let update_proofs: [UpdateProof; NUM_CEREMONIES] = update_proofs.try_into().unwrap();
 
-    Some((contribution, update_proofs))
+    Ok((contribution, update_proofs))
 }
 
-pub fn contribution_subgroup_check(contribution: Contribution) -> bool {
-    for srs in &contribution.contributions {
-        if !srs.subgroup_check() {
-            return false;
-        }
+// Updates each SRS in the contribution using an independent private key
+// generated from `rng`
+pub fn update_contribution_with_rng<R: Rng>(
+    contribution: Contribution,
+    rng: &mut R,
+) -> Result<(Contribution, [UpdateProof; NUM_CEREMONIES]), CeremonyError> {
+    update_contribution_with_progress(contribution, rng, &mut NoProgress)
+}
+
+// The same as `update_contribution_with_rng`, reporting the progress of each
+// update to `progress`
+pub fn update_contribution_with_progress<R: Rng>(
+    mut contribution: Contribution,
+    rng: &mut R,
+    progress: &mut impl ProgressSink,
+) -> Result<(Contribution, [UpdateProof; NUM_CEREMONIES]), CeremonyError> {
+    contribution.check_parameters()?;
+
+    let mut update_proofs = Vec::with_capacity(NUM_CEREMONIES);
+
+    for srs in contribution.contributions.iter_mut() {
+        update_proofs.push(with_fresh_secret(&mut *rng, |priv_key| {
+            srs.update_with_progress(priv_key, &mut *progress)
+        }));
     }
-    true
+
+    let update_proofs: [UpdateProof; NUM_CEREMONIES] = update_proofs.try_into().unwrap();
+
+    Ok((contribution, update_proofs))
+}
+
+pub fn contribution_subgroup_check(contribution: Contribution) -> Result<(), VerificationError> {
+    check_each_ceremony(|i| contribution.contributions[i].subgroup_check())
 }
 
 pub fn contribution_verify_update(
@@ -74,39 +188,49 @@ pub fn contribution_verify_update(
     new_contribution: &Contribution,
     update_proofs: &[UpdateProof; NUM_CEREMONIES],
     random_hex_elements: [String; NUM_CEREMONIES],
-) -> bool {
-    for i in 0..NUM_CEREMONIES {
-        // Decode random hex string into a field element
-        //
-        //
-        let hex_str = &random_hex_elements[i];
-        let hex_str = if let Some(stripped_random_hex) = hex_str.strip_prefix("0x") {
-            stripped_random_hex
-        } else {
-            return false;
-        };
-
-        let element = match hex::decode(hex_str) {
-            Ok(bytes) => Fr::from_be_bytes_mod_order(&bytes),
-            Err(_) => return false,
-        };
-
-        // Verify update
-        //
-        let proof = update_proofs[i];
+) -> Result<(), Error> {
+    contribution_verify_update_at_level(
+        old_contribution,
+        new_contribution,
+        update_proofs,
+        random_hex_elements,
+        VerificationLevel::Standard,
+    )
+}
+
+pub fn contribution_verify_update_at_level(
+    old_contribution: &Contribution,
+    new_contribution: &Contribution,
+    update_proofs: &[UpdateProof; NUM_CEREMONIES],
+    random_hex_elements: [String; NUM_CEREMONIES],
+    level: VerificationLevel,
+) -> Result<(), Error> {
+    // Decode random hex strings into field elements
+    let mut elements = Vec::with_capacity(NUM_CEREMONIES);
+    for random_hex in &random_hex_elements {
+        let bytes = hex_string_to_bytes(random_hex)?;
+        elements.push(Fr::from_be_bytes_mod_order(&bytes));
+    }
+
+    // Verify updates
+    check_each_ceremony(|i| {
         let before = &old_contribution.contributions[i];
         let after = &new_contribution.contributions[i];
-        if !SRS::verify_update(before, after, &proof, element) {
-            return false;
-        };
-    }
+        SRS::verify_updates_at_level(before, after, &update_proofs[i..i + 1], elements[i], level)
+    })?;
 
-    true
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContributionJSON {
     pub contributions: [SRSJson; NUM_CEREMONIES],
+    #[serde(
+        rename = "ecdsaSignature",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ecdsa_signature: Option<String>,
 }
 
 impl From<&Contribution> for ContributionJSON {
@@ -118,28 +242,48 @@ impl From<&Contribution> for ContributionJSON {
             .map(|srs| SRSJson::from(&srs));
         Self {
             contributions: contributions_json,
+            ecdsa_signature: None,
         }
     }
 }
 
-impl From<&ContributionJSON> for Contribution {
-    fn from(contribution_json: &ContributionJSON) -> Self {
-        // TODO: find a cleaner way to write this
-        let contributions_option: [Option<SRS>; NUM_CEREMONIES] = contribution_json
-            .contributions
-            .clone()
-            .map(|srs_json| (&srs_json).into());
+impl TryFrom<&ContributionJSON> for Contribution {
+    type Error = Error;
 
-        let mut contributions = Vec::new();
+    fn try_from(contribution_json: &ContributionJSON) -> Result<Self, Self::Error> {
+        Contribution::from_json(contribution_json)
+    }
+}
 
-        for optional_srs in contributions_option {
-            match optional_srs {
-                Some(srs) => contributions.push(srs),
-                None => return Contribution::default(),
-            }
-        }
-        Self {
-            contributions: contributions.try_into().unwrap(),
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_only_depends_on_the_points() {
+        let mut contribution = Contribution::default();
+        contribution.contributions[1].update(PrivateKey::from_u64(252));
+
+        let bytes = contribution.serialise_bytes();
+        let decoded = Contribution::deserialise_bytes(&bytes, SubgroupCheck::None).unwrap();
+        assert_eq!(decoded.digest(), contribution.digest());
+        assert_eq!(
+            contribution.digest(),
+            <[u8; 32]>::from(Sha256::digest(&bytes))
+        );
+
+        assert_ne!(Contribution::default().digest(), contribution.digest());
+        assert!(Contribution::deserialise_bytes(&bytes[1..], SubgroupCheck::None).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn invalid_json_is_not_replaced_by_the_default() {
+        let mut contribution = Contribution::default();
+        contribution.contributions[0].update(PrivateKey::from_u64(252));
+        let mut contribution_json = ContributionJSON::from(&contribution);
+        assert!(Contribution::try_from(&contribution_json).is_ok());
+
+        contribution_json.contributions[0].powers_of_tau.g1_powers[1] = "0x00".to_string();
+        assert!(Contribution::try_from(&contribution_json).is_err());
+    }
+}
@@ -1,8 +1,11 @@
-use crate::srs::Parameters;
+use crate::srs::{Parameters, DEFAULT_MAX_NUM_ELEMENTS};
 
-pub mod transcript;
-pub mod contribution;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
+pub mod contribution;
+pub mod precheck;
+pub mod transcript;
 
 pub const NUM_CEREMONIES: usize = 4;
 
@@ -10,17 +13,34 @@ pub const CEREMONIES: [Parameters; NUM_CEREMONIES] = [
     Parameters {
         num_g1_elements_needed: 4096,
         num_g2_elements_needed: 65,
+        max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
     },
     Parameters {
         num_g1_elements_needed: 8192,
         num_g2_elements_needed: 65,
+        max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
     },
     Parameters {
         num_g1_elements_needed: 16384,
         num_g2_elements_needed: 65,
+        max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
     },
     Parameters {
         num_g1_elements_needed: 32768,
         num_g2_elements_needed: 65,
+        max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
     },
-];
\ No newline at end of file
+];
+
+// Runs `check` for each SRS of a batch. The SRS are independent, so with the
+// `parallel` feature they are checked at the same time, each using the
+// thread pool for its own parallel work. Every check runs to completion, and
+// the error of the first SRS which failed is returned
+pub(crate) fn check_each_ceremony<E: Send>(
+    check: impl Fn(usize) -> Result<(), E> + Sync + Send,
+) -> Result<(), E> {
+    let results: Vec<_> = ark_std::cfg_into_iter!(0..NUM_CEREMONIES)
+        .map(check)
+        .collect();
+    results.into_iter().collect()
+}
@@ -0,0 +1,204 @@
+// A quick check of a batch contribution which was downloaded from the sequencer,
+// so that a participant learns that it is unusable in a few seconds rather than
+// after computing their contribution.
+//
+// Every point is decoded, which checks that it is on the curve, however only
+// a sample of the points are checked to be in the prime order subgroup and the
+// structure of the SRS beyond the degree-1 powers is not checked. Passing the
+// precheck does not mean that the contribution is valid.
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_ec::AffineCurve;
+use ark_ff::Zero;
+use rand::Rng;
+
+use crate::{
+    error::{PrecheckError, SerialisationError},
+    interop_subgroup_checks::{g1, g2},
+    sdk::{contribution::ContributionJSON, CEREMONIES},
+    serialisation::{hex_string_to_g1, hex_string_to_g2, SRSJson},
+    srs::Parameters,
+    verification,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// The number of randomly chosen powers in each group of each SRS which are
+// subgroup checked, along with the degree-1 power
+pub const PRECHECK_SAMPLES: usize = 32;
+
+pub fn precheck_contribution<R: Rng>(
+    contribution: &ContributionJSON,
+    rng: &mut R,
+) -> Result<(), PrecheckError> {
+    for (ceremony, (srs, parameters)) in contribution
+        .contributions
+        .iter()
+        .zip(CEREMONIES)
+        .enumerate()
+    {
+        precheck_srs(ceremony, srs, parameters, rng)?;
+    }
+    Ok(())
+}
+
+fn precheck_srs<R: Rng>(
+    ceremony: usize,
+    srs: &SRSJson,
+    parameters: Parameters,
+    rng: &mut R,
+) -> Result<(), PrecheckError> {
+    let g1_powers = &srs.powers_of_tau.g1_powers;
+    let g2_powers = &srs.powers_of_tau.g2_powers;
+
+    let wrong_number = |group, got| PrecheckError::WrongNumberOfPowers {
+        ceremony,
+        group,
+        expected: if group == 1 {
+            parameters.num_g1_elements()
+        } else {
+            parameters.num_g2_elements()
+        },
+        got,
+    };
+    for got in [srs.num_g1_powers, g1_powers.len()] {
+        if got != parameters.num_g1_elements() {
+            return Err(wrong_number(1, got));
+        }
+    }
+    for got in [srs.num_g2_powers, g2_powers.len()] {
+        if got != parameters.num_g2_elements() {
+            return Err(wrong_number(2, got));
+        }
+    }
+
+    let g1_points = decode_points(ceremony, 1, g1_powers, hex_string_to_g1)?;
+    let g2_points = decode_points(ceremony, 2, g2_powers, hex_string_to_g2)?;
+
+    if g1_points[0] != G1Affine::prime_subgroup_generator() {
+        return Err(PrecheckError::NotGenerator { ceremony, group: 1 });
+    }
+    if g2_points[0] != G2Affine::prime_subgroup_generator() {
+        return Err(PrecheckError::NotGenerator { ceremony, group: 2 });
+    }
+    if g1_points[1].is_zero()
+        || g2_points[1].is_zero()
+        || !verification::same_ratio([g1_points[0], g1_points[1]], [g2_points[0], g2_points[1]])
+    {
+        return Err(PrecheckError::InvalidDegreeOne { ceremony });
+    }
+
+    for index in sample_indices(g1_points.len(), rng) {
+        if !g1::is_in_correct_subgroup_assuming_on_curve(&g1_points[index]) {
+            return Err(PrecheckError::NotInSubgroup {
+                ceremony,
+                group: 1,
+                index,
+            });
+        }
+    }
+    for index in sample_indices(g2_points.len(), rng) {
+        if !g2::is_in_correct_subgroup_assuming_on_curve(&g2_points[index]) {
+            return Err(PrecheckError::NotInSubgroup {
+                ceremony,
+                group: 2,
+                index,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_points<A: Send, F>(
+    ceremony: usize,
+    group: u8,
+    powers: &[String],
+    decode: F,
+) -> Result<Vec<A>, PrecheckError>
+where
+    F: Fn(&str) -> Result<A, SerialisationError> + Sync,
+{
+    ark_std::cfg_iter!(powers)
+        .enumerate()
+        .map(|(index, power)| {
+            decode(power).map_err(|error| PrecheckError::InvalidPoint {
+                ceremony,
+                group,
+                index,
+                error,
+            })
+        })
+        .collect()
+}
+
+// The degree-1 power is always included, since it is the one that
+// the update proofs commit to
+fn sample_indices<R: Rng>(num_points: usize, rng: &mut R) -> Vec<usize> {
+    let mut indices = vec![1];
+    indices.extend((0..PRECHECK_SAMPLES).map(|_| rng.gen_range(0..num_points)));
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keypair::PrivateKey, sdk::contribution::Contribution};
+
+    fn contribution_json() -> ContributionJSON {
+        let mut contribution = Contribution::default();
+        for (index, srs) in contribution.contributions.iter_mut().enumerate() {
+            srs.update(PrivateKey::from_u64(2 + index as u64));
+        }
+        ContributionJSON {
+            contributions: contribution
+                .contributions
+                .iter()
+                .map(SRSJson::from)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            ecdsa_signature: None,
+        }
+    }
+
+    #[test]
+    fn precheck_reports_the_failing_power() {
+        let mut rng = rand::thread_rng();
+        let valid = contribution_json();
+        assert!(precheck_contribution(&valid, &mut rng).is_ok());
+
+        let mut truncated = valid.clone();
+        truncated.contributions[2].powers_of_tau.g2_powers.pop();
+        assert!(matches!(
+            precheck_contribution(&truncated, &mut rng),
+            Err(PrecheckError::WrongNumberOfPowers {
+                ceremony: 2,
+                group: 2,
+                expected: 65,
+                got: 64
+            })
+        ));
+
+        let mut invalid_point = valid.clone();
+        invalid_point.contributions[1].powers_of_tau.g1_powers[100] = "0x1234".to_string();
+        assert!(matches!(
+            precheck_contribution(&invalid_point, &mut rng),
+            Err(PrecheckError::InvalidPoint {
+                ceremony: 1,
+                group: 1,
+                index: 100,
+                ..
+            })
+        ));
+
+        // The degree-1 powers of two different SRS were created using different secrets
+        let mut mixed = valid.clone();
+        mixed.contributions[0].powers_of_tau.g2_powers[1] =
+            valid.contributions[1].powers_of_tau.g2_powers[1].clone();
+        assert!(matches!(
+            precheck_contribution(&mixed, &mut rng),
+            Err(PrecheckError::InvalidDegreeOne { ceremony: 0 })
+        ));
+    }
+}
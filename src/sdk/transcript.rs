@@ -1,18 +1,22 @@
 use ark_bls12_381::Fr;
 use ark_ff::PrimeField;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::{
+    error::{CeremonyError, Error, SerialisationError, VerificationError},
     keypair::PrivateKey,
-    srs::SRS,
+    merkle::{InclusionProof, MerkleTree},
+    sdk::{check_each_ceremony, CEREMONIES, NUM_CEREMONIES},
+    serialisation::{bytes_to_hex_string, hex_string_to_bytes, SRSJson},
+    srs::{VerificationLevel, SRS},
     update_proof::UpdateProof,
-    serialisation::SRSJson,
-    sdk::{NUM_CEREMONIES, CEREMONIES},
 };
 
-
 pub struct Transcript {
     pub transcripts: [SRS; NUM_CEREMONIES],
+    // The digest of each contribution, see `contribution_digest`
+    pub contributions: MerkleTree,
 }
 
 impl Default for Transcript {
@@ -24,50 +28,106 @@ impl Default for Transcript {
                 SRS::new(CEREMONIES[2]).unwrap(),
                 SRS::new(CEREMONIES[3]).unwrap(),
             ],
+            contributions: MerkleTree::new(),
         }
     }
 }
 
+impl Transcript {
+    // Converts the json representation into a transcript, returning an error
+    // if any of the SRS could not be deserialised
+    pub fn from_json(transcript_json: &TranscriptJSON) -> Result<Self, Error> {
+        let mut transcripts = Vec::with_capacity(NUM_CEREMONIES);
+
+        for srs_json in &transcript_json.transcripts {
+            transcripts.push(SRS::try_from(srs_json)?);
+        }
+
+        let mut contributions = MerkleTree::new();
+        for digest_hex in &transcript_json.contribution_digests {
+            let digest = hex_string_to_bytes(digest_hex)?;
+            let digest =
+                digest
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| SerialisationError::InvalidLength {
+                        expected: 32,
+                        got: digest.len(),
+                    })?;
+            contributions.push(digest);
+        }
+
+        Ok(Self {
+            // There is exactly one SRS for each ceremony in the json representation
+            transcripts: transcripts.try_into().unwrap(),
+            contributions,
+        })
+    }
+
+    // The root of the Merkle tree over the contributions, which is given to
+    // participants in their receipts
+    pub fn contributions_root(&self) -> [u8; 32] {
+        self.contributions.root()
+    }
+    // The SHA-256 hash of every SRS followed by the root of the contributions,
+    // which is what gets timestamped, see `timestamp::DetachedTimestamp`
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        for srs in &self.transcripts {
+            srs.write_to(&mut hasher)
+                .expect("writing to a hasher cannot fail");
+        }
+        hasher.update(self.contributions_root());
+        hasher.finalize().into()
+    }
+    // Proves that the contribution at `index` is included in the transcript
+    pub fn inclusion_proof(&self, index: usize) -> Option<InclusionProof> {
+        self.contributions.inclusion_proof(index)
+    }
+}
+
+// The digest of a contribution, which is the hash of its update proof to each ceremony
+pub fn contribution_digest(update_proofs: &[UpdateProof]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for update_proof in update_proofs {
+        hasher.update(update_proof.serialise_bytes());
+    }
+    hasher.finalize().into()
+}
+
 pub fn update_transcript(
     mut transcript: Transcript,
     secrets: [String; NUM_CEREMONIES],
-) -> Option<(Transcript, [UpdateProof; NUM_CEREMONIES])> {
+) -> Result<(Transcript, [UpdateProof; NUM_CEREMONIES]), Error> {
     // Check that the parameters for each SRS is correct
-    for (srs, params) in transcript.transcripts.iter().zip(CEREMONIES.into_iter()) {
+    for (i, (srs, params)) in transcript.transcripts.iter().zip(CEREMONIES).enumerate() {
         if srs.g1_elements().len() != params.num_g1_elements_needed {
-            return None;
+            return Err(CeremonyError::UnexpectedParameters(i).into());
         }
         if srs.g2_elements().len() != params.num_g2_elements_needed {
-            return None;
+            return Err(CeremonyError::UnexpectedParameters(i).into());
         }
     }
 
     let mut update_proofs = Vec::with_capacity(NUM_CEREMONIES);
 
     for (i, secret_hex) in secrets.into_iter().enumerate() {
-        if let Some(stripped_point_json) = secret_hex.strip_prefix("0x") {
-            let bytes = hex::decode(stripped_point_json).ok()?;
-            let priv_key = PrivateKey::from_bytes(&bytes);
-
-            let update_proof = transcript.transcripts[i].update(priv_key);
-            update_proofs.push(update_proof);
-        } else {
-            return None;
-        }
+        let priv_key = PrivateKey::from_hex(&secret_hex)?;
+
+        let update_proof = transcript.transcripts[i].update(priv_key);
+        update_proofs.push(update_proof);
     }
 
     let update_proofs: [UpdateProof; NUM_CEREMONIES] = update_proofs.try_into().unwrap();
+    transcript
+        .contributions
+        .push(contribution_digest(&update_proofs));
 
-    Some((transcript, update_proofs))
+    Ok((transcript, update_proofs))
 }
 
-pub fn transcript_subgroup_check(transcript: Transcript) -> bool {
-    for srs in &transcript.transcripts {
-        if !srs.subgroup_check() {
-            return false;
-        }
-    }
-    true
+pub fn transcript_subgroup_check(transcript: Transcript) -> Result<(), VerificationError> {
+    check_each_ceremony(|i| transcript.transcripts[i].subgroup_check())
 }
 
 pub fn transcript_verify_update(
@@ -75,39 +135,50 @@ pub fn transcript_verify_update(
     new_transcript: &Transcript,
     update_proofs: &[UpdateProof; NUM_CEREMONIES],
     random_hex_elements: [String; NUM_CEREMONIES],
-) -> bool {
-    for i in 0..NUM_CEREMONIES {
-        // Decode random hex string into a field element
-        //
-        //
-        let hex_str = &random_hex_elements[i];
-        let hex_str = if let Some(stripped_random_hex) = hex_str.strip_prefix("0x") {
-            stripped_random_hex
-        } else {
-            return false;
-        };
-
-        let element = match hex::decode(hex_str) {
-            Ok(bytes) => Fr::from_be_bytes_mod_order(&bytes),
-            Err(_) => return false,
-        };
-
-        // Verify update
-        //
-        let proof = update_proofs[i];
+) -> Result<(), Error> {
+    transcript_verify_update_at_level(
+        old_transcript,
+        new_transcript,
+        update_proofs,
+        random_hex_elements,
+        VerificationLevel::Standard,
+    )
+}
+
+pub fn transcript_verify_update_at_level(
+    old_transcript: &Transcript,
+    new_transcript: &Transcript,
+    update_proofs: &[UpdateProof; NUM_CEREMONIES],
+    random_hex_elements: [String; NUM_CEREMONIES],
+    level: VerificationLevel,
+) -> Result<(), Error> {
+    // Decode random hex strings into field elements
+    let mut elements = Vec::with_capacity(NUM_CEREMONIES);
+    for random_hex in &random_hex_elements {
+        let bytes = hex_string_to_bytes(random_hex)?;
+        elements.push(Fr::from_be_bytes_mod_order(&bytes));
+    }
+
+    // Verify updates
+    check_each_ceremony(|i| {
         let before = &old_transcript.transcripts[i];
         let after = &new_transcript.transcripts[i];
-        if !SRS::verify_update(before, after, &proof, element) {
-            return false;
-        };
-    }
+        SRS::verify_updates_at_level(before, after, &update_proofs[i..i + 1], elements[i], level)
+    })?;
 
-    true
+    Ok(())
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TranscriptJSON {
     pub transcripts: [SRSJson; NUM_CEREMONIES],
+    // The 0x prefixed hex digest of each contribution, in order
+    #[serde(
+        rename = "contributionDigests",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub contribution_digests: Vec<String>,
 }
 
 impl From<&Transcript> for TranscriptJSON {
@@ -117,30 +188,23 @@ impl From<&Transcript> for TranscriptJSON {
             // TODO: can remove clone but will need to try_into for array size
             .clone()
             .map(|srs| SRSJson::from(&srs));
+        let contribution_digests = transcript
+            .contributions
+            .digests()
+            .iter()
+            .map(|digest| bytes_to_hex_string(digest))
+            .collect();
         Self {
             transcripts: transcripts_json,
+            contribution_digests,
         }
     }
 }
 
-impl From<&TranscriptJSON> for Transcript {
-    fn from(transcript_json: &TranscriptJSON) -> Self {
-        // TODO: find a cleaner way to write this
-        let transcripts_option: [Option<SRS>; NUM_CEREMONIES] = transcript_json
-            .transcripts
-            .clone()
-            .map(|srs_json| (&srs_json).into());
-
-        let mut transcripts = Vec::new();
+impl TryFrom<&TranscriptJSON> for Transcript {
+    type Error = Error;
 
-        for optional_srs in transcripts_option {
-            match optional_srs {
-                Some(srs) => transcripts.push(srs),
-                None => return Transcript::default(),
-            }
-        }
-        Self {
-            transcripts: transcripts.try_into().unwrap(),
-        }
+    fn try_from(transcript_json: &TranscriptJSON) -> Result<Self, Self::Error> {
+        Transcript::from_json(transcript_json)
     }
 }
@@ -0,0 +1,289 @@
+// Loading, checking and updating the G1 and G2 powers of an SRS separately.
+//
+// For KZG style parameters there are only a few G2 powers, but there can be
+// millions of G1 powers. A section holds a range of the powers in one group,
+// so that the G2 powers can stay in memory while the G1 powers are streamed
+// through a section at a time, for example
+//
+// let mut g2 = G2Section::read_g2_from(&mut file, parameters, 0..num_g2)?;
+// g2.update(&private_key);
+// g2.write_g2_to(&mut output, parameters)?;
+// for start in (0..num_g1).step_by(chunk_size) {
+//     let mut g1 = G1Section::read_from(&mut file, parameters, start..start + chunk_size)?;
+//     g1.update(&private_key);
+//     g1.write_to(&mut output, parameters)?;
+// }
+//
+// Sections are read from and written to the encoding used by `SRS::write_to`.
+// The G2 methods carry a `_g2` suffix, as both groups are instances of the
+// same generic affine type and so cannot share method names.
+use crate::{
+    error::{Error, SerialisationError, VerificationError},
+    interop_point_encoding::{
+        g1_from_reader, g2_from_reader, G1_SERIALISED_SIZE, G2_SERIALISED_SIZE,
+    },
+    keypair::PrivateKey,
+    serialisation::{check_bounds, g1s_to_bytes, g2s_to_bytes},
+    shard::powers_from,
+    srs::{subgroup_check_points, update_points, Parameters},
+    update_proof::UpdateProof,
+    verification::{self, PreparedG2Tau},
+};
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_ec::AffineCurve;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use zeroize::Zeroizing;
+
+// A range of the powers of tau in one group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<A> {
+    // The degree of the first power in the section
+    start: usize,
+    points: Vec<A>,
+}
+
+pub type G1Section = Section<G1Affine>;
+pub type G2Section = Section<G2Affine>;
+
+impl<A: AffineCurve<ScalarField = Fr>> Section<A> {
+    // Creates a section where `points` are the powers starting from tau^start
+    pub fn new(start: usize, points: Vec<A>) -> Self {
+        Section { start, points }
+    }
+
+    // The range of powers in this section
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.start + self.points.len()
+    }
+    pub fn points(&self) -> &[A] {
+        &self.points
+    }
+    pub fn into_points(self) -> Vec<A> {
+        self.points
+    }
+    // Returns tau^degree, or None if it is not in this section
+    pub fn power(&self, degree: usize) -> Option<A> {
+        self.points.get(degree.checked_sub(self.start)?).copied()
+    }
+
+    // Updates the powers in this section, as `SRS::update` would.
+    //
+    // Every section of the SRS, in both groups, must be updated using the same private key
+    pub fn update(&mut self, private_key: &PrivateKey) {
+        let powers_of_priv_key =
+            Zeroizing::new(powers_from(private_key.tau, self.start, self.points.len()));
+        update_points(&mut self.points, &powers_of_priv_key);
+    }
+}
+
+impl Section<G1Affine> {
+    // Checks that the powers in this section go up in powers of tau, where
+    // `g2_tau` are the degree-0 and degree-1 G2 powers of the same SRS.
+    //
+    // Consecutive sections should overlap by one power, so that every pair
+    // of consecutive powers is checked
+    pub fn verify(
+        &self,
+        g2_tau: [G2Affine; 2],
+        random_element: Fr,
+    ) -> Result<(), VerificationError> {
+        self.verify_prepared(&PreparedG2Tau::new(g2_tau), random_element)
+    }
+    // Checks the powers in the same way as `verify`, using G2 powers which
+    // were prepared once for every section of the SRS
+    pub fn verify_prepared(
+        &self,
+        g2_tau: &PreparedG2Tau,
+        random_element: Fr,
+    ) -> Result<(), VerificationError> {
+        if !verification::g1_powers_check_prepared(&self.points, g2_tau, random_element) {
+            return Err(VerificationError::InvalidStructure);
+        }
+        Ok(())
+    }
+
+    // Checks that the powers in this section are in the prime order subgroup
+    pub fn subgroup_check(&self) -> Result<(), VerificationError> {
+        subgroup_check_points(&self.points, &[])
+    }
+
+    // Reads the G1 powers in `range`, which is truncated to the number of G1 powers.
+    //
+    // The points are checked to be on the curve, but they are not subgroup checked
+    pub fn read_from<R: Read + Seek>(
+        reader: &mut R,
+        parameters: Parameters,
+        range: Range<usize>,
+    ) -> Result<Self, Error> {
+        check_bounds(parameters)?;
+        let end = std::cmp::min(range.end, parameters.num_g1_elements());
+        let range = std::cmp::min(range.start, end)..end;
+
+        reader
+            .seek(SeekFrom::Start((range.start * G1_SERIALISED_SIZE) as u64))
+            .map_err(SerialisationError::Io)?;
+
+        let mut points = Vec::with_capacity(range.len());
+        for _ in range.clone() {
+            points.push(g1_from_reader(reader)?);
+        }
+        Ok(Section::new(range.start, points))
+    }
+
+    // Writes the powers in this section to their position in an SRS with these parameters
+    pub fn write_to<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        parameters: Parameters,
+    ) -> Result<(), Error> {
+        check_bounds(parameters)?;
+
+        writer
+            .seek(SeekFrom::Start((self.start * G1_SERIALISED_SIZE) as u64))
+            .map_err(SerialisationError::Io)?;
+        writer
+            .write_all(&g1s_to_bytes(&self.points))
+            .map_err(SerialisationError::Io)?;
+        Ok(())
+    }
+
+    // Produces a proof of the update, once every section has been updated using `private_key`.
+    //
+    // Returns None if this section does not contain the degree-1 power
+    pub fn update_proof(&self, private_key: PrivateKey) -> Option<UpdateProof> {
+        let new_accumulated_point = self.power(1)?.into_projective();
+
+        Some(UpdateProof {
+            commitment_to_secret: private_key.to_public(),
+            new_accumulated_point,
+        })
+    }
+}
+
+impl Section<G2Affine> {
+    // Checks that the powers in this section go up in powers of tau, where
+    // `g1_tau` are the degree-0 and degree-1 G1 powers of the same SRS.
+    //
+    // Consecutive sections should overlap by one power, so that every pair
+    // of consecutive powers is checked
+    pub fn verify_g2(
+        &self,
+        g1_tau: [G1Affine; 2],
+        random_element: Fr,
+    ) -> Result<(), VerificationError> {
+        if !verification::g2_powers_check(&self.points, g1_tau, random_element) {
+            return Err(VerificationError::InvalidStructure);
+        }
+        Ok(())
+    }
+
+    // Checks that the powers in this section are in the prime order subgroup
+    pub fn subgroup_check_g2(&self) -> Result<(), VerificationError> {
+        subgroup_check_points(&[], &self.points)
+    }
+
+    // Reads the G2 powers in `range`, which is truncated to the number of G2 powers.
+    //
+    // The points are checked to be on the curve, but they are not subgroup checked
+    pub fn read_g2_from<R: Read + Seek>(
+        reader: &mut R,
+        parameters: Parameters,
+        range: Range<usize>,
+    ) -> Result<Self, Error> {
+        let (g1_len, _) = check_bounds(parameters)?;
+        let end = std::cmp::min(range.end, parameters.num_g2_elements());
+        let range = std::cmp::min(range.start, end)..end;
+
+        reader
+            .seek(SeekFrom::Start(
+                (g1_len + range.start * G2_SERIALISED_SIZE) as u64,
+            ))
+            .map_err(SerialisationError::Io)?;
+
+        let mut points = Vec::with_capacity(range.len());
+        for _ in range.clone() {
+            points.push(g2_from_reader(reader)?);
+        }
+        Ok(Section::new(range.start, points))
+    }
+
+    // Writes the powers in this section to their position in an SRS with these parameters
+    pub fn write_g2_to<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        parameters: Parameters,
+    ) -> Result<(), Error> {
+        let (g1_len, _) = check_bounds(parameters)?;
+
+        writer
+            .seek(SeekFrom::Start(
+                (g1_len + self.start * G2_SERIALISED_SIZE) as u64,
+            ))
+            .map_err(SerialisationError::Io)?;
+        writer
+            .write_all(&g2s_to_bytes(&self.points))
+            .map_err(SerialisationError::Io)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{serialisation::SubgroupCheck, srs::SRS};
+    use std::io::Cursor;
+
+    #[test]
+    fn update_g1_in_sections() {
+        let parameters = Parameters::new(100, 2);
+        let srs = SRS::new(parameters).unwrap();
+
+        let mut expected_srs = srs.clone();
+        let expected_proof = expected_srs.update(PrivateKey::from_u64(252));
+
+        let mut input = Cursor::new(srs.serialise_bytes());
+        let mut output = Cursor::new(vec![0u8; input.get_ref().len()]);
+
+        // The G2 powers stay in memory, while the G1 powers are updated 16 at a time
+        let mut g2 = G2Section::read_g2_from(&mut input, parameters, 0..2).unwrap();
+        g2.update(&PrivateKey::from_u64(252));
+        g2.write_g2_to(&mut output, parameters).unwrap();
+
+        let mut update_proof = None;
+        for start in (0..100).step_by(16) {
+            let mut g1 = G1Section::read_from(&mut input, parameters, start..start + 16).unwrap();
+            g1.update(&PrivateKey::from_u64(252));
+            g1.write_to(&mut output, parameters).unwrap();
+
+            if start == 0 {
+                update_proof = g1.update_proof(PrivateKey::from_u64(252));
+            }
+        }
+
+        let updated_srs =
+            SRS::deserialise_bytes(output.get_ref(), parameters, SubgroupCheck::Full).unwrap();
+        assert_eq!(updated_srs, expected_srs);
+        assert_eq!(update_proof, Some(expected_proof));
+
+        // The sections can also be checked separately, where each G1 section
+        // overlaps the next one by one power
+        let g2_tau = [g2.power(0).unwrap(), g2.power(1).unwrap()];
+        for start in (0..100).step_by(16) {
+            let g1 = G1Section::read_from(&mut output, parameters, start..start + 17).unwrap();
+            assert!(g1.subgroup_check().is_ok());
+            assert!(g1.verify(g2_tau, Fr::from(123456789u64)).is_ok());
+        }
+        let g1_section = G1Section::read_from(&mut output, parameters, 0..2).unwrap();
+        let g1_tau = [g1_section.power(0).unwrap(), g1_section.power(1).unwrap()];
+        assert!(g2.verify_g2(g1_tau, Fr::from(123456789u64)).is_ok());
+
+        // A section whose powers are not consecutive should be rejected
+        let mut bad_section = G1Section::read_from(&mut output, parameters, 32..48).unwrap();
+        bad_section.points.swap(3, 4);
+        assert_eq!(
+            bad_section.verify(g2_tau, Fr::from(123456789u64)),
+            Err(VerificationError::InvalidStructure)
+        );
+    }
+}
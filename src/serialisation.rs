@@ -1,73 +1,336 @@
+use crate::error::{Cancelled, Error, ParameterError, SerialisationError};
 use crate::interop_point_encoding::{
-    deserialize_g1, deserialize_g2, serialize_g1, serialize_g2, G1_SERIALISED_SIZE,
+    decode_g1_batch, decode_g2_batch, encode_g1_batch, encode_g2_batch, g1_from_reader,
+    g1_from_slice, g2_from_reader, g2_from_slice, serialize_g1, serialize_g2, G1_SERIALISED_SIZE,
     G2_SERIALISED_SIZE,
 };
+use crate::progress::{NoProgress, Phase, ProgressSink};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// The size of an update proof serialised using `UpdateProof::serialise_bytes`
+pub const UPDATE_PROOF_SERIALISED_SIZE: usize = G2_SERIALISED_SIZE + G1_SERIALISED_SIZE;
+// The size of an update proof serialised using `G1UpdateProof::serialise_bytes`
+pub const G1_UPDATE_PROOF_SERIALISED_SIZE: usize = G1_SERIALISED_SIZE + G2_SERIALISED_SIZE;
+
+// Whether the points of an SRS are checked to be in the prime order subgroup
+// when it is deserialised. Every point is always checked to be on the curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubgroupCheck {
+    // Check that every point is in the prime order subgroup, using the
+    // endomorphism based checks from https://eprint.iacr.org/2021/1130
+    Full,
+    // Check random linear combinations of the points, see `SRS::batched_subgroup_check`.
+    // This is much faster than `Full`, with a soundness error below 2^-64
+    Batched,
+    // Skip the subgroup checks.
+    //
+    // This is NOT safe for untrusted input. It should only be used to re-load
+    // an SRS which this process serialised itself, for example in benchmarks
+    // or when round-tripping through a local file.
+    None,
+}
 
-use crate::{
-    srs::{Parameters, SRS},
-    update_proof::UpdateProof,
-};
-use ark_bls12_381::{G1Projective, G2Projective};
-use ark_ec::{AffineCurve, ProjectiveCurve};
+// The encodings that an SRS can be serialised to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    // The compressed points, see `SRS::serialise_bytes` and `SRS::write_to`
+    Bytes,
+    // The 0x prefixed hex strings of the compressed points, see `SRS::serialise`.
+    // This does not include the quotes and commas of a json array
+    Hex,
+}
 
-fn hex_string_to_g1(hex_str: &str) -> Option<G1Projective> {
-    if let Some(stripped_point_json) = hex_str.strip_prefix("0x") {
-        let bytes = hex::decode(stripped_point_json).ok()?;
-        if bytes.len() != G1_SERIALISED_SIZE {
-            return None;
-        }
-        let mut fixed_array = [0u8; G1_SERIALISED_SIZE];
-        fixed_array.copy_from_slice(&bytes);
-        return Some(deserialize_g1(fixed_array)?.into_projective());
-    } else {
-        return None;
+// Returns the number of bytes that an SRS with the given parameters serialises to,
+// or None if it does not fit into a usize
+pub fn serialised_size(parameters: Parameters, encoding: Encoding) -> Option<usize> {
+    let (g1_size, g2_size) = match encoding {
+        Encoding::Bytes => (G1_SERIALISED_SIZE, G2_SERIALISED_SIZE),
+        // Two hex characters per byte, along with the 0x prefix
+        Encoding::Hex => (2 + 2 * G1_SERIALISED_SIZE, 2 + 2 * G2_SERIALISED_SIZE),
+    };
+
+    let g1_len = parameters.num_g1_elements_needed.checked_mul(g1_size)?;
+    let g2_len = parameters.num_g2_elements_needed.checked_mul(g2_size)?;
+    g1_len.checked_add(g2_len)
+}
+
+// Checks the parameters against their maximum number of elements, see
+// `Parameters::with_max_num_elements`, returning the length of the serialised
+// G1 and G2 elements
+pub(crate) fn check_bounds(parameters: Parameters) -> Result<(usize, usize), ParameterError> {
+    let max = parameters.max_num_elements;
+    let num_elements = std::cmp::max(
+        parameters.num_g1_elements_needed,
+        parameters.num_g2_elements_needed,
+    );
+    let too_many_elements = ParameterError::TooManyElements {
+        max,
+        got: num_elements,
+    };
+    if num_elements > max {
+        return Err(too_many_elements);
     }
+
+    // The limit is configurable, so the lengths can still overflow
+    serialised_size(parameters, Encoding::Bytes).ok_or(too_many_elements)?;
+
+    Ok((
+        parameters.num_g1_elements_needed * G1_SERIALISED_SIZE,
+        parameters.num_g2_elements_needed * G2_SERIALISED_SIZE,
+    ))
 }
-fn hex_string_to_g2(hex_str: &str) -> Option<G2Projective> {
-    if let Some(stripped_point_json) = hex_str.strip_prefix("0x") {
-        let bytes = hex::decode(stripped_point_json).ok()?;
-        if bytes.len() != G2_SERIALISED_SIZE {
-            return None;
+
+impl SRS {
+    fn apply_subgroup_check(self, subgroup_check: SubgroupCheck) -> Result<Self, Error> {
+        match subgroup_check {
+            SubgroupCheck::Full => self.subgroup_check()?,
+            SubgroupCheck::Batched => self.batched_subgroup_check(&mut rand::thread_rng())?,
+            SubgroupCheck::None => {}
         }
-        let mut fixed_array = [0u8; G2_SERIALISED_SIZE];
-        fixed_array.copy_from_slice(&bytes);
-        return Some(deserialize_g2(fixed_array)?.into_projective());
-    } else {
-        return None;
+        Ok(self)
     }
 }
 
+use crate::{
+    srs::{Parameters, DEFAULT_MAX_NUM_ELEMENTS, SRS},
+    update_proof::{G1UpdateProof, ProofMode, UpdateLink, UpdateProof},
+};
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+
+// Decodes a 0x prefixed hex string
+pub(crate) fn hex_string_to_bytes(hex_str: &str) -> Result<Vec<u8>, SerialisationError> {
+    let stripped_hex_str = hex_str
+        .strip_prefix("0x")
+        .ok_or(SerialisationError::MissingHexPrefix)?;
+    Ok(hex::decode(stripped_hex_str)?)
+}
+
+// Encodes bytes as a 0x prefixed hex string
+pub(crate) fn bytes_to_hex_string(bytes: &[u8]) -> String {
+    let mut hex_str = hex::encode(bytes);
+    hex_str.insert_str(0, "0x");
+    hex_str
+}
+
+pub(crate) fn hex_string_to_g1(hex_str: &str) -> Result<G1Affine, SerialisationError> {
+    g1_from_slice(&hex_string_to_bytes(hex_str)?)
+}
+pub(crate) fn hex_string_to_g2(hex_str: &str) -> Result<G2Affine, SerialisationError> {
+    g2_from_slice(&hex_string_to_bytes(hex_str)?)
+}
+
+// Serialises a list of G1 points as concatenated compressed points
+pub fn g1s_to_bytes(g1s: &[G1Affine]) -> Vec<u8> {
+    encode_g1_batch(g1s)
+}
+// Serialises a list of G2 points as concatenated compressed points
+pub fn g2s_to_bytes(g2s: &[G2Affine]) -> Vec<u8> {
+    encode_g2_batch(g2s)
+}
+
+// Deserialises concatenated compressed G1 points
+pub fn g1s_from_bytes(bytes: &[u8]) -> Result<Vec<G1Affine>, SerialisationError> {
+    decode_g1_batch(bytes)
+}
+// Deserialises concatenated compressed G2 points
+pub fn g2s_from_bytes(bytes: &[u8]) -> Result<Vec<G2Affine>, SerialisationError> {
+    decode_g2_batch(bytes)
+}
+
 impl SRS {
-    pub fn serialise(&self) -> (Vec<String>, Vec<String>) {
-        self.to_json_array()
+    // Serialises the SRS as the compressed G1 points followed by the compressed G2 points
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "serialise",
+            skip_all,
+            fields(num_g1 = self.g1_elements().len(), num_g2 = self.g2_elements().len())
+        )
+    )]
+    pub fn serialise_bytes(&self) -> Vec<u8> {
+        let mut bytes = g1s_to_bytes(self.g1_elements());
+        bytes.extend(g2s_to_bytes(self.g2_elements()));
+        bytes
     }
 
-    fn g1s_to_json_array(g1s: &[G1Projective]) -> Vec<String> {
-        let mut g1_points_json = Vec::new();
+    // Deserialises an SRS which was serialised using `serialise_bytes`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "deserialise",
+            skip_all,
+            fields(num_g1 = parameters.num_g1_elements(), num_g2 = parameters.num_g2_elements())
+        )
+    )]
+    pub fn deserialise_bytes(
+        bytes: &[u8],
+        parameters: Parameters,
+        subgroup_check: SubgroupCheck,
+    ) -> Result<Self, Error> {
+        let (g1_len, g2_len) = check_bounds(parameters)?;
+        if bytes.len() != g1_len + g2_len {
+            return Err(SerialisationError::InvalidLength {
+                expected: g1_len + g2_len,
+                got: bytes.len(),
+            }
+            .into());
+        }
+
+        let (g1_bytes, g2_bytes) = bytes.split_at(g1_len);
+        let g1 = g1s_from_bytes(g1_bytes)?;
+        let g2 = g2s_from_bytes(g2_bytes)?;
+
+        SRS::from_vectors(g1, g2)?.apply_subgroup_check(subgroup_check)
+    }
 
-        let g1_points_affine = G1Projective::batch_normalization_into_affine(g1s);
+    // Writes the SRS using the same encoding as `serialise_bytes`.
+    //
+    // The points are serialised a chunk at a time, so the encoding of the
+    // whole SRS is never held in memory.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "serialise",
+            skip_all,
+            fields(num_g1 = self.g1_elements().len(), num_g2 = self.g2_elements().len())
+        )
+    )]
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        // The number of points which are serialised before being written
+        const CHUNK_SIZE: usize = 4096;
 
-        for point in &g1_points_affine {
-            let mut point_as_hex = hex::encode(serialize_g1(point));
-            point_as_hex.insert_str(0, "0x");
-            g1_points_json.push(point_as_hex)
+        for chunk in self.g1_elements().chunks(CHUNK_SIZE) {
+            writer.write_all(&g1s_to_bytes(chunk))?;
+        }
+        for chunk in self.g2_elements().chunks(CHUNK_SIZE) {
+            writer.write_all(&g2s_to_bytes(chunk))?;
         }
+        writer.flush()
+    }
 
-        g1_points_json
+    // Writes the SRS in the same way as `write_to`, calling `is_cancelled`
+    // before each chunk of points is written
+    pub fn write_to_cancellable<W: Write>(
+        &self,
+        writer: &mut W,
+        is_cancelled: impl Fn() -> bool,
+    ) -> Result<(), Error> {
+        self.write_to_with_progress(writer, &mut NoProgress, is_cancelled)
+    }
+
+    // Writes the SRS in the same way as `write_to_cancellable`, reporting the
+    // number of points written after each chunk
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "serialise",
+            skip_all,
+            fields(num_g1 = self.g1_elements().len(), num_g2 = self.g2_elements().len())
+        )
+    )]
+    pub fn write_to_with_progress<W: Write>(
+        &self,
+        writer: &mut W,
+        progress: &mut impl ProgressSink,
+        is_cancelled: impl Fn() -> bool,
+    ) -> Result<(), Error> {
+        // The number of points which are serialised before being written
+        const CHUNK_SIZE: usize = 4096;
+
+        let num_points = self.g1_elements().len() + self.g2_elements().len();
+        let mut points_written = 0;
+
+        for chunk in self.g1_elements().chunks(CHUNK_SIZE) {
+            if is_cancelled() {
+                return Err(Cancelled.into());
+            }
+            writer
+                .write_all(&g1s_to_bytes(chunk))
+                .map_err(SerialisationError::Io)?;
+
+            points_written += chunk.len();
+            progress.progress(Phase::Serialise, points_written, num_points);
+        }
+        for chunk in self.g2_elements().chunks(CHUNK_SIZE) {
+            if is_cancelled() {
+                return Err(Cancelled.into());
+            }
+            writer
+                .write_all(&g2s_to_bytes(chunk))
+                .map_err(SerialisationError::Io)?;
+
+            points_written += chunk.len();
+            progress.progress(Phase::Serialise, points_written, num_points);
+        }
+        writer.flush().map_err(SerialisationError::Io)?;
+        Ok(())
     }
-    fn g2s_to_json_array(g2s: &[G2Projective]) -> Vec<String> {
-        let mut g2_points_json = Vec::new();
 
-        let g2_points_affine = G2Projective::batch_normalization_into_affine(g2s);
+    // Reads an SRS which was written using `write_to` or `serialise_bytes`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "deserialise",
+            skip_all,
+            fields(num_g1 = parameters.num_g1_elements(), num_g2 = parameters.num_g2_elements())
+        )
+    )]
+    pub fn read_from<R: Read>(
+        reader: &mut R,
+        parameters: Parameters,
+        subgroup_check: SubgroupCheck,
+    ) -> Result<Self, Error> {
+        check_bounds(parameters)?;
+
+        let mut g1 = Vec::with_capacity(parameters.num_g1_elements_needed);
+        for _ in 0..parameters.num_g1_elements_needed {
+            g1.push(g1_from_reader(reader)?);
+        }
 
-        for point in &g2_points_affine {
-            let mut point_as_hex = hex::encode(serialize_g2(point));
-            point_as_hex.insert_str(0, "0x");
-            g2_points_json.push(point_as_hex)
+        let mut g2 = Vec::with_capacity(parameters.num_g2_elements_needed);
+        for _ in 0..parameters.num_g2_elements_needed {
+            g2.push(g2_from_reader(reader)?);
         }
 
-        g2_points_json
+        SRS::from_vectors(g1, g2)?.apply_subgroup_check(subgroup_check)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "serialise",
+            skip_all,
+            fields(num_g1 = self.g1_elements().len(), num_g2 = self.g2_elements().len())
+        )
+    )]
+    pub fn serialise(&self) -> (Vec<String>, Vec<String>) {
+        self.to_json_array()
+    }
+
+    fn g1s_to_json_array(g1s: &[G1Affine]) -> Vec<String> {
+        ark_std::cfg_iter!(g1s)
+            .map(|point| {
+                let mut point_as_hex = hex::encode(serialize_g1(point));
+                point_as_hex.insert_str(0, "0x");
+                point_as_hex
+            })
+            .collect()
+    }
+    fn g2s_to_json_array(g2s: &[G2Affine]) -> Vec<String> {
+        ark_std::cfg_iter!(g2s)
+            .map(|point| {
+                let mut point_as_hex = hex::encode(serialize_g2(point));
+                point_as_hex.insert_str(0, "0x");
+                point_as_hex
+            })
+            .collect()
     }
 
     fn to_json_array(&self) -> (Vec<String>, Vec<String>) {
@@ -79,30 +342,57 @@ impl SRS {
 
     // We do not check if the point is the identity when deserialising
     // What we do check, is that every point is a point on the curve
-    pub fn deserialise(json_arr: (&[String], &[String]), parameters: Parameters) -> Option<Self> {
-        SRS::from_json_array(json_arr, parameters)
+    // and, unless `SubgroupCheck::None` is used, in the prime order subgroup
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "deserialise",
+            skip_all,
+            fields(num_g1 = parameters.num_g1_elements(), num_g2 = parameters.num_g2_elements())
+        )
+    )]
+    pub fn deserialise(
+        json_arr: (&[String], &[String]),
+        parameters: Parameters,
+        subgroup_check: SubgroupCheck,
+    ) -> Result<Self, Error> {
+        SRS::from_json_array(json_arr, parameters)?.apply_subgroup_check(subgroup_check)
     }
 
-    fn from_json_array(json_array: (&[String], &[String]), parameters: Parameters) -> Option<Self> {
+    fn from_json_array(
+        json_array: (&[String], &[String]),
+        parameters: Parameters,
+    ) -> Result<Self, Error> {
         let (g1_points_json_array, g2_points_json_array) = json_array;
-        let mut g1 = vec![];
-        let mut g2 = vec![];
 
-        for point_json in g1_points_json_array {
-            g1.push(hex_string_to_g1(&point_json)?);
+        // Check the lengths before decoding any of the points
+        check_bounds(parameters)?;
+        if g1_points_json_array.len() != parameters.num_g1_elements_needed {
+            return Err(ParameterError::WrongNumberOfG1Elements {
+                expected: parameters.num_g1_elements_needed,
+                got: g1_points_json_array.len(),
+            }
+            .into());
         }
-        for point_json in g2_points_json_array {
-            g2.push(hex_string_to_g2(&point_json)?)
+        if g2_points_json_array.len() != parameters.num_g2_elements_needed {
+            return Err(ParameterError::WrongNumberOfG2Elements {
+                expected: parameters.num_g2_elements_needed,
+                got: g2_points_json_array.len(),
+            }
+            .into());
         }
 
-        if g1.len() != parameters.num_g1_elements_needed {
-            return None;
+        let mut g1 = Vec::with_capacity(g1_points_json_array.len());
+        let mut g2 = Vec::with_capacity(g2_points_json_array.len());
+
+        for point_json in g1_points_json_array {
+            g1.push(hex_string_to_g1(point_json)?);
         }
-        if g2.len() != parameters.num_g2_elements_needed {
-            return None;
+        for point_json in g2_points_json_array {
+            g2.push(hex_string_to_g2(point_json)?)
         }
 
-        SRS::from_vectors(g1, g2)
+        Ok(SRS::from_vectors(g1, g2)?)
     }
 }
 
@@ -111,7 +401,7 @@ impl UpdateProof {
         self.to_json_array()
     }
 
-    fn to_json_array(&self) -> [String; 2] {
+    fn to_json_array(self) -> [String; 2] {
         let mut a = hex::encode(serialize_g2(&self.commitment_to_secret.into_affine()));
         a.insert_str(0, "0x");
 
@@ -120,39 +410,223 @@ impl UpdateProof {
 
         [a, b]
     }
-    pub fn deserialise(json_array: [String; 2]) -> Option<Self> {
+    pub fn deserialise(json_array: [String; 2]) -> Result<Self, SerialisationError> {
         UpdateProof::from_json_array(json_array)
     }
 
-    fn from_json_array(points_json_arr: [String; 2]) -> Option<Self> {
-        let commitment_to_secret = hex_string_to_g2(&points_json_arr[0])?;
-        let new_accumulated_point = hex_string_to_g1(&points_json_arr[1])?;
+    fn from_json_array(points_json_arr: [String; 2]) -> Result<Self, SerialisationError> {
+        let commitment_to_secret = hex_string_to_g2(&points_json_arr[0])?.into_projective();
+        let new_accumulated_point = hex_string_to_g1(&points_json_arr[1])?.into_projective();
 
-        Some(UpdateProof {
+        Ok(UpdateProof {
             commitment_to_secret,
             new_accumulated_point,
         })
     }
+
+    // Serialises the update proof as the compressed commitment to the secret
+    // followed by the compressed new accumulated point
+    pub fn serialise_bytes(&self) -> [u8; UPDATE_PROOF_SERIALISED_SIZE] {
+        let mut bytes = [0u8; UPDATE_PROOF_SERIALISED_SIZE];
+        bytes[..G2_SERIALISED_SIZE]
+            .copy_from_slice(&serialize_g2(&self.commitment_to_secret.into_affine()));
+        bytes[G2_SERIALISED_SIZE..]
+            .copy_from_slice(&serialize_g1(&self.new_accumulated_point.into_affine()));
+        bytes
+    }
+
+    pub fn deserialise_bytes(
+        bytes: &[u8; UPDATE_PROOF_SERIALISED_SIZE],
+    ) -> Result<Self, SerialisationError> {
+        let (g2_bytes, g1_bytes) = bytes.split_at(G2_SERIALISED_SIZE);
+
+        let commitment_to_secret = g2_from_slice(g2_bytes)?;
+        let new_accumulated_point = g1_from_slice(g1_bytes)?;
+
+        Ok(UpdateProof {
+            commitment_to_secret: commitment_to_secret.into_projective(),
+            new_accumulated_point: new_accumulated_point.into_projective(),
+        })
+    }
+}
+
+impl G1UpdateProof {
+    // Serialises the update proof as the compressed commitment to the secret
+    // followed by the compressed new accumulated point
+    pub fn serialise_bytes(&self) -> [u8; G1_UPDATE_PROOF_SERIALISED_SIZE] {
+        let mut bytes = [0u8; G1_UPDATE_PROOF_SERIALISED_SIZE];
+        bytes[..G1_SERIALISED_SIZE]
+            .copy_from_slice(&serialize_g1(&self.commitment_to_secret.into_affine()));
+        bytes[G1_SERIALISED_SIZE..]
+            .copy_from_slice(&serialize_g2(&self.new_accumulated_point.into_affine()));
+        bytes
+    }
+
+    pub fn deserialise_bytes(
+        bytes: &[u8; G1_UPDATE_PROOF_SERIALISED_SIZE],
+    ) -> Result<Self, SerialisationError> {
+        let (g1_bytes, g2_bytes) = bytes.split_at(G1_SERIALISED_SIZE);
+
+        Ok(G1UpdateProof {
+            commitment_to_secret: g1_from_slice(g1_bytes)?.into_projective(),
+            new_accumulated_point: g2_from_slice(g2_bytes)?.into_projective(),
+        })
+    }
+}
+
+impl ProofMode {
+    fn to_byte(self) -> u8 {
+        match self {
+            ProofMode::G2Commitment => 0,
+            ProofMode::G1Commitment => 1,
+        }
+    }
+}
+
+impl UpdateLink {
+    // Serialises the link as a byte for the mode, 0 for a G2 commitment and 1
+    // for a G1 commitment, followed by the serialised update proof
+    pub fn serialise_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.mode().to_byte()];
+        match self {
+            UpdateLink::G2Commitment(proof) => bytes.extend_from_slice(&proof.serialise_bytes()),
+            UpdateLink::G1Commitment(proof) => bytes.extend_from_slice(&proof.serialise_bytes()),
+        }
+        bytes
+    }
+
+    pub fn deserialise_bytes(bytes: &[u8]) -> Result<Self, SerialisationError> {
+        let (mode, proof) = bytes
+            .split_first()
+            .ok_or(SerialisationError::InvalidLength {
+                expected: 1 + UPDATE_PROOF_SERIALISED_SIZE,
+                got: 0,
+            })?;
+        let invalid_length = |expected| SerialisationError::InvalidLength {
+            expected: 1 + expected,
+            got: bytes.len(),
+        };
+
+        match *mode {
+            0 => {
+                let proof = proof
+                    .try_into()
+                    .map_err(|_| invalid_length(UPDATE_PROOF_SERIALISED_SIZE))?;
+                Ok(UpdateLink::G2Commitment(UpdateProof::deserialise_bytes(
+                    proof,
+                )?))
+            }
+            1 => {
+                let proof = proof
+                    .try_into()
+                    .map_err(|_| invalid_length(G1_UPDATE_PROOF_SERIALISED_SIZE))?;
+                Ok(UpdateLink::G1Commitment(G1UpdateProof::deserialise_bytes(
+                    proof,
+                )?))
+            }
+            _ => Err(SerialisationError::InvalidHeader),
+        }
+    }
+}
+
+// String encodings of the byte serialisations, for web APIs and configuration
+// files. Hex strings are 0x prefixed, and base64 uses the standard alphabet
+// with padding
+impl SRS {
+    pub fn to_hex(&self) -> String {
+        bytes_to_hex_string(&self.serialise_bytes())
+    }
+    pub fn from_hex(
+        hex_str: &str,
+        parameters: Parameters,
+        subgroup_check: SubgroupCheck,
+    ) -> Result<Self, Error> {
+        let bytes = hex_string_to_bytes(hex_str)?;
+        SRS::deserialise_bytes(&bytes, parameters, subgroup_check)
+    }
+
+    pub fn to_base64(&self) -> String {
+        base64::encode(self.serialise_bytes())
+    }
+    pub fn from_base64(
+        base64_str: &str,
+        parameters: Parameters,
+        subgroup_check: SubgroupCheck,
+    ) -> Result<Self, Error> {
+        let bytes = base64::decode(base64_str).map_err(SerialisationError::from)?;
+        SRS::deserialise_bytes(&bytes, parameters, subgroup_check)
+    }
+}
+
+impl UpdateProof {
+    pub fn to_hex(&self) -> String {
+        bytes_to_hex_string(&self.serialise_bytes())
+    }
+    pub fn from_hex(hex_str: &str) -> Result<Self, SerialisationError> {
+        UpdateProof::from_slice(&hex_string_to_bytes(hex_str)?)
+    }
+
+    pub fn to_base64(&self) -> String {
+        base64::encode(self.serialise_bytes())
+    }
+    pub fn from_base64(base64_str: &str) -> Result<Self, SerialisationError> {
+        UpdateProof::from_slice(&base64::decode(base64_str)?)
+    }
+
+    fn from_slice(bytes: &[u8]) -> Result<Self, SerialisationError> {
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| SerialisationError::InvalidLength {
+                expected: UPDATE_PROOF_SERIALISED_SIZE,
+                got: bytes.len(),
+            })?;
+        UpdateProof::deserialise_bytes(bytes)
+    }
+}
+
+// Encodes a public key, which is the commitment to a secret in G2, in the
+// same way as `PrivateKey::to_pot_pubkey`
+pub fn pubkey_to_hex(pubkey: &G2Affine) -> String {
+    bytes_to_hex_string(&serialize_g2(pubkey))
+}
+// Decodes a public key which was encoded using `pubkey_to_hex`.
+//
+// The point is not checked to be in the prime order subgroup
+pub fn pubkey_from_hex(hex_str: &str) -> Result<G2Affine, SerialisationError> {
+    hex_string_to_g2(hex_str)
+}
+pub fn pubkey_to_base64(pubkey: &G2Affine) -> String {
+    base64::encode(serialize_g2(pubkey))
+}
+pub fn pubkey_from_base64(base64_str: &str) -> Result<G2Affine, SerialisationError> {
+    g2_from_slice(&base64::decode(base64_str)?)
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SRSJson {
     #[serde(rename = "numG1Powers")]
-    num_g1_powers: usize,
+    pub(crate) num_g1_powers: usize,
     #[serde(rename = "numG2Powers")]
-    num_g2_powers: usize,
+    pub(crate) num_g2_powers: usize,
     #[serde(rename = "powersOfTau")]
-    powers_of_tau: PowerOfTau,
+    pub(crate) powers_of_tau: PowerOfTau,
     #[serde(rename = "potPubkey")]
     pub pot_pubkey: String,
+    // The contributor's identity signed using their secret, see `PrivateKey::sign`
+    #[serde(
+        rename = "blsSignature",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bls_signature: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PowerOfTau {
     #[serde(rename = "G1Powers")]
-    g1_powers: Vec<String>,
+    pub(crate) g1_powers: Vec<String>,
     #[serde(rename = "G2Powers")]
-    g2_powers: Vec<String>,
+    pub(crate) g2_powers: Vec<String>,
 }
 
 impl From<&SRS> for SRSJson {
@@ -165,28 +639,108 @@ impl From<&SRS> for SRSJson {
             num_g2_powers: g2s.len(),
             powers_of_tau: PowerOfTau {
                 g1_powers: SRS::g1s_to_json_array(g1s),
-                g2_powers: SRS::g2s_to_json_array(g2s)
+                g2_powers: SRS::g2s_to_json_array(g2s),
             },
-            pot_pubkey: SRS::g2s_to_json_array(g2s).get(0).unwrap().to_string(),
+            pot_pubkey: SRS::g2s_to_json_array(g2s).first().unwrap().to_string(),
+            bls_signature: None,
         }
     }
 }
-impl From<&SRSJson> for Option<SRS> {
-    fn from(srs: &SRSJson) -> Self {
-        let parameters = Parameters {
-            num_g1_elements_needed: srs.num_g1_powers,
-            num_g2_elements_needed: srs.num_g2_powers,
-        };
+impl SRSJson {
+    // The parameters that the json claims the SRS has
+    pub(crate) fn parameters(&self) -> Parameters {
+        Parameters {
+            num_g1_elements_needed: self.num_g1_powers,
+            num_g2_elements_needed: self.num_g2_powers,
+            max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
+        }
+    }
+}
+
+impl TryFrom<&SRSJson> for SRS {
+    type Error = Error;
+
+    fn try_from(srs: &SRSJson) -> Result<Self, Self::Error> {
         SRS::deserialise(
             (&srs.powers_of_tau.g1_powers, &srs.powers_of_tau.g2_powers),
-            parameters,
+            srs.parameters(),
+            SubgroupCheck::Full,
+        )
+    }
+}
+// A SHA-256 hash of an SRS, see `SRS::digest`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SRSDigest(pub [u8; 32]);
+
+impl std::fmt::Display for SRSDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl SRS {
+    // Hashes the SRS using the same encoding as `serialise_bytes`.
+    //
+    // Two SRS are equal if and only if their digests are equal, so a digest
+    // can be stored or logged in place of a large SRS and compared later
+    pub fn digest(&self) -> SRSDigest {
+        let mut hasher = Sha256::new();
+        self.write_to(&mut hasher)
+            .expect("writing to a hasher cannot fail");
+        SRSDigest(hasher.finalize().into())
+    }
+
+    // Checks if two SRS are equal by comparing their digests.
+    //
+    // This is useful when one of the SRS has already been hashed, see `matches_digest`
+    pub fn digest_eq(&self, other: &SRS) -> bool {
+        self.digest() == other.digest()
+    }
+    pub fn matches_digest(&self, digest: &SRSDigest) -> bool {
+        self.digest() == *digest
+    }
+}
+
+// The number of points in each group which are shown when an SRS is formatted
+const NUM_FORMATTED_POINTS: usize = 2;
+
+// Formats an SRS as a summary, since it can contain millions of points
+impl std::fmt::Debug for SRS {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let num_g1 = std::cmp::min(self.g1_elements().len(), NUM_FORMATTED_POINTS);
+        let num_g2 = std::cmp::min(self.g2_elements().len(), NUM_FORMATTED_POINTS);
+
+        f.debug_struct("SRS")
+            .field("num_g1_elements", &self.g1_elements().len())
+            .field("num_g2_elements", &self.g2_elements().len())
+            .field("digest", &format_args!("{}", self.digest()))
+            .field(
+                "first_g1_elements",
+                &SRS::g1s_to_json_array(&self.g1_elements()[..num_g1]),
+            )
+            .field(
+                "first_g2_elements",
+                &SRS::g2s_to_json_array(&self.g2_elements()[..num_g2]),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Display for SRS {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SRS with {} G1 and {} G2 elements, digest {}",
+            self.g1_elements().len(),
+            self.g2_elements().len(),
+            self.digest()
         )
     }
 }
 #[cfg(test)]
 mod tests {
     use crate::keypair::PrivateKey;
-    use ark_bls12_381::Fr;
+    use ark_bls12_381::{Fr, G1Projective, G2Projective};
     use ark_ec::ProjectiveCurve;
     use ark_ff::PrimeField;
 
@@ -203,6 +757,11 @@ mod tests {
         let bytes = proof.serialise();
         let deserialised_proof = UpdateProof::deserialise(bytes).unwrap();
 
+        assert_eq!(proof, deserialised_proof);
+
+        let bytes = proof.serialise_bytes();
+        let deserialised_proof = UpdateProof::deserialise_bytes(&bytes).unwrap();
+
         assert_eq!(proof, deserialised_proof)
     }
 
@@ -211,6 +770,7 @@ mod tests {
         let params = Parameters {
             num_g1_elements_needed: 100,
             num_g2_elements_needed: 25,
+            max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
         };
 
         let secret = PrivateKey::from_u64(5687);
@@ -218,8 +778,171 @@ mod tests {
         acc.update(secret);
 
         let bytes = acc.serialise();
-        let deserialised_srs = SRS::deserialise((&bytes.0, &bytes.1), params).unwrap();
+        let hex_len: usize = bytes.0.iter().chain(&bytes.1).map(String::len).sum();
+        assert_eq!(Some(hex_len), serialised_size(params, Encoding::Hex));
+
+        let deserialised_srs =
+            SRS::deserialise((&bytes.0, &bytes.1), params, SubgroupCheck::Full).unwrap();
 
         assert_eq!(acc, deserialised_srs);
     }
+
+    #[test]
+    fn srs_serialise_bytes_roundtrip() {
+        let params = Parameters {
+            num_g1_elements_needed: 100,
+            num_g2_elements_needed: 25,
+            max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
+        };
+
+        let secret = PrivateKey::from_u64(5687);
+        let mut acc = SRS::new(params).unwrap();
+        acc.update(secret);
+
+        let bytes = acc.serialise_bytes();
+        assert_eq!(
+            bytes.len(),
+            100 * G1_SERIALISED_SIZE + 25 * G2_SERIALISED_SIZE
+        );
+        assert_eq!(Some(bytes.len()), serialised_size(params, Encoding::Bytes));
+
+        let deserialised_srs = SRS::deserialise_bytes(&bytes, params, SubgroupCheck::Full).unwrap();
+        assert_eq!(acc, deserialised_srs);
+
+        let deserialised_srs =
+            SRS::deserialise_bytes(&bytes, params, SubgroupCheck::Batched).unwrap();
+        assert_eq!(acc, deserialised_srs);
+
+        // The bytes should be rejected if they do not match the parameters
+        assert!(matches!(
+            SRS::deserialise_bytes(&bytes[1..], params, SubgroupCheck::Full),
+            Err(Error::Serialisation(
+                SerialisationError::InvalidLength { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn deserialise_rejects_oversized_parameters() {
+        // These would overflow or allocate far too much memory, if they were trusted
+        for params in [
+            Parameters::new(usize::MAX, 2),
+            Parameters::new(2, usize::MAX / G1_SERIALISED_SIZE),
+            Parameters::new(DEFAULT_MAX_NUM_ELEMENTS + 1, 2),
+            // The limit is set per call, so a caller can lower it for its own input
+            Parameters::new(100, 2).with_max_num_elements(64),
+        ] {
+            assert!(matches!(
+                SRS::deserialise_bytes(&[0u8; 1024], params, SubgroupCheck::Full),
+                Err(Error::Parameter(ParameterError::TooManyElements { .. }))
+            ));
+            assert!(matches!(
+                SRS::read_from(&mut [0u8; 1024].as_slice(), params, SubgroupCheck::Full),
+                Err(Error::Parameter(ParameterError::TooManyElements { .. }))
+            ));
+        }
+    }
+
+    #[test]
+    fn srs_write_read_roundtrip() {
+        let params = Parameters {
+            num_g1_elements_needed: 100,
+            num_g2_elements_needed: 25,
+            max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
+        };
+
+        let secret = PrivateKey::from_u64(5687);
+        let mut acc = SRS::new(params).unwrap();
+        acc.update(secret);
+
+        let mut file = Vec::new();
+        acc.write_to(&mut file).unwrap();
+        assert_eq!(file, acc.serialise_bytes());
+
+        let read_srs = SRS::read_from(&mut file.as_slice(), params, SubgroupCheck::None).unwrap();
+        assert_eq!(acc, read_srs);
+
+        let mut cancelled_file = Vec::new();
+        assert!(matches!(
+            acc.write_to_cancellable(&mut cancelled_file, || true),
+            Err(Error::Cancelled(Cancelled))
+        ));
+        assert!(cancelled_file.is_empty());
+    }
+
+    #[test]
+    fn srs_digest_and_summary() {
+        let params = Parameters::new(100, 25);
+
+        let mut acc = SRS::new(params).unwrap();
+        let digest = acc.digest();
+        assert!(acc.matches_digest(&digest));
+        assert!(acc.digest_eq(&acc.clone()));
+
+        acc.update(PrivateKey::from_u64(5687));
+        assert!(!acc.matches_digest(&digest));
+
+        // The summary should not contain every point
+        let summary = format!("{:?}", acc);
+        assert!(summary.contains(&acc.digest().to_string()));
+        assert!(summary.contains(&acc.serialise().0[1]));
+        assert!(!summary.contains(&acc.serialise().0[2]));
+        assert_eq!(
+            acc.to_string(),
+            format!(
+                "SRS with 100 G1 and 25 G2 elements, digest {}",
+                acc.digest()
+            )
+        );
+    }
+
+    #[test]
+    fn string_codecs_roundtrip() {
+        let parameters = Parameters::new(10, 3);
+        let mut srs = SRS::new(parameters).unwrap();
+        let update_proof = srs.update(PrivateKey::from_u64(252));
+
+        let srs_hex = srs.to_hex();
+        assert!(srs_hex.starts_with("0x"));
+        assert_eq!(
+            SRS::from_hex(&srs_hex, parameters, SubgroupCheck::Full).unwrap(),
+            srs
+        );
+        assert_eq!(
+            SRS::from_base64(&srs.to_base64(), parameters, SubgroupCheck::Full).unwrap(),
+            srs
+        );
+
+        assert_eq!(
+            UpdateProof::from_hex(&update_proof.to_hex()).unwrap(),
+            update_proof
+        );
+        assert_eq!(
+            UpdateProof::from_base64(&update_proof.to_base64()).unwrap(),
+            update_proof
+        );
+
+        let pubkey = PrivateKey::from_u64(252).to_public().into_affine();
+        assert_eq!(
+            pubkey_to_hex(&pubkey),
+            PrivateKey::from_u64(252).to_pot_pubkey()
+        );
+        assert_eq!(pubkey_from_hex(&pubkey_to_hex(&pubkey)).unwrap(), pubkey);
+        assert_eq!(
+            pubkey_from_base64(&pubkey_to_base64(&pubkey)).unwrap(),
+            pubkey
+        );
+
+        assert!(matches!(
+            UpdateProof::from_base64("not base64!"),
+            Err(SerialisationError::InvalidBase64(_))
+        ));
+        assert!(matches!(
+            UpdateProof::from_hex("0x00"),
+            Err(SerialisationError::InvalidLength {
+                expected: UPDATE_PROOF_SERIALISED_SIZE,
+                got: 1
+            })
+        ));
+    }
 }
@@ -0,0 +1,305 @@
+// Splitting an SRS into ranges of powers, so that a very large SRS can be
+// stored or verified across multiple files or machines.
+//
+// Each shard owns the powers in `start..end` and also holds the first power of
+// the next shard. This allows each shard to check, on its own, that its powers
+// continue on from the previous shard.
+//
+// Shards can also be updated independently, so that the update of a very large
+// SRS can be spread across multiple machines. The coordinator splits the SRS,
+// each worker updates a shard using the same private key, and the coordinator
+// joins the shards and proves the update, see `SRS::join_update`.
+use crate::{
+    error::{Error, VerificationError},
+    keypair::PrivateKey,
+    srs::{subgroup_check_points, update_points, SRS},
+    update_proof::UpdateProof,
+    verification,
+};
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{Field, PrimeField};
+use zeroize::Zeroizing;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SRSShard {
+    // The range of powers which this shard owns
+    start: usize,
+    end: usize,
+    // The number of powers in the whole SRS
+    num_g1: usize,
+    num_g2: usize,
+    // The owned powers, followed by the first power of the next shard if there is one
+    tau_g1: Vec<G1Affine>,
+    tau_g2: Vec<G2Affine>,
+    // The degree-0 and degree-1 elements of the SRS, which are needed to check
+    // the structure of the shard
+    g1_tau: [G1Affine; 2],
+    g2_tau: [G2Affine; 2],
+}
+
+impl SRSShard {
+    // The range of powers which this shard owns
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+    pub fn g1_elements(&self) -> &[G1Affine] {
+        &self.tau_g1
+    }
+    pub fn g2_elements(&self) -> &[G2Affine] {
+        &self.tau_g2
+    }
+
+    // The number of powers in each group which the shard owns, excluding
+    // the first power of the next shard
+    fn num_owned(&self) -> (usize, usize) {
+        (
+            std::cmp::min(self.end, self.num_g1).saturating_sub(self.start),
+            std::cmp::min(self.end, self.num_g2).saturating_sub(self.start),
+        )
+    }
+
+    // Checks that the powers in this shard go up in powers of tau.
+    //
+    // If every shard of an SRS is valid and `SRS::join` succeeds, then the
+    // joined SRS has the correct structure. The update proofs still need to
+    // be checked against the joined SRS, see `SRS::verify_updates`
+    pub fn verify(&self, random_element: Fr) -> Result<(), VerificationError> {
+        // The shard containing the degree-0 and degree-1 elements must agree
+        // with the elements that every shard uses to check its structure
+        for (index, point) in self.tau_g1.iter().enumerate().take(2 - self.start.min(2)) {
+            if *point != self.g1_tau[self.start + index] {
+                return Err(VerificationError::InvalidStructure);
+            }
+        }
+        for (index, point) in self.tau_g2.iter().enumerate().take(2 - self.start.min(2)) {
+            if *point != self.g2_tau[self.start + index] {
+                return Err(VerificationError::InvalidStructure);
+            }
+        }
+
+        if !verification::powers_check(
+            &self.tau_g1,
+            &self.tau_g2,
+            self.g1_tau,
+            self.g2_tau,
+            random_element,
+        ) {
+            return Err(VerificationError::InvalidStructure);
+        }
+        Ok(())
+    }
+
+    // Checks that the powers in this shard are in the prime order subgroup
+    pub fn subgroup_check(&self) -> Result<(), VerificationError> {
+        subgroup_check_points(&self.tau_g1, &self.tau_g2)
+    }
+
+    // Updates the powers in this shard, as `SRS::update` would.
+    //
+    // Every shard of the SRS must be updated using the same private key
+    pub fn update(&mut self, private_key: &PrivateKey) {
+        let tau = private_key.tau;
+
+        let num_powers = std::cmp::max(self.tau_g1.len(), self.tau_g2.len());
+        let powers_of_priv_key = Zeroizing::new(powers_from(tau, self.start, num_powers));
+
+        update_points(&mut self.tau_g1, &powers_of_priv_key);
+        update_points(&mut self.tau_g2, &powers_of_priv_key);
+
+        // The degree-1 elements are updated in every shard, so that each
+        // shard can still be verified on its own
+        self.g1_tau[1] = self.g1_tau[1].mul(tau.into_repr()).into_affine();
+        self.g2_tau[1] = self.g2_tau[1].mul(tau.into_repr()).into_affine();
+    }
+}
+
+impl SRS {
+    // Splits the SRS into shards which each own `shard_size` powers
+    pub fn split(&self, shard_size: usize) -> Vec<SRSShard> {
+        let shard_size = std::cmp::max(shard_size, 1);
+
+        let tau_g1 = self.g1_elements();
+        let tau_g2 = self.g2_elements();
+        let num_powers = std::cmp::max(tau_g1.len(), tau_g2.len());
+
+        (0..num_powers)
+            .step_by(shard_size)
+            .map(|start| {
+                let end = std::cmp::min(start + shard_size, num_powers);
+                SRSShard {
+                    start,
+                    end,
+                    num_g1: tau_g1.len(),
+                    num_g2: tau_g2.len(),
+                    tau_g1: range_with_next(tau_g1, start, end),
+                    tau_g2: range_with_next(tau_g2, start, end),
+                    g1_tau: [tau_g1[0], tau_g1[1]],
+                    g2_tau: [tau_g2[0], tau_g2[1]],
+                }
+            })
+            .collect()
+    }
+
+    // Joins shards which were created using `split`, checking that they
+    // are consecutive and agree with each other where they overlap
+    pub fn join(shards: &[SRSShard]) -> Result<SRS, Error> {
+        let first_shard = shards.first().ok_or(VerificationError::InvalidShards)?;
+        let (num_g1, num_g2) = (first_shard.num_g1, first_shard.num_g2);
+
+        let mut tau_g1 = Vec::with_capacity(num_g1);
+        let mut tau_g2 = Vec::with_capacity(num_g2);
+
+        let mut expected_start = 0;
+        for (index, shard) in shards.iter().enumerate() {
+            let consistent = shard.start == expected_start
+                && shard.start < shard.end
+                && shard.num_g1 == num_g1
+                && shard.num_g2 == num_g2
+                && shard.g1_tau == first_shard.g1_tau
+                && shard.g2_tau == first_shard.g2_tau
+                && shard.tau_g1.len() == range_len(num_g1, shard.start, shard.end)
+                && shard.tau_g2.len() == range_len(num_g2, shard.start, shard.end);
+            if !consistent {
+                return Err(VerificationError::InvalidShards.into());
+            }
+
+            let (num_owned_g1, num_owned_g2) = shard.num_owned();
+            tau_g1.extend_from_slice(&shard.tau_g1[..num_owned_g1]);
+            tau_g2.extend_from_slice(&shard.tau_g2[..num_owned_g2]);
+
+            // The first power of the next shard must match the one in this shard
+            if let Some(next_shard) = shards.get(index + 1) {
+                let g1_overlap = shard.tau_g1[num_owned_g1..].first();
+                let g2_overlap = shard.tau_g2[num_owned_g2..].first();
+                if g1_overlap != next_shard.tau_g1.first()
+                    || g2_overlap != next_shard.tau_g2.first()
+                {
+                    return Err(VerificationError::InvalidShards.into());
+                }
+            }
+
+            expected_start = shard.end;
+        }
+
+        if expected_start != std::cmp::max(num_g1, num_g2) {
+            return Err(VerificationError::InvalidShards.into());
+        }
+        let srs = SRS::from_vectors(tau_g1, tau_g2)?;
+
+        // The degree-0 and degree-1 elements must match the context of the shards
+        if srs.g1_elements()[..2] != first_shard.g1_tau
+            || srs.g2_elements()[..2] != first_shard.g2_tau
+        {
+            return Err(VerificationError::InvalidShards.into());
+        }
+
+        Ok(srs)
+    }
+
+    // Joins shards which were each updated using `private_key`, and produces
+    // a proof of the update. See `SRSShard::update`
+    //
+    // The shards are not verified, so the result should be checked using
+    // `SRS::verify_update` before it is sent to the sequencer
+    pub fn join_update(
+        shards: &[SRSShard],
+        private_key: PrivateKey,
+    ) -> Result<(SRS, UpdateProof), Error> {
+        let srs = SRS::join(shards)?;
+
+        let update_proof = UpdateProof {
+            commitment_to_secret: private_key.to_public(),
+            new_accumulated_point: srs.g1_elements()[1].into_projective(),
+        };
+
+        Ok((srs, update_proof))
+    }
+}
+
+// The powers tau^start, ..., tau^(start + len - 1)
+pub(crate) fn powers_from(tau: Fr, start: usize, len: usize) -> Vec<Fr> {
+    let mut power = tau.pow([start as u64]);
+
+    let mut powers = Vec::with_capacity(len);
+    for _ in 0..len {
+        powers.push(power);
+        power *= tau;
+    }
+    powers
+}
+
+// The number of points in a shard owning `start..end`, including the first point of the next shard
+fn range_len(num_points: usize, start: usize, end: usize) -> usize {
+    std::cmp::min(end + 1, num_points).saturating_sub(start)
+}
+
+fn range_with_next<T: Copy>(points: &[T], start: usize, end: usize) -> Vec<T> {
+    let range_end = std::cmp::min(end + 1, points.len());
+    if start >= range_end {
+        return Vec::new();
+    }
+    points[start..range_end].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        keypair::PrivateKey,
+        srs::{Parameters, SRS},
+    };
+
+    #[test]
+    fn split_verify_join() {
+        let mut srs = SRS::new(Parameters::new(100, 25)).unwrap();
+        srs.update(PrivateKey::from_u64(252));
+
+        let shards = srs.split(16);
+        assert_eq!(shards.len(), 7);
+        for shard in &shards {
+            assert!(shard.verify(Fr::from(123456789u64)).is_ok());
+        }
+        assert_eq!(SRS::join(&shards).unwrap(), srs);
+
+        // Shards which do not link up should be rejected
+        let mut missing_shard = shards.clone();
+        missing_shard.remove(3);
+        assert!(SRS::join(&missing_shard).is_err());
+
+        // A shard whose powers are not consecutive should be rejected
+        let mut bad_shard = shards[2].clone();
+        bad_shard.tau_g1.swap(3, 4);
+        assert_eq!(
+            bad_shard.verify(Fr::from(123456789u64)),
+            Err(VerificationError::InvalidStructure)
+        );
+    }
+
+    #[test]
+    fn sharded_update() {
+        let srs = SRS::new(Parameters::new(100, 25)).unwrap();
+
+        let mut expected_srs = srs.clone();
+        let expected_proof = expected_srs.update(PrivateKey::from_u64(252));
+
+        // Each worker updates its own shard using the same private key
+        let mut shards = srs.split(16);
+        for shard in &mut shards {
+            shard.update(&PrivateKey::from_u64(252));
+            assert!(shard.verify(Fr::from(123456789u64)).is_ok());
+        }
+
+        let (updated_srs, update_proof) =
+            SRS::join_update(&shards, PrivateKey::from_u64(252)).unwrap();
+        assert_eq!(updated_srs, expected_srs);
+        assert_eq!(update_proof, expected_proof);
+        assert!(
+            SRS::verify_update(&srs, &updated_srs, &update_proof, Fr::from(123456789u64)).is_ok()
+        );
+
+        // Shards which were updated using different private keys cannot be joined
+        shards[0].update(&PrivateKey::from_u64(2));
+        assert!(SRS::join_update(&shards, PrivateKey::from_u64(252)).is_err());
+    }
+}
@@ -1,5 +1,5 @@
-use ark_bls12_381::{G1Projective, G2Affine, G2Projective};
-use ark_ec::{AffineCurve, PairingEngine};
+use crate::verification;
+use ark_bls12_381::{G1Projective, G2Projective};
 
 // A shared secret proof proves that a point was necessarily created by multiplying the discrete log of a series of previous points
 //
@@ -37,22 +37,7 @@ impl SharedSecretChain {
     // Verifies a shared secret chain, each srs is checked to have been transformed from the previous one
     // using the specified witness
     pub fn verify(&self) -> bool {
-        // Overlapping window of two; see example: https://gist.github.com/rust-play/d83ae8ffdbf24f17612e05dc75c2ee06
-        // Group accumulated points into overlapping pairs
-        let acc_pairs = self.accumulated_points.as_slice().windows(2);
-
-        let gen_g2 = G2Affine::prime_subgroup_generator();
-
-        for (acc_pair, witness) in acc_pairs.zip(&self.witnesses) {
-            let prev_acc = acc_pair[0];
-            let next_acc = acc_pair[1];
-            let p1 = ark_bls12_381::Bls12_381::pairing(next_acc, gen_g2);
-            let p2 = ark_bls12_381::Bls12_381::pairing(prev_acc, *witness);
-            if p1 != p2 {
-                return false;
-            }
-        }
-        true
+        verification::verify_shared_secret_chain(&self.accumulated_points, &self.witnesses)
     }
 }
 
@@ -102,11 +87,11 @@ mod tests {
 
         // -- Now for the witness we add `c` instead of `d`. This should verify as false
         chain.extend(abcd_g1, c_witness);
-        assert!(chain.verify() == false);
+        assert!(!chain.verify());
         chain.remove_last();
         // -- Now add the correct witness, but the wrong accumulated point
         chain.extend(abc_g1, d_witness);
-        assert!(chain.verify() == false);
+        assert!(!chain.verify());
         chain.remove_last();
         // -- Add the correct accumulated point and witness
         chain.extend(abcd_g1, d_witness);
@@ -0,0 +1,194 @@
+// Simulates a ceremony with a number of participants, so that the full
+// verification path can be tested deterministically.
+//
+// The secrets of the participants are generated from a seed, and faults
+// can be injected into the contributions of individual participants.
+use crate::{
+    error::{ParameterError, VerificationError},
+    keypair::PrivateKey,
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+use ark_bls12_381::Fr;
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+// A fault which a participant introduces into their contribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    // The update proof commits to a different secret than the one used to update the SRS
+    BadProof,
+    // The last G1 element of the SRS is not the correct power of tau
+    WrongPoint,
+    // The participant reuses the secret of the earlier participant with the given index.
+    //
+    // This is not detected by `SRS::verify_updates`, since the SRS is still
+    // updated correctly, however the repeated commitment to the secret
+    // is visible in the update proofs
+    ReusedSecret(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    parameters: Parameters,
+    num_participants: usize,
+    seed: u64,
+    faults: Vec<(usize, Fault)>,
+}
+
+// The result of a simulated ceremony
+#[derive(Debug, Clone)]
+pub struct SimulatedTranscript {
+    pub starting_srs: SRS,
+    pub final_srs: SRS,
+    pub update_proofs: Vec<UpdateProof>,
+}
+
+impl SimulatedTranscript {
+    // Verifies the transcript in the same way as a ceremony verifier would
+    pub fn verify(&self, random_element: Fr) -> Result<(), VerificationError> {
+        self.final_srs.subgroup_check()?;
+        SRS::verify_updates(
+            &self.starting_srs,
+            &self.final_srs,
+            &self.update_proofs,
+            random_element,
+        )
+    }
+}
+
+impl Simulation {
+    pub fn new(parameters: Parameters, num_participants: usize) -> Self {
+        Simulation {
+            parameters,
+            num_participants,
+            seed: 0,
+            faults: Vec::new(),
+        }
+    }
+
+    // The seed used to generate the secret of each participant
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // Injects a fault into the contribution of the participant with the given index
+    pub fn fault(mut self, participant: usize, fault: Fault) -> Self {
+        self.faults.push((participant, fault));
+        self
+    }
+
+    pub fn run(&self) -> Result<SimulatedTranscript, ParameterError> {
+        let mut rng = ChaCha20Rng::seed_from_u64(self.seed);
+
+        let starting_srs = SRS::new(self.parameters)?;
+        let mut srs = starting_srs.clone();
+
+        let mut secrets = Vec::with_capacity(self.num_participants);
+        let mut update_proofs = Vec::with_capacity(self.num_participants);
+
+        for participant in 0..self.num_participants {
+            let faults = self
+                .faults
+                .iter()
+                .filter(|(index, _)| *index == participant)
+                .map(|(_, fault)| *fault)
+                .collect::<Vec<_>>();
+
+            // Draw a secret even if it is replaced, so that the secrets of the
+            // other participants do not depend on the faults
+            let mut secret = Fr::rand(&mut rng);
+            for fault in &faults {
+                if let Fault::ReusedSecret(index) = fault {
+                    secret = *secrets
+                        .get(*index)
+                        .expect("a secret can only be reused from an earlier participant");
+                }
+            }
+            secrets.push(secret);
+
+            let mut update_proof = srs.update(PrivateKey { tau: secret });
+
+            for fault in faults {
+                match fault {
+                    Fault::BadProof => {
+                        update_proof.commitment_to_secret = PrivateKey::rand(&mut rng).to_public();
+                    }
+                    Fault::WrongPoint => srs = with_wrong_last_point(&srs)?,
+                    Fault::ReusedSecret(_) => {}
+                }
+            }
+
+            update_proofs.push(update_proof);
+        }
+
+        Ok(SimulatedTranscript {
+            starting_srs,
+            final_srs: srs,
+            update_proofs,
+        })
+    }
+}
+
+// Doubles the last G1 element, which keeps it in the prime order subgroup
+fn with_wrong_last_point(srs: &SRS) -> Result<SRS, ParameterError> {
+    let mut tau_g1 = srs.g1_elements().to_vec();
+    let last = tau_g1.len() - 1;
+    tau_g1[last] = tau_g1[last].mul(Fr::from(2u64).into_repr()).into_affine();
+
+    let tau_g2 = srs.g2_elements().to_vec();
+    SRS::from_vectors(tau_g1, tau_g2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_ceremony_faults() {
+        let params = Parameters::new(32, 4);
+        let random_element = Fr::from(123456789u64);
+
+        let honest = Simulation::new(params, 4).seed(1).run().unwrap();
+        assert_eq!(honest.update_proofs.len(), 4);
+        assert!(honest.verify(random_element).is_ok());
+
+        // The same seed should give the same ceremony
+        let again = Simulation::new(params, 4).seed(1).run().unwrap();
+        assert_eq!(honest.final_srs, again.final_srs);
+
+        let bad_proof = Simulation::new(params, 4)
+            .seed(1)
+            .fault(2, Fault::BadProof)
+            .run()
+            .unwrap();
+        assert_eq!(
+            bad_proof.verify(random_element),
+            Err(VerificationError::InvalidUpdateChain)
+        );
+
+        let wrong_point = Simulation::new(params, 4)
+            .seed(1)
+            .fault(3, Fault::WrongPoint)
+            .run()
+            .unwrap();
+        assert_eq!(
+            wrong_point.verify(random_element),
+            Err(VerificationError::InvalidStructure)
+        );
+
+        let reused_secret = Simulation::new(params, 4)
+            .seed(1)
+            .fault(2, Fault::ReusedSecret(0))
+            .run()
+            .unwrap();
+        assert!(reused_secret.verify(random_element).is_ok());
+        assert_eq!(
+            reused_secret.update_proofs[0].commitment_to_secret,
+            reused_secret.update_proofs[2].commitment_to_secret
+        );
+    }
+}
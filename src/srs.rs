@@ -1,23 +1,97 @@
-use crate::{keypair::PrivateKey, update_proof::UpdateProof};
-use ark_bls12_381::{Fr, G1Projective, G2Projective};
-use ark_ec::{msm::VariableBaseMSM, PairingEngine, ProjectiveCurve};
-use ark_ff::{PrimeField, Zero};
-use itertools::Itertools;
+use crate::{
+    error::{Cancelled, ParameterError, VerificationError},
+    interop_point_encoding::serialize_g2,
+    keypair::PrivateKey,
+    metrics::{self, FailureCategory},
+    progress::{NoProgress, Phase, ProgressSink},
+    serialisation::SRSDigest,
+    update_proof::{G1UpdateProof, ProofMode, UpdateLink, UpdateProof},
+    verification::{self, vandemonde_challenge, PreparedG2Tau},
+};
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+// The number of rounds of the batched subgroup check, see `batched_subgroup_check`
+//
+// Each round fails to detect a point outside of the subgroup with probability
+// at most 1/q + 1/256, where q is the smallest prime factor of the cofactor.
+// The rounds are chosen so that the overall soundness error is below 2^-64
+//
+// The G1 cofactor is divisible by 3: (1/3 + 1/256)^41 < 2^-64
+const G1_BATCH_ROUNDS: usize = 41;
+// The smallest prime factor of the G2 cofactor is 13: (1/13 + 1/256)^18 < 2^-64
+const G2_BATCH_ROUNDS: usize = 18;
+
+// The number of points which are subgroup checked by a thread at a time.
+// Large chunks keep the scheduling overhead low, which matters for the
+// web worker based thread pool in wasm
+const SUBGROUP_CHECK_CHUNK_SIZE: usize = 1024;
+
+// The number of points which are updated by a thread at a time, see `update_points`
+pub(crate) const UPDATE_CHUNK_SIZE: usize = 1024;
+
+// The number of points which are updated between progress reports, see `SRS::update_with_progress`.
+// Each chunk is updated in parallel, so the chunks should not be too small
+pub const PROGRESS_CHUNK_SIZE: usize = 1 << 14;
+
+// The number of pairs of consecutive powers in each group which are checked
+// by `VerificationLevel::Quick`
+const QUICK_CHECK_SAMPLES: usize = 32;
 
 // Structured Reference String. Stores the powers of tau
 // in G1 and G2
-#[derive(Debug, Clone, PartialEq, Eq)]
+//
+// The powers are stored in affine form, which takes two thirds of the memory
+// of projective form. They are only converted to projective form while they
+// are being updated.
+//
+// The Debug and Display implementations only show a summary of the SRS,
+// see the serialisation module
+#[derive(Clone, PartialEq, Eq)]
 pub struct SRS {
     // #[serde(serialize_with = "serialize_vec_g1s", rename = "G1Powers")]
-    tau_g1: Vec<G1Projective>,
+    tau_g1: Vec<G1Affine>,
     // #[serde(serialize_with = "serialize_vec_g2s", rename = "G2Powers")]
-    tau_g2: Vec<G2Projective>,
+    tau_g2: Vec<G2Affine>,
+}
+
+// How thoroughly the new SRS is checked when verifying updates. Every level
+// checks the update proofs, so the levels only differ in the structure check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationLevel {
+    // Check a random sample of pairs of consecutive powers in each group, so
+    // that contributions can be gated quickly. An SRS where only a few powers
+    // are wrong is likely to pass
+    Quick,
+    // Check every power using a random linear combination in each group, see
+    // `SRS::structure_check_opt`
+    Standard,
+    // Check every pair of consecutive powers using its own pairings, and that
+    // every point is in the prime order subgroup, as an auditor would
+    Exhaustive,
+}
+
+impl Default for VerificationLevel {
+    fn default() -> Self {
+        VerificationLevel::Standard
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Parameters {
     pub(crate) num_g1_elements_needed: usize,
     pub(crate) num_g2_elements_needed: usize,
+    // The maximum number of G1 or G2 elements which will be deserialised
+    // using these parameters
+    pub(crate) max_num_elements: usize,
 }
 
 impl Parameters {
@@ -25,36 +99,233 @@ impl Parameters {
         Parameters {
             num_g1_elements_needed: num_g1,
             num_g2_elements_needed: num_g2,
+            max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
+        }
+    }
+
+    // Returns a builder which validates the parameters, see `ParametersBuilder`
+    pub fn builder() -> ParametersBuilder {
+        ParametersBuilder::default()
+    }
+
+    pub fn num_g1_elements(&self) -> usize {
+        self.num_g1_elements_needed
+    }
+    pub fn num_g2_elements(&self) -> usize {
+        self.num_g2_elements_needed
+    }
+
+    // Sets the maximum number of G1 or G2 elements which will be deserialised
+    // using these parameters, which defaults to `DEFAULT_MAX_NUM_ELEMENTS`.
+    //
+    // The parameters are checked against this limit before anything is allocated,
+    // so that untrusted input cannot make the process run out of memory
+    pub fn with_max_num_elements(mut self, max_num_elements: usize) -> Self {
+        self.max_num_elements = max_num_elements;
+        self
+    }
+    pub fn max_num_elements(&self) -> usize {
+        self.max_num_elements
+    }
+}
+
+// The default maximum number of G1 or G2 elements allowed by `ParametersBuilder`,
+// and deserialised using `Parameters`.
+// A G2 element takes 200 bytes in memory, so this caps an SRS at a few gigabytes
+pub const DEFAULT_MAX_NUM_ELEMENTS: usize = 1 << 24;
+
+// Builds `Parameters`, checking that an SRS with them can be created and
+// that it will not need an unreasonable amount of memory.
+//
+// Example; `Parameters::builder().eip_4844().build()`
+#[derive(Debug, Clone, Copy)]
+pub struct ParametersBuilder {
+    num_g1_elements: usize,
+    num_g2_elements: usize,
+    max_num_elements: usize,
+}
+
+impl Default for ParametersBuilder {
+    fn default() -> Self {
+        ParametersBuilder {
+            num_g1_elements: 0,
+            num_g2_elements: 0,
+            max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
+        }
+    }
+}
+
+impl ParametersBuilder {
+    // The parameters for the kzg polynomial commitment scheme, where
+    // `num_coefficients` is the number of coefficients of the largest
+    // polynomial that will be committed to
+    pub fn kzg(mut self, num_coefficients: usize) -> Self {
+        // The amount of G2 elements needed for KZG based commitment schemes
+        const NUM_G2_ELEMENTS_NEEDED: usize = 2;
+
+        self.num_g1_elements = num_coefficients;
+        self.num_g2_elements = NUM_G2_ELEMENTS_NEEDED;
+        self
+    }
+    // The parameters for a Plonk circuit with `num_gates` gates.
+    //
+    // The evaluation domain is the smallest power of two with at least one
+    // element per gate, and blinding raises the degree of the committed
+    // polynomials by up to 5 above the domain size, so 6 extra G1 powers are needed
+    pub fn plonk(mut self, num_gates: usize) -> Self {
+        // The number of G1 elements needed above the domain size for blinding
+        const NUM_BLINDING_G1_ELEMENTS: usize = 6;
+        const NUM_G2_ELEMENTS_NEEDED: usize = 2;
+
+        // A domain which cannot be represented is rejected as too large by `build`
+        self.num_g1_elements = num_gates
+            .checked_next_power_of_two()
+            .and_then(|domain_size| domain_size.checked_add(NUM_BLINDING_G1_ELEMENTS))
+            .unwrap_or(usize::MAX);
+        self.num_g2_elements = NUM_G2_ELEMENTS_NEEDED;
+        self
+    }
+    // The parameters of the smallest EIP-4844 ceremony
+    pub fn eip_4844(mut self) -> Self {
+        self.num_g1_elements = 4096;
+        self.num_g2_elements = 65;
+        self
+    }
+    // Custom parameters
+    pub fn custom(mut self, num_g1_elements: usize, num_g2_elements: usize) -> Self {
+        self.num_g1_elements = num_g1_elements;
+        self.num_g2_elements = num_g2_elements;
+        self
+    }
+    // Sets the maximum number of G1 or G2 elements, which defaults to `DEFAULT_MAX_NUM_ELEMENTS`
+    pub fn max_num_elements(mut self, max_num_elements: usize) -> Self {
+        self.max_num_elements = max_num_elements;
+        self
+    }
+
+    pub fn build(self) -> Result<Parameters, ParameterError> {
+        if self.num_g1_elements == 0 || self.num_g2_elements == 0 {
+            return Err(ParameterError::ZeroElements);
+        }
+        // An SRS needs the degree-1 element in both groups
+        if self.num_g1_elements < 2 || self.num_g2_elements < 2 {
+            return Err(ParameterError::TooFewElements);
+        }
+
+        let num_elements = std::cmp::max(self.num_g1_elements, self.num_g2_elements);
+        if num_elements > self.max_num_elements {
+            return Err(ParameterError::TooManyElements {
+                max: self.max_num_elements,
+                got: num_elements,
+            });
         }
+
+        Ok(Parameters::new(self.num_g1_elements, self.num_g2_elements)
+            .with_max_num_elements(self.max_num_elements))
     }
 }
 
 impl SRS {
     // Creates a powers of tau ceremony.
     // This is not compatible with the BGM17 Groth16 powers of tau ceremony (notice there is no \alpha, \beta)
-    pub fn new(parameters: Parameters) -> Option<SRS> {
-        let g1s = vec![G1Projective::prime_subgroup_generator(); parameters.num_g1_elements_needed];
-        let g2s = vec![G2Projective::prime_subgroup_generator(); parameters.num_g2_elements_needed];
+    pub fn new(parameters: Parameters) -> Result<SRS, ParameterError> {
+        let g1s = vec![G1Affine::prime_subgroup_generator(); parameters.num_g1_elements_needed];
+        let g2s = vec![G2Affine::prime_subgroup_generator(); parameters.num_g2_elements_needed];
         SRS::from_vectors(g1s, g2s)
     }
-    pub fn from_vectors(g1s: Vec<G1Projective>, g2s: Vec<G2Projective>) -> Option<SRS> {
+    pub fn from_vectors(g1s: Vec<G1Affine>, g2s: Vec<G2Affine>) -> Result<SRS, ParameterError> {
         let cond = g1s.len() > 1 && g2s.len() > 1;
         if !cond {
-            return None;
+            Err(ParameterError::TooFewElements)
         } else {
-            Some(SRS {
+            Ok(SRS {
                 tau_g1: g1s,
                 tau_g2: g2s,
             })
         }
     }
 
-    pub fn g1_elements(&self) -> &[G1Projective] {
+    pub fn g1_elements(&self) -> &[G1Affine] {
+        &self.tau_g1
+    }
+    pub fn g2_elements(&self) -> &[G2Affine] {
+        &self.tau_g2
+    }
+    pub(crate) fn g1_elements_mut(&mut self) -> &mut [G1Affine] {
+        &mut self.tau_g1
+    }
+    pub(crate) fn g2_elements_mut(&mut self) -> &mut [G2Affine] {
+        &mut self.tau_g2
+    }
+
+    // Returns the powers of tau in G1, which is what provers such as kzg
+    // expect. Use `to_vec` if an owned copy is needed
+    pub fn g1_powers(&self) -> &[G1Affine] {
         &self.tau_g1
     }
-    pub fn g2_elements(&self) -> &[G2Projective] {
+    pub fn g2_powers(&self) -> &[G2Affine] {
         &self.tau_g2
     }
+    // Returns tau^index in G1, or None if the SRS does not have that many powers
+    pub fn g1_power(&self, index: usize) -> Option<G1Affine> {
+        self.tau_g1.get(index).copied()
+    }
+    pub fn g2_power(&self, index: usize) -> Option<G2Affine> {
+        self.tau_g2.get(index).copied()
+    }
+
+    // Returns an SRS with only the first `num_g1` and `num_g2` powers.
+    //
+    // The degree-1 elements are kept, so the update proofs for this SRS also
+    // verify the truncated SRS. This allows one ceremony to serve applications
+    // which need smaller degrees.
+    pub fn truncate(&self, num_g1: usize, num_g2: usize) -> Result<SRS, ParameterError> {
+        if num_g1 > self.tau_g1.len() {
+            return Err(ParameterError::WrongNumberOfG1Elements {
+                expected: num_g1,
+                got: self.tau_g1.len(),
+            });
+        }
+        if num_g2 > self.tau_g2.len() {
+            return Err(ParameterError::WrongNumberOfG2Elements {
+                expected: num_g2,
+                got: self.tau_g2.len(),
+            });
+        }
+
+        SRS::from_vectors(
+            self.tau_g1[..num_g1].to_vec(),
+            self.tau_g2[..num_g2].to_vec(),
+        )
+    }
+
+    // Returns the shifted powers tau^(max_degree - degree_bound + i) in G1, for i
+    // from 0 to `degree_bound`.
+    //
+    // Schemes such as Marlin and Sonic enforce that a committed polynomial p has
+    // degree at most `degree_bound` by also committing to x^(max_degree - degree_bound) * p,
+    // where `max_degree` is the largest degree supported by their parameters.
+    // Committing to the shifted polynomial only needs these powers.
+    pub fn shifted_g1_powers(
+        &self,
+        max_degree: usize,
+        degree_bound: usize,
+    ) -> Result<Vec<G1Affine>, ParameterError> {
+        if max_degree >= self.tau_g1.len() {
+            return Err(ParameterError::WrongNumberOfG1Elements {
+                expected: max_degree.saturating_add(1),
+                got: self.tau_g1.len(),
+            });
+        }
+        if degree_bound == 0 || degree_bound > max_degree {
+            return Err(ParameterError::InvalidDegreeBound {
+                degree_bound,
+                max_degree,
+            });
+        }
+
+        Ok(self.tau_g1[max_degree - degree_bound..=max_degree].to_vec())
+    }
 
     // Creates a ceremony for the kzg polynomial commitment scheme
     // One should input the number of coefficients for the polynomial with the
@@ -72,52 +343,107 @@ impl SRS {
         let params = Parameters {
             num_g1_elements_needed: num_coefficients,
             num_g2_elements_needed: NUM_G2_ELEMENTS_NEEDED,
+            max_num_elements: DEFAULT_MAX_NUM_ELEMENTS,
         };
 
         SRS::new(params).unwrap()
     }
 
+    // Creates a ceremony for Plonk circuits with up to `num_gates` gates, see `ParametersBuilder::plonk`
+    pub fn new_for_plonk(num_gates: usize) -> Result<SRS, ParameterError> {
+        SRS::new(Parameters::builder().plonk(num_gates).build()?)
+    }
+
     // Updates the srs and produces a proof of this update
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "update",
+            skip_all,
+            fields(num_g1 = self.tau_g1.len(), num_g2 = self.tau_g2.len())
+        )
+    )]
     pub fn update(&mut self, private_key: PrivateKey) -> UpdateProof {
-        self.update_srs(private_key.tau);
-        let updated_tau = self.tau_g1[1];
+        self.chunked_update(private_key, usize::MAX).finish()
+    }
 
-        UpdateProof {
-            commitment_to_secret: private_key.to_public(),
-            new_accumulated_point: updated_tau,
+    // Updates the srs in the same way as `update`, proving the update with a
+    // commitment to the secret in G1, see `G1UpdateProof`
+    pub fn update_with_g1_commitment(&mut self, private_key: PrivateKey) -> G1UpdateProof {
+        let commitment_to_secret = private_key.to_public_g1();
+        self.update(private_key);
+
+        G1UpdateProof {
+            commitment_to_secret,
+            new_accumulated_point: self.tau_g2[1].into_projective(),
         }
     }
 
-    // Updates the group elements using a users private key
-    fn update_srs(&mut self, private_key: Fr) {
-        use ark_ec::wnaf::WnafContext;
-
-        #[cfg(feature = "parallel")]
-        use rayon::prelude::*;
+    // Updates the srs in the same way as `update`, reporting the number of
+    // points updated after each chunk of `PROGRESS_CHUNK_SIZE` points
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "update",
+            skip_all,
+            fields(num_g1 = self.tau_g1.len(), num_g2 = self.tau_g2.len())
+        )
+    )]
+    pub fn update_with_progress(
+        &mut self,
+        private_key: PrivateKey,
+        progress: &mut impl ProgressSink,
+    ) -> UpdateProof {
+        let mut update = self.chunked_update(private_key, PROGRESS_CHUNK_SIZE);
+        // The update is never cancelled, so it always runs to completion
+        let _ = update.run_with_progress(progress, || false);
+        update.finish()
+    }
 
+    // Prepares an update of the srs which will be applied `chunk_size` points at a time.
+    //
+    // This allows the caller to do other work in between chunks, for example
+    // yielding to the browser event loop or reporting progress.
+    pub fn chunked_update(
+        &mut self,
+        private_key: PrivateKey,
+        chunk_size: usize,
+    ) -> ChunkedUpdate<'_> {
         let max_number_elements = std::cmp::max(self.tau_g1.len(), self.tau_g2.len());
+        let powers_of_priv_key = vandemonde_challenge(private_key.tau, max_number_elements - 1);
 
-        let powers_of_priv_key = vandemonde_challenge(private_key, max_number_elements - 1);
+        ChunkedUpdate {
+            srs: self,
+            powers_of_priv_key: Zeroizing::new(powers_of_priv_key),
+            commitment_to_secret: private_key.to_public(),
+            chunk_size: std::cmp::max(chunk_size, 1),
+            points_updated: 0,
+        }
+    }
 
-        let wnaf = WnafContext::new(3);
+    // Resumes an update from a checkpoint, see `ChunkedUpdate::checkpoint`.
+    //
+    // The SRS must be the partially updated SRS which was saved along with the
+    // checkpoint, and the private key must be the one that the update started with
+    pub fn resume_update(
+        &mut self,
+        private_key: PrivateKey,
+        chunk_size: usize,
+        checkpoint: &UpdateCheckpoint,
+    ) -> Result<ChunkedUpdate<'_>, VerificationError> {
+        if !self.matches_digest(&checkpoint.digest) {
+            return Err(VerificationError::CheckpointMismatch);
+        }
 
-        ark_std::cfg_iter_mut!(self.tau_g1)
-            // Skip the degree-0 element as it does not get updated
-            .skip(1)
-            .zip(&powers_of_priv_key)
-            .into_par_iter()
-            .for_each(|(tg1, priv_pow)| {
-                *tg1 = wnaf.mul(*tg1, priv_pow);
-            });
+        let mut update = self.chunked_update(private_key, chunk_size);
+        if checkpoint.points_updated > update.num_points()
+            || commitment_to_hex(update.commitment_to_secret) != checkpoint.commitment_to_secret
+        {
+            return Err(VerificationError::CheckpointMismatch);
+        }
+        update.points_updated = checkpoint.points_updated;
 
-        ark_std::cfg_iter_mut!(self.tau_g2)
-            // Skip the degree-0 element as it does not get updated
-            .skip(1)
-            .zip(&powers_of_priv_key)
-            .into_par_iter()
-            .for_each(|(tg2, priv_pow)| {
-                *tg2 = wnaf.mul(*tg2, priv_pow);
-            })
+        Ok(update)
     }
 
     // Verify whether the transition from one SRS to the other was valid
@@ -125,73 +451,264 @@ impl SRS {
     // After the ceremony is over, an actor whom wants to verify that the ceremony was
     // was done correctly will collect all of the updates from the ceremony, along with
     // the starting and ending SRS in order to call this method.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "verify_updates",
+            skip_all,
+            fields(num_updates = update_proofs.len())
+        )
+    )]
     pub fn verify_updates(
         before: &SRS,
         after: &SRS,
         update_proofs: &[UpdateProof],
         random_element: Fr,
-    ) -> bool {
+    ) -> Result<(), VerificationError> {
+        SRS::verify_updates_at_level(
+            before,
+            after,
+            update_proofs,
+            random_element,
+            VerificationLevel::Standard,
+        )
+    }
+
+    // Verifies the transition in the same way as `verify_updates`, checking the
+    // new SRS at the given level
+    pub fn verify_updates_at_level(
+        before: &SRS,
+        after: &SRS,
+        update_proofs: &[UpdateProof],
+        random_element: Fr,
+        level: VerificationLevel,
+    ) -> Result<(), VerificationError> {
+        let result = SRS::check_updates(before, after, update_proofs, random_element, level);
+        if let Err(error) = result {
+            metrics::record_failure(error.into());
+        }
+        result
+    }
+
+    // Verifies the transition in the same way as `verify_updates`, for update
+    // proofs which commit to the secret in G1
+    pub fn verify_g1_updates(
+        before: &SRS,
+        after: &SRS,
+        update_proofs: &[G1UpdateProof],
+        random_element: Fr,
+    ) -> Result<(), VerificationError> {
+        SRS::verify_g1_updates_at_level(
+            before,
+            after,
+            update_proofs,
+            random_element,
+            VerificationLevel::Standard,
+        )
+    }
+
+    pub fn verify_g1_updates_at_level(
+        before: &SRS,
+        after: &SRS,
+        update_proofs: &[G1UpdateProof],
+        random_element: Fr,
+        level: VerificationLevel,
+    ) -> Result<(), VerificationError> {
+        let result = SRS::check_g1_updates(before, after, update_proofs, random_element, level);
+        if let Err(error) = result {
+            metrics::record_failure(error.into());
+        }
+        result
+    }
+
+    // Verifies the transition using whichever mode the links were created in.
+    // Every link must use the same mode
+    pub fn verify_links(
+        before: &SRS,
+        after: &SRS,
+        links: &[UpdateLink],
+        random_element: Fr,
+    ) -> Result<(), VerificationError> {
+        SRS::verify_links_at_level(
+            before,
+            after,
+            links,
+            random_element,
+            VerificationLevel::Standard,
+        )
+    }
+
+    pub fn verify_links_at_level(
+        before: &SRS,
+        after: &SRS,
+        links: &[UpdateLink],
+        random_element: Fr,
+        level: VerificationLevel,
+    ) -> Result<(), VerificationError> {
+        let mode = links
+            .first()
+            .ok_or(VerificationError::NoUpdateProofs)?
+            .mode();
+
+        match mode {
+            ProofMode::G2Commitment => {
+                let update_proofs = links
+                    .iter()
+                    .map(|link| match link {
+                        UpdateLink::G2Commitment(proof) => Ok(*proof),
+                        UpdateLink::G1Commitment(_) => Err(VerificationError::MixedProofModes),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                SRS::verify_updates_at_level(before, after, &update_proofs, random_element, level)
+            }
+            ProofMode::G1Commitment => {
+                let update_proofs = links
+                    .iter()
+                    .map(|link| match link {
+                        UpdateLink::G1Commitment(proof) => Ok(*proof),
+                        UpdateLink::G2Commitment(_) => Err(VerificationError::MixedProofModes),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                SRS::verify_g1_updates_at_level(
+                    before,
+                    after,
+                    &update_proofs,
+                    random_element,
+                    level,
+                )
+            }
+        }
+    }
+
+    fn check_updates(
+        before: &SRS,
+        after: &SRS,
+        update_proofs: &[UpdateProof],
+        random_element: Fr,
+        level: VerificationLevel,
+    ) -> Result<(), VerificationError> {
         // If there are no update proofs and the user calls this method
-        // we return False regardless. Even if `before===after`
+        // we return an error regardless. Even if `before===after`
         // We do not accept a transition without a proof
-        let last_update = match update_proofs.last() {
-            Some(update) => update,
-            None => return false,
-        };
+        let last_update = update_proofs
+            .last()
+            .ok_or(VerificationError::NoUpdateProofs)?;
 
         // 1. Check that the updates finished at the ending SRS
-        if after.tau_g1[1] != last_update.new_accumulated_point {
-            return false;
+        if after.tau_g1[1].into_projective() != last_update.new_accumulated_point {
+            return Err(VerificationError::UpdateDoesNotEndAtSRS);
         }
 
-        // 2. Check the update proofs are correct and form a chain of updates
-        if !UpdateProof::verify_chain(before.tau_g1[1], update_proofs) {
-            return false;
+        // 2. Check that every update changed the SRS. An update using the secret one
+        // has a valid proof, but it does not contribute any randomness
+        if UpdateProof::first_no_op(before.tau_g1[1].into_projective(), update_proofs).is_some() {
+            return Err(VerificationError::NoOpUpdate);
         }
 
-        // 3. Check that the degree-1 component is not the identity element
+        // 3. Check the update proofs are correct and form a chain of updates
+        if !UpdateProof::verify_chain(before.tau_g1[1].into_projective(), update_proofs) {
+            return Err(VerificationError::InvalidUpdateChain);
+        }
+
+        // 4. Check that the degree-1 component is not the identity element
         // No need to check the other elements because the structure check will fail
         // if they are also not the identity element
         //
         // Since resulting SRS is not zero, it implies that the private key/randomness
         // used was also not zero. Which implies that the public key inside of the
         // update proof is not the identity element or the update proof check will fail.
-        if after.tau_g1[1].is_zero() {
-            return false;
-        }
-        if after.tau_g2[1].is_zero() {
-            return false;
+        if after.tau_g1[1].is_zero() || after.tau_g2[1].is_zero() {
+            return Err(VerificationError::IdentityElement);
         }
 
-        // 3. Check that the new SRS goes up in incremental powers
-        if !after.structure_check_opt(random_element) {
-            return false;
-        }
+        // 5. Check that the new SRS goes up in incremental powers
+        after.check_at_level(level, random_element)
+    }
 
-        true
+    // The same checks as `check_updates`, following the degree-1 G2 element
+    fn check_g1_updates(
+        before: &SRS,
+        after: &SRS,
+        update_proofs: &[G1UpdateProof],
+        random_element: Fr,
+        level: VerificationLevel,
+    ) -> Result<(), VerificationError> {
+        let last_update = update_proofs
+            .last()
+            .ok_or(VerificationError::NoUpdateProofs)?;
+
+        if after.tau_g2[1].into_projective() != last_update.new_accumulated_point {
+            return Err(VerificationError::UpdateDoesNotEndAtSRS);
+        }
+        let starting_point = before.tau_g2[1].into_projective();
+        if G1UpdateProof::first_no_op(starting_point, update_proofs).is_some() {
+            return Err(VerificationError::NoOpUpdate);
+        }
+        if !G1UpdateProof::verify_chain(starting_point, update_proofs) {
+            return Err(VerificationError::InvalidUpdateChain);
+        }
+        if after.tau_g1[1].is_zero() || after.tau_g2[1].is_zero() {
+            return Err(VerificationError::IdentityElement);
+        }
+        after.check_at_level(level, random_element)
     }
 
     // Check that the list of G1 and G2 elements are in the
     // prime order subgroup
     // These points are already checked to be on the curve which is _cheap_
     // so that we do not become victim to the invalid curve attack
-    pub fn subgroup_check(&self) -> bool {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "subgroup_check",
+            skip_all,
+            fields(num_g1 = self.tau_g1.len(), num_g2 = self.tau_g2.len())
+        )
+    )]
+    pub fn subgroup_check(&self) -> Result<(), VerificationError> {
+        subgroup_check_points(&self.tau_g1, &self.tau_g2)
+    }
+
+    // Checks that the G1 and G2 elements are in the prime order subgroup by
+    // checking random linear combinations of them, instead of each point.
+    //
+    // The exponents are 8 bits, so each combination costs roughly one
+    // addition per point, compared to a scalar multiplication per point for
+    // `subgroup_check`. Since the cofactors have small prime factors, a point
+    // outside of the subgroup can be cancelled out with noticeable probability,
+    // so we use enough independent combinations for a soundness error of 2^-64
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "batched_subgroup_check",
+            skip_all,
+            fields(num_g1 = self.tau_g1.len(), num_g2 = self.tau_g2.len())
+        )
+    )]
+    pub fn batched_subgroup_check<R: Rng>(&self, rng: &mut R) -> Result<(), VerificationError> {
         use crate::interop_subgroup_checks::{g1, g2};
 
-        let g1_points_affine = G1Projective::batch_normalization_into_affine(&self.tau_g1);
-        let g2_points_affine = G2Projective::batch_normalization_into_affine(&self.tau_g2);
-        for point in g1_points_affine {
-            if !g1::is_in_correct_subgroup_assuming_on_curve(&point) {
-                return false;
-            }
+        // Each round uses its own seed, so that the rounds can run in parallel
+        let g1_seeds: Vec<[u8; 32]> = (0..G1_BATCH_ROUNDS).map(|_| rng.gen()).collect();
+        let g2_seeds: Vec<[u8; 32]> = (0..G2_BATCH_ROUNDS).map(|_| rng.gen()).collect();
+
+        metrics::record_points_validated(self.tau_g1.len() + self.tau_g2.len());
+        let g1_valid = ark_std::cfg_into_iter!(g1_seeds).all(|seed| {
+            let exponents = small_exponents(seed, self.tau_g1.len());
+            let combination: G1Projective = small_linear_combination(&self.tau_g1, &exponents);
+            g1::is_in_correct_subgroup_assuming_on_curve(&combination.into_affine())
+        });
+        let g2_valid = ark_std::cfg_into_iter!(g2_seeds).all(|seed| {
+            let exponents = small_exponents(seed, self.tau_g2.len());
+            let combination: G2Projective = small_linear_combination(&self.tau_g2, &exponents);
+            g2::is_in_correct_subgroup_assuming_on_curve(&combination.into_affine())
+        });
+
+        if !(g1_valid && g2_valid) {
+            metrics::record_failure(FailureCategory::Subgroup);
+            return Err(VerificationError::NotInSubgroup);
         }
-        for point in g2_points_affine {
-            if !g2::is_in_correct_subgroup_assuming_on_curve(&point) {
-                return false;
-            }
-        }
-
-        true
+        Ok(())
     }
 
     // Verify that a single update was applied to transition `before` to `after`
@@ -202,90 +719,124 @@ impl SRS {
         after: &SRS,
         update_proof: &UpdateProof,
         random_element: Fr,
-    ) -> bool {
+    ) -> Result<(), VerificationError> {
         SRS::verify_updates(before, after, &[*update_proof], random_element)
     }
 
     // We detail the algorithm here: https://hackmd.io/C0lk1xyWQryGggRlNYDqZw#Appendix-1---Incremental-powers-of-tau-check-Batching
     // This allows us to check that the SRS has the correct structure using only 1 pairing
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "structure_check",
+            skip_all,
+            fields(num_g1 = self.tau_g1.len(), num_g2 = self.tau_g2.len())
+        )
+    )]
     pub fn structure_check_opt(&self, random_element: Fr) -> bool {
-        // Check will always pass if the random element is zero
-        // We return false in this case
-        if random_element.is_zero() {
-            return false;
-        }
-
-        let len_g1 = self.tau_g1.len();
-        let len_g2 = self.tau_g2.len();
-
-        let max_number_elements = std::cmp::max(len_g1, len_g2);
-        let rand_pow = vandemonde_challenge(random_element, max_number_elements - 1);
-
-        let tau_g2_0 = self.tau_g2[0];
-        let tau_g2_1 = self.tau_g2[1];
-
-        let tau_g1_0 = self.tau_g1[0];
-        let tau_g1_1 = self.tau_g1[1];
-
-        let scalars = rand_pow
-            .into_iter()
-            .map(|scalar| scalar.into_repr())
-            .collect_vec();
-
-        // All elements in G1 except the last element
-        #[allow(non_snake_case)]
-        let L = &self.tau_g1[0..len_g1 - 1];
-        assert_eq!(L.len(), len_g1 - 1);
-
-        // All elements in G1 except the first element
-        #[allow(non_snake_case)]
-        let R = &self.tau_g1[1..];
-        assert_eq!(R.len(), len_g1 - 1);
-
-        #[allow(non_snake_case)]
-        let L_comm = VariableBaseMSM::multi_scalar_mul(
-            &L.iter().map(|element| element.into_affine()).collect_vec(),
-            &scalars,
-        );
-        #[allow(non_snake_case)]
-        let R_comm = VariableBaseMSM::multi_scalar_mul(
-            &R.iter().map(|element| element.into_affine()).collect_vec(),
-            &scalars,
-        );
-        let p1 = ark_bls12_381::Bls12_381::pairing(L_comm, tau_g2_1);
-        let p2 = ark_bls12_381::Bls12_381::pairing(R_comm, tau_g2_0);
+        verification::structure_check(&self.tau_g1, &self.tau_g2, random_element)
+    }
 
-        if p1 != p2 {
-            return false;
+    // Checks the structure of the SRS as thoroughly as `level` asks for
+    pub fn check_at_level(
+        &self,
+        level: VerificationLevel,
+        random_element: Fr,
+    ) -> Result<(), VerificationError> {
+        let valid = match level {
+            VerificationLevel::Quick => self.sampled_structure_check(random_element),
+            VerificationLevel::Standard => self.structure_check_opt(random_element),
+            VerificationLevel::Exhaustive => {
+                self.subgroup_check()?;
+                self.structure_check()
+            }
+        };
+        if !valid {
+            return Err(VerificationError::InvalidStructure);
         }
+        Ok(())
+    }
 
-        // Check G2
-
-        // All elements in G2 except the last element
-        #[allow(non_snake_case)]
-        let L = &self.tau_g2[0..len_g2 - 1];
-        assert_eq!(L.len(), len_g2 - 1);
-
-        // All elements in G2 except the first element
-        #[allow(non_snake_case)]
-        let R = &self.tau_g2[1..];
-        assert_eq!(R.len(), len_g2 - 1);
+    // Checks a random sample of pairs of consecutive powers in each group. The
+    // pairs are chosen using `random_element`, and combined using its powers,
+    // so that each group needs a single pairing check
+    fn sampled_structure_check(&self, random_element: Fr) -> bool {
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&random_element.into_repr().to_bytes_le());
+        let mut rng = ChaCha20Rng::from_seed(seed);
+
+        let g1_pair = combine_sampled_pairs(&self.tau_g1, &mut rng, random_element);
+        let g2_pair = combine_sampled_pairs(&self.tau_g2, &mut rng, random_element);
+        verification::same_ratio(g1_pair, [self.tau_g2[0], self.tau_g2[1]])
+            && verification::same_ratio([self.tau_g1[0], self.tau_g1[1]], g2_pair)
+    }
 
-        #[allow(non_snake_case)]
-        let L_comm = VariableBaseMSM::multi_scalar_mul(
-            &L.iter().map(|element| element.into_affine()).collect_vec(),
-            &scalars,
-        );
-        #[allow(non_snake_case)]
-        let R_comm = VariableBaseMSM::multi_scalar_mul(
-            &R.iter().map(|element| element.into_affine()).collect_vec(),
-            &scalars,
-        );
+    // Checks the structure of the SRS `chunk_size` powers at a time, in the same
+    // way as `SRSShard::verify`, calling `is_cancelled` before each chunk
+    pub fn structure_check_cancellable(
+        &self,
+        random_element: Fr,
+        chunk_size: usize,
+        is_cancelled: impl Fn() -> bool,
+    ) -> Result<bool, Cancelled> {
+        self.structure_check_with_progress(
+            random_element,
+            chunk_size,
+            &mut NoProgress,
+            is_cancelled,
+        )
+    }
 
-        let p1 = ark_bls12_381::Bls12_381::pairing(tau_g1_1, L_comm);
-        let p2 = ark_bls12_381::Bls12_381::pairing(tau_g1_0, R_comm);
+    // Checks the structure of the SRS in the same way as `structure_check_cancellable`,
+    // reporting the number of powers checked after each chunk
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "structure_check",
+            skip_all,
+            fields(num_g1 = self.tau_g1.len(), num_g2 = self.tau_g2.len())
+        )
+    )]
+    pub fn structure_check_with_progress(
+        &self,
+        random_element: Fr,
+        chunk_size: usize,
+        progress: &mut impl ProgressSink,
+        is_cancelled: impl Fn() -> bool,
+    ) -> Result<bool, Cancelled> {
+        let chunk_size = std::cmp::max(chunk_size, 1);
+        let num_powers = std::cmp::max(self.tau_g1.len(), self.tau_g2.len());
+        let g2_tau = PreparedG2Tau::new([self.tau_g2[0], self.tau_g2[1]]);
+
+        for start in (0..num_powers).step_by(chunk_size) {
+            if is_cancelled() {
+                return Err(Cancelled);
+            }
 
-        p1 == p2
+            // Each chunk overlaps the next chunk by one power, so that every
+            // pair of consecutive powers is checked
+            let end = std::cmp::min(start + chunk_size + 1, num_powers);
+            let g1 = &self.tau_g1[std::cmp::min(start, self.tau_g1.len())..]
+                [..std::cmp::min(end, self.tau_g1.len()).saturating_sub(start)];
+            let g2 = &self.tau_g2[std::cmp::min(start, self.tau_g2.len())..]
+                [..std::cmp::min(end, self.tau_g2.len()).saturating_sub(start)];
+
+            if !verification::g1_powers_check_prepared(g1, &g2_tau, random_element)
+                || !verification::g2_powers_check(
+                    g2,
+                    [self.tau_g1[0], self.tau_g1[1]],
+                    random_element,
+                )
+            {
+                return Ok(false);
+            }
+            progress.progress(
+                Phase::Verify,
+                std::cmp::min(start + chunk_size, num_powers),
+                num_powers,
+            );
+        }
+        Ok(true)
     }
 
     // Inefficiently checks that the srs has the correct structure
@@ -325,16 +876,233 @@ impl SRS {
     }
 }
 
-fn vandemonde_challenge(x: Fr, n: usize) -> Vec<Fr> {
-    let mut challenges: Vec<Fr> = Vec::with_capacity(n);
-    challenges.push(x);
-    for i in 0..n - 1 {
-        challenges.push(challenges[i] * x);
+// An update of the SRS which is applied a chunk of points at a time.
+//
+// The degree-0 elements are not updated, so the G1 elements from degree-1
+// onwards are updated first, followed by the G2 elements from degree-1 onwards.
+pub struct ChunkedUpdate<'a> {
+    srs: &'a mut SRS,
+    powers_of_priv_key: Zeroizing<Vec<Fr>>,
+    commitment_to_secret: G2Projective,
+    chunk_size: usize,
+    points_updated: usize,
+}
+
+impl<'a> ChunkedUpdate<'a> {
+    // The total number of points which need to be updated
+    pub fn num_points(&self) -> usize {
+        (self.srs.tau_g1.len() - 1) + (self.srs.tau_g2.len() - 1)
+    }
+
+    // The number of points which have been updated so far
+    pub fn points_updated(&self) -> usize {
+        self.points_updated
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.points_updated == self.num_points()
+    }
+
+    // Updates the next chunk of points.
+    // Returns true if there are still points left to update
+    pub fn step(&mut self) -> bool {
+        let num_g1_points = self.srs.tau_g1.len() - 1;
+        let num_points = self.num_points();
+
+        let start = self.points_updated;
+        let end = std::cmp::min(start.saturating_add(self.chunk_size), num_points);
+
+        if start < num_g1_points {
+            let g1_end = std::cmp::min(end, num_g1_points);
+            update_points(
+                // Skip the degree-0 element as it does not get updated
+                &mut self.srs.tau_g1[1 + start..1 + g1_end],
+                &self.powers_of_priv_key[start..g1_end],
+            );
+        }
+        if end > num_g1_points {
+            let g2_start = std::cmp::max(start, num_g1_points) - num_g1_points;
+            let g2_end = end - num_g1_points;
+            update_points(
+                // Skip the degree-0 element as it does not get updated
+                &mut self.srs.tau_g2[1 + g2_start..1 + g2_end],
+                &self.powers_of_priv_key[g2_start..g2_end],
+            );
+        }
+
+        self.points_updated = end;
+
+        !self.is_finished()
+    }
+
+    // Updates the remaining points a chunk at a time, calling `is_cancelled`
+    // before each chunk.
+    //
+    // If the update is cancelled, the SRS is left partially updated. It can be
+    // resumed later using `checkpoint`, or discarded
+    pub fn run_cancellable(&mut self, is_cancelled: impl Fn() -> bool) -> Result<(), Cancelled> {
+        self.run_with_progress(&mut NoProgress, is_cancelled)
+    }
+
+    // Updates the remaining points in the same way as `run_cancellable`,
+    // reporting the number of points updated after each chunk
+    pub fn run_with_progress(
+        &mut self,
+        progress: &mut impl ProgressSink,
+        is_cancelled: impl Fn() -> bool,
+    ) -> Result<(), Cancelled> {
+        while !self.is_finished() {
+            if is_cancelled() {
+                return Err(Cancelled);
+            }
+            self.step();
+            progress.progress(Phase::Update, self.points_updated, self.num_points());
+        }
+        Ok(())
+    }
+
+    // Records the progress of the update, so that it can be resumed using
+    // `SRS::resume_update` if the process is restarted.
+    //
+    // The SRS must be saved along with the checkpoint, for example using
+    // `SRS::write_to`. The private key is not part of the checkpoint, so it
+    // has to be kept until the update is finished
+    pub fn checkpoint(&self) -> UpdateCheckpoint {
+        UpdateCheckpoint {
+            points_updated: self.points_updated,
+            commitment_to_secret: commitment_to_hex(self.commitment_to_secret),
+            digest: self.srs.digest(),
+        }
+    }
+
+    // Updates any remaining points and produces a proof of the update
+    pub fn finish(mut self) -> UpdateProof {
+        while self.step() {}
+
+        UpdateProof {
+            commitment_to_secret: self.commitment_to_secret,
+            new_accumulated_point: self.srs.tau_g1[1].into_projective(),
+        }
+    }
+}
+
+// The progress of a `ChunkedUpdate`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCheckpoint {
+    pub points_updated: usize,
+    // The 0x prefixed hex encoding of the commitment to the private key
+    pub commitment_to_secret: String,
+    // The digest of the partially updated SRS
+    pub digest: SRSDigest,
+}
+
+fn commitment_to_hex(commitment_to_secret: G2Projective) -> String {
+    let mut commitment = hex::encode(serialize_g2(&commitment_to_secret.into_affine()));
+    commitment.insert_str(0, "0x");
+    commitment
+}
+
+// Checks that each point is in the prime order subgroup, see `SRS::subgroup_check`
+pub(crate) fn subgroup_check_points(
+    tau_g1: &[G1Affine],
+    tau_g2: &[G2Affine],
+) -> Result<(), VerificationError> {
+    use crate::interop_subgroup_checks::{g1, g2};
+
+    metrics::record_points_validated(tau_g1.len() + tau_g2.len());
+    let g1_valid = ark_std::cfg_chunks!(tau_g1, SUBGROUP_CHECK_CHUNK_SIZE).all(|chunk| {
+        chunk
+            .iter()
+            .all(g1::is_in_correct_subgroup_assuming_on_curve)
+    });
+    let g2_valid = ark_std::cfg_chunks!(tau_g2, SUBGROUP_CHECK_CHUNK_SIZE).all(|chunk| {
+        chunk
+            .iter()
+            .all(g2::is_in_correct_subgroup_assuming_on_curve)
+    });
+
+    if !(g1_valid && g2_valid) {
+        metrics::record_failure(FailureCategory::Subgroup);
+        return Err(VerificationError::NotInSubgroup);
     }
-    challenges
+    Ok(())
+}
+
+// Multiplies each point by its corresponding power of the private key.
+//
+// Each chunk of points is converted to projective form for the multiplications,
+// and then converted back using a single inversion
+pub(crate) fn update_points<A: AffineCurve>(
+    points: &mut [A],
+    powers_of_priv_key: &[A::ScalarField],
+) {
+    use ark_ec::wnaf::WnafContext;
+
+    let wnaf = WnafContext::new(3);
+
+    ark_std::cfg_chunks_mut!(points, UPDATE_CHUNK_SIZE)
+        .zip(ark_std::cfg_chunks!(powers_of_priv_key, UPDATE_CHUNK_SIZE))
+        .for_each(|(points, powers_of_priv_key)| {
+            let updated_points: Vec<A::Projective> = points
+                .iter()
+                .zip(powers_of_priv_key)
+                .map(|(point, priv_pow)| wnaf.mul(point.into_projective(), priv_pow))
+                .collect();
+            points.copy_from_slice(
+                &<A::Projective as ProjectiveCurve>::batch_normalization_into_affine(
+                    &updated_points,
+                ),
+            );
+        });
+}
+
+// Samples an 8 bit exponent for each point, using the seed
+fn small_exponents(seed: [u8; 32], num_points: usize) -> Vec<u8> {
+    let mut exponents = vec![0u8; num_points];
+    ChaCha20Rng::from_seed(seed).fill_bytes(&mut exponents);
+    exponents
+}
+
+// Returns sum_j r^j * p_(i_j) and sum_j r^j * p_(i_j + 1) for randomly
+// sampled indices i_j. `points` must contain at least two elements
+fn combine_sampled_pairs<A: AffineCurve<ScalarField = Fr>>(
+    points: &[A],
+    rng: &mut impl Rng,
+    random_element: Fr,
+) -> [A; 2] {
+    let mut left = A::Projective::zero();
+    let mut right = A::Projective::zero();
+    let mut power = Fr::one();
+    for _ in 0..QUICK_CHECK_SAMPLES {
+        let i = rng.gen_range(0..points.len() - 1);
+        left += points[i].mul(power.into_repr());
+        right += points[i + 1].mul(power.into_repr());
+        power *= random_element;
+    }
+    [left.into_affine(), right.into_affine()]
+}
+
+// Computes the sum of `exponents[i] * points[i]` by adding each point into the
+// bucket for its exponent
+fn small_linear_combination<C: ProjectiveCurve>(points: &[C::Affine], exponents: &[u8]) -> C {
+    let mut buckets = vec![C::zero(); 256];
+    for (point, exponent) in points.iter().zip(exponents) {
+        buckets[*exponent as usize].add_assign_mixed(point);
+    }
+
+    // sum_k k * B_k is the sum of the running sums, starting from the last bucket
+    let mut running_sum = C::zero();
+    let mut combination = C::zero();
+    for bucket in buckets.into_iter().skip(1).rev() {
+        running_sum += bucket;
+        combination += running_sum;
+    }
+    combination
 }
 
 #[cfg(test)]
+// The tests still build their SRS using `SRS::new_for_kzg`
+#[allow(deprecated)]
 mod tests {
     use super::*;
     use ark_ff::{Field, One, PrimeField};
@@ -348,12 +1116,86 @@ mod tests {
         let secret = PrivateKey::from_u64(0);
         let update_proof = after.update(secret);
 
-        assert!(!SRS::verify_update(
-            &before,
-            &after,
-            &update_proof,
-            Fr::from(123456789)
-        ));
+        assert_eq!(
+            SRS::verify_update(&before, &after, &update_proof, Fr::from(123456789)),
+            Err(VerificationError::IdentityElement)
+        );
+    }
+    #[test]
+    fn reject_no_op_update() {
+        // An update using the secret one leaves the SRS unchanged, so it should
+        // be rejected even when it is in the middle of a chain of updates
+        let before = SRS::new_for_kzg(100);
+        let mut after = before.clone();
+
+        let update_proofs = [
+            after.update(PrivateKey::from_u64(252)),
+            after.update(PrivateKey::from_u64(1)),
+            after.update(PrivateKey::from_u64(789)),
+        ];
+
+        assert_eq!(
+            SRS::verify_updates(&before, &after, &update_proofs, Fr::from(123456789)),
+            Err(VerificationError::NoOpUpdate)
+        );
+    }
+    #[test]
+    fn verification_levels() {
+        let before = SRS::new_for_kzg(100);
+        let mut after = before.clone();
+        let update_proof = after.update(PrivateKey::from_u64(252));
+        let levels = [
+            VerificationLevel::Quick,
+            VerificationLevel::Standard,
+            VerificationLevel::Exhaustive,
+        ];
+
+        for level in levels {
+            assert!(SRS::verify_updates_at_level(
+                &before,
+                &after,
+                &[update_proof],
+                Fr::from(123456789),
+                level
+            )
+            .is_ok());
+        }
+
+        // The G2 powers of a different secret fail every level, since every
+        // sampled pair is inconsistent with them
+        let mut other = before.clone();
+        other.update(PrivateKey::from_u64(789));
+        let mismatched =
+            SRS::from_vectors(after.g1_powers().to_vec(), other.g2_powers().to_vec()).unwrap();
+        for level in levels {
+            assert_eq!(
+                SRS::verify_updates_at_level(
+                    &before,
+                    &mismatched,
+                    &[update_proof],
+                    Fr::from(123456789),
+                    level
+                ),
+                Err(VerificationError::InvalidStructure)
+            );
+        }
+
+        // Swapping two powers is only certain to be caught by checking every power
+        let mut g1 = after.g1_powers().to_vec();
+        g1.swap(50, 51);
+        let swapped = SRS::from_vectors(g1, after.g2_powers().to_vec()).unwrap();
+        for level in [VerificationLevel::Standard, VerificationLevel::Exhaustive] {
+            assert_eq!(
+                SRS::verify_updates_at_level(
+                    &before,
+                    &swapped,
+                    &[update_proof],
+                    Fr::from(123456789),
+                    level
+                ),
+                Err(VerificationError::InvalidStructure)
+            );
+        }
     }
     #[test]
     fn zero_pow_zero() {
@@ -378,17 +1220,17 @@ mod tests {
         let mut expected_srs = got_srs.clone();
 
         let secret = PrivateKey::from_u64(123456789);
-        let secret_fr = secret.tau.clone();
+        let secret_fr = secret.tau;
 
         got_srs.update(secret);
 
         for (index, tg1) in expected_srs.tau_g1.iter_mut().enumerate() {
             let secret_pow_i = secret_fr.pow(&[index as u64]);
-            *tg1 = tg1.mul(secret_pow_i.into_repr())
+            *tg1 = tg1.mul(secret_pow_i.into_repr()).into_affine()
         }
         for (index, tg2) in expected_srs.tau_g2.iter_mut().enumerate() {
             let secret_pow_i = secret_fr.pow(&[index as u64]);
-            *tg2 = tg2.mul(secret_pow_i.into_repr())
+            *tg2 = tg2.mul(secret_pow_i.into_repr()).into_affine()
         }
 
         assert_eq!(expected_srs, got_srs)
@@ -403,7 +1245,7 @@ mod tests {
         let mut acc = SRS::new_for_kzg(100);
 
         // Simulate 3 participants updating the srs, one after the other
-        let before_update_1_degree_1 = acc.tau_g1[1];
+        let before_update_1_degree_1 = acc.tau_g1[1].into_projective();
         let update_proof_1 = acc.update(secret_a);
 
         let update_proof_2 = acc.update(secret_b);
@@ -417,6 +1259,237 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn parameters_builder() {
+        let params = Parameters::builder().eip_4844().build().unwrap();
+        assert_eq!(params, crate::sdk::CEREMONIES[0]);
+
+        let params = Parameters::builder().kzg(100).build().unwrap();
+        assert_eq!(params, Parameters::new(100, 2));
+
+        // Plonk circuits round the number of gates up to the domain size
+        let params = Parameters::builder().plonk(1000).build().unwrap();
+        assert_eq!(params, Parameters::new(1024 + 6, 2));
+        assert_eq!(
+            SRS::new_for_plonk(1024).unwrap().g1_elements().len(),
+            1024 + 6
+        );
+        assert!(matches!(
+            Parameters::builder().plonk(usize::MAX).build(),
+            Err(ParameterError::TooManyElements { .. })
+        ));
+
+        assert_eq!(
+            Parameters::builder().custom(0, 2).build(),
+            Err(ParameterError::ZeroElements)
+        );
+        assert_eq!(
+            Parameters::builder().custom(100, 1).build(),
+            Err(ParameterError::TooFewElements)
+        );
+        assert_eq!(
+            Parameters::builder()
+                .custom(1 << 20, 2)
+                .max_num_elements(1 << 16)
+                .build(),
+            Err(ParameterError::TooManyElements {
+                max: 1 << 16,
+                got: 1 << 20
+            })
+        );
+    }
+
+    #[test]
+    fn batched_subgroup_check() {
+        use ark_bls12_381::Fq;
+
+        let mut rng = rand::thread_rng();
+
+        let mut acc = SRS::new_for_kzg(100);
+        acc.update(PrivateKey::from_u64(252));
+        assert!(acc.batched_subgroup_check(&mut rng).is_ok());
+
+        // Find a point which is on the curve, but not in the prime order subgroup
+        let point_not_in_subgroup = (1u64..)
+            .filter_map(|x| G1Affine::get_point_from_x(Fq::from(x), false))
+            .find(|point| !point.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        acc.tau_g1[50] = point_not_in_subgroup;
+        assert_eq!(
+            acc.batched_subgroup_check(&mut rng),
+            Err(VerificationError::NotInSubgroup)
+        );
+        assert_eq!(acc.subgroup_check(), Err(VerificationError::NotInSubgroup));
+    }
+
+    #[test]
+    fn resume_update_from_checkpoint() {
+        let mut srs = SRS::new(Parameters::new(100, 25)).unwrap();
+
+        let mut expected_srs = srs.clone();
+        let expected_proof = expected_srs.update(PrivateKey::from_u64(252));
+
+        // Interrupt the update part of the way through
+        let checkpoint = {
+            let mut update = srs.chunked_update(PrivateKey::from_u64(252), 10);
+            update.step();
+            update.step();
+            update.checkpoint()
+        };
+        assert_eq!(checkpoint.points_updated, 20);
+
+        // The checkpoint can only be resumed with the same private key and SRS
+        assert!(matches!(
+            srs.resume_update(PrivateKey::from_u64(3), 10, &checkpoint),
+            Err(VerificationError::CheckpointMismatch)
+        ));
+        assert!(matches!(
+            expected_srs
+                .clone()
+                .resume_update(PrivateKey::from_u64(252), 10, &checkpoint),
+            Err(VerificationError::CheckpointMismatch)
+        ));
+
+        let update_proof = srs
+            .resume_update(PrivateKey::from_u64(252), 10, &checkpoint)
+            .unwrap()
+            .finish();
+        assert_eq!(srs, expected_srs);
+        assert_eq!(update_proof, expected_proof);
+    }
+
+    #[test]
+    fn cancel_update_and_structure_check() {
+        use std::cell::Cell;
+
+        let mut srs = SRS::new(Parameters::new(100, 25)).unwrap();
+
+        let mut expected_srs = srs.clone();
+        let expected_proof = expected_srs.update(PrivateKey::from_u64(252));
+
+        // Progress is reported after every chunk
+        let mut progress_srs = srs.clone();
+        let mut reports = Vec::new();
+        let mut update = progress_srs.chunked_update(PrivateKey::from_u64(252), 50);
+        update
+            .run_with_progress(
+                &mut |phase: Phase, done: usize, total: usize| reports.push((phase, done, total)),
+                || false,
+            )
+            .unwrap();
+        assert_eq!(update.finish(), expected_proof);
+        assert_eq!(
+            reports,
+            vec![
+                (Phase::Update, 50, 123),
+                (Phase::Update, 100, 123),
+                (Phase::Update, 123, 123)
+            ]
+        );
+
+        // Cancel the update after two chunks
+        let num_chunks = Cell::new(0);
+        let mut update = srs.chunked_update(PrivateKey::from_u64(252), 10);
+        assert_eq!(
+            update.run_cancellable(|| {
+                num_chunks.set(num_chunks.get() + 1);
+                num_chunks.get() > 2
+            }),
+            Err(Cancelled)
+        );
+        assert_eq!(update.points_updated(), 20);
+
+        // The update can still be finished after it was cancelled
+        assert_eq!(update.run_cancellable(|| false), Ok(()));
+        assert_eq!(update.finish(), expected_proof);
+        assert_eq!(srs, expected_srs);
+
+        let random_element = Fr::from(123456789u64);
+        assert_eq!(
+            srs.structure_check_cancellable(random_element, 16, || false),
+            Ok(true)
+        );
+        assert_eq!(
+            srs.structure_check_cancellable(random_element, 16, || true),
+            Err(Cancelled)
+        );
+
+        srs.tau_g1.swap(40, 41);
+        assert_eq!(
+            srs.structure_check_cancellable(random_element, 16, || false),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn truncated_srs_verifies() {
+        let before = SRS::new_for_kzg(100);
+        let mut after = before.clone();
+        let update_proof = after.update(PrivateKey::from_u64(252));
+
+        let truncated_before = before.truncate(10, 2).unwrap();
+        let truncated_after = after.truncate(10, 2).unwrap();
+        assert_eq!(truncated_after.g1_elements(), &after.g1_elements()[..10]);
+        assert!(SRS::verify_update(
+            &truncated_before,
+            &truncated_after,
+            &update_proof,
+            Fr::from(123456789)
+        )
+        .is_ok());
+
+        assert_eq!(after.truncate(1, 2), Err(ParameterError::TooFewElements));
+        assert!(after.truncate(101, 2).is_err());
+    }
+
+    #[test]
+    fn shifted_powers() {
+        let secret = PrivateKey::from_u64(252);
+        let secret_fr = secret.tau;
+
+        let mut acc = SRS::new_for_kzg(100);
+        acc.update(secret);
+
+        let shifted = acc.shifted_g1_powers(50, 10).unwrap();
+        assert_eq!(shifted.len(), 11);
+        for (i, power) in shifted.iter().enumerate() {
+            let expected = G1Projective::prime_subgroup_generator()
+                .mul(secret_fr.pow(&[(40 + i) as u64]).into_repr());
+            assert_eq!(*power, expected.into_affine());
+        }
+        assert_eq!(acc.shifted_g1_powers(99, 99).unwrap(), acc.g1_powers());
+
+        assert!(acc.shifted_g1_powers(100, 10).is_err());
+        assert_eq!(
+            acc.shifted_g1_powers(50, 51),
+            Err(ParameterError::InvalidDegreeBound {
+                degree_bound: 51,
+                max_degree: 50
+            })
+        );
+        assert!(acc.shifted_g1_powers(50, 0).is_err());
+    }
+
+    #[test]
+    fn affine_powers() {
+        let secret = PrivateKey::from_u64(252);
+        let secret_fr = secret.tau;
+
+        let mut acc = SRS::new_for_kzg(100);
+        acc.update(secret);
+
+        let g1_powers = acc.g1_powers();
+        assert_eq!(g1_powers.len(), 100);
+        assert_eq!(acc.g2_powers().len(), 2);
+
+        let expected =
+            G1Projective::prime_subgroup_generator().mul(secret_fr.pow(&[3]).into_repr());
+        assert_eq!(g1_powers[3], expected.into_affine());
+        assert_eq!(acc.g1_power(3), Some(expected.into_affine()));
+        assert_eq!(acc.g1_power(100), None);
+        assert_eq!(acc.g2_power(1), Some(acc.tau_g2[1]));
+    }
+
     #[test]
     fn structure_checks_probabilistic() {
         let secret_a = PrivateKey::from_u64(252);
@@ -0,0 +1,228 @@
+// Test vectors for the point encoding and contribution format used by the
+// KZG ceremony specs, see https://github.com/ethereum/kzg-ceremony-specs
+//
+// The vectors are exposed so that other implementations can be checked
+// against the same data, and `run_conformance_checks` checks this crate.
+use crate::{
+    interop_point_encoding::{
+        deserialize_g1, deserialize_g2, serialize_g1, serialize_g2, G1_SERIALISED_SIZE,
+        G2_SERIALISED_SIZE,
+    },
+    keypair::PrivateKey,
+    sdk::CEREMONIES,
+    serialisation::{hex_string_to_bytes, SRSJson},
+    srs::{Parameters, SRS},
+};
+use ark_bls12_381::Fr;
+use ark_ff::One;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    G1,
+    G2,
+}
+
+// A compressed point, and whether it should be accepted by a deserialiser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointEncodingVector {
+    pub name: &'static str,
+    pub group: Group,
+    pub hex: &'static str,
+    pub valid: bool,
+}
+
+pub const G1_GENERATOR: &str = "0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb";
+pub const G2_GENERATOR: &str = "0x93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8";
+
+pub const POINT_ENCODING_VECTORS: &[PointEncodingVector] = &[
+    PointEncodingVector {
+        name: "g1 generator",
+        group: Group::G1,
+        hex: G1_GENERATOR,
+        valid: true,
+    },
+    PointEncodingVector {
+        name: "g1 negated generator",
+        group: Group::G1,
+        hex: "0xb7f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+        valid: true,
+    },
+    PointEncodingVector {
+        name: "g1 infinity",
+        group: Group::G1,
+        hex: "0xc00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        valid: true,
+    },
+    PointEncodingVector {
+        name: "g1 missing compression flag",
+        group: Group::G1,
+        hex: "0x17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+        valid: false,
+    },
+    PointEncodingVector {
+        name: "g1 infinity with sort flag",
+        group: Group::G1,
+        hex: "0xe00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        valid: false,
+    },
+    PointEncodingVector {
+        name: "g1 infinity with non-zero x",
+        group: Group::G1,
+        hex: "0xc00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001",
+        valid: false,
+    },
+    PointEncodingVector {
+        name: "g1 x equal to the field modulus",
+        group: Group::G1,
+        hex: "0x9a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab",
+        valid: false,
+    },
+    PointEncodingVector {
+        name: "g2 generator",
+        group: Group::G2,
+        hex: G2_GENERATOR,
+        valid: true,
+    },
+    PointEncodingVector {
+        name: "g2 negated generator",
+        group: Group::G2,
+        hex: "0xb3e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+        valid: true,
+    },
+    PointEncodingVector {
+        name: "g2 infinity",
+        group: Group::G2,
+        hex: "0xc00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        valid: true,
+    },
+    PointEncodingVector {
+        name: "g2 missing compression flag",
+        group: Group::G2,
+        hex: "0x13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+        valid: false,
+    },
+    PointEncodingVector {
+        name: "g2 infinity with non-zero x",
+        group: Group::G2,
+        hex: "0xc00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001",
+        valid: false,
+    },
+    PointEncodingVector {
+        name: "g2 x.c1 equal to the field modulus",
+        group: Group::G2,
+        hex: "0x9a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        valid: false,
+    },
+];
+
+// A check which this crate does not pass, along with what was expected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceFailure {
+    pub name: &'static str,
+    pub expected: String,
+    pub got: String,
+}
+
+// Checks that the valid vectors round trip, and that the invalid vectors are rejected
+pub fn check_point_encodings() -> Result<(), ConformanceFailure> {
+    for vector in POINT_ENCODING_VECTORS {
+        let bytes = hex_string_to_bytes(vector.hex).expect("test vectors are valid hex");
+
+        let roundtrip = match vector.group {
+            Group::G1 => <[u8; G1_SERIALISED_SIZE]>::try_from(bytes.as_slice())
+                .ok()
+                .and_then(deserialize_g1)
+                .map(|point| serialize_g1(&point).to_vec()),
+            Group::G2 => <[u8; G2_SERIALISED_SIZE]>::try_from(bytes.as_slice())
+                .ok()
+                .and_then(deserialize_g2)
+                .map(|point| serialize_g2(&point).to_vec()),
+        };
+
+        let expected = if vector.valid { Some(bytes) } else { None };
+        if roundtrip != expected {
+            return Err(ConformanceFailure {
+                name: vector.name,
+                expected: format!("{:?}", expected.map(hex::encode)),
+                got: format!("{:?}", roundtrip.map(hex::encode)),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Checks the initial contribution of each ceremony, where every power is the generator
+pub fn check_initial_contribution() -> Result<(), ConformanceFailure> {
+    for parameters in CEREMONIES {
+        let srs = SRS::new(parameters).expect("ceremony parameters are valid");
+        let srs_json = serde_json::to_value(SRSJson::from(&srs)).expect("SRSJson serialises");
+
+        let expected = serde_json::json!({
+            "numG1Powers": parameters.num_g1_elements_needed,
+            "numG2Powers": parameters.num_g2_elements_needed,
+            "powersOfTau": {
+                "G1Powers": vec![G1_GENERATOR; parameters.num_g1_elements_needed],
+                "G2Powers": vec![G2_GENERATOR; parameters.num_g2_elements_needed],
+            },
+            "potPubkey": G2_GENERATOR,
+        });
+        if srs_json != expected {
+            return Err(ConformanceFailure {
+                name: "initial contribution",
+                expected: format!(
+                    "{} G1 and {} G2 generators",
+                    parameters.num_g1_elements_needed, parameters.num_g2_elements_needed
+                ),
+                got: srs.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Checks a contribution using the secret one, which leaves the SRS unchanged and
+// whose pot pubkey and update proof only contain the generators
+pub fn check_identity_contribution() -> Result<(), ConformanceFailure> {
+    // The constructors of `PrivateKey` refuse a secret of one, since an honest
+    // contribution should never use it
+    let secret = || PrivateKey { tau: Fr::one() };
+
+    let pot_pubkey = secret().to_pot_pubkey();
+    if pot_pubkey != G2_GENERATOR {
+        return Err(ConformanceFailure {
+            name: "identity contribution pot pubkey",
+            expected: G2_GENERATOR.to_string(),
+            got: pot_pubkey,
+        });
+    }
+
+    let mut srs = SRS::new(Parameters::new(4, 2)).expect("parameters are valid");
+    let starting_srs = srs.clone();
+    let update_proof = srs.update(secret());
+
+    let expected_proof = [G2_GENERATOR.to_string(), G1_GENERATOR.to_string()];
+    if update_proof.serialise() != expected_proof || srs != starting_srs {
+        return Err(ConformanceFailure {
+            name: "identity contribution update proof",
+            expected: format!("{:?}", expected_proof),
+            got: format!("{:?}", update_proof.serialise()),
+        });
+    }
+    Ok(())
+}
+
+pub fn run_conformance_checks() -> Result<(), ConformanceFailure> {
+    check_point_encodings()?;
+    check_initial_contribution()?;
+    check_identity_contribution()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conformance() {
+        assert_eq!(run_conformance_checks(), Ok(()));
+    }
+}
@@ -0,0 +1,118 @@
+// `Arbitrary` implementations and proptest strategies, so that integrations
+// can be property tested against valid (and invalid) ceremony data.
+//
+// The SRS which are generated are kept small, since every one of them
+// is updated with a random secret.
+use crate::{
+    keypair::PrivateKey,
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+use arbitrary::{Arbitrary, Unstructured};
+use ark_bls12_381::Fr;
+use proptest::prelude::*;
+
+// The largest number of G1 and G2 elements in a generated SRS
+pub const MAX_NUM_G1_ELEMENTS: usize = 64;
+pub const MAX_NUM_G2_ELEMENTS: usize = 16;
+
+impl<'a> Arbitrary<'a> for Parameters {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Parameters::new(
+            u.int_in_range(2..=MAX_NUM_G1_ELEMENTS)?,
+            u.int_in_range(2..=MAX_NUM_G2_ELEMENTS)?,
+        ))
+    }
+}
+
+impl<'a> Arbitrary<'a> for SRS {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let parameters = Parameters::arbitrary(u)?;
+        let secret = <[u8; 32]>::arbitrary(u)?;
+        Ok(updated_srs(parameters, secret))
+    }
+}
+
+impl<'a> Arbitrary<'a> for UpdateProof {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let secret = <[u8; 32]>::arbitrary(u)?;
+        Ok(update_proof_from_secret(secret))
+    }
+}
+
+// An SRS which has been updated once using `secret`
+fn updated_srs(parameters: Parameters, secret: [u8; 32]) -> SRS {
+    let mut srs = SRS::new(parameters).expect("parameters have at least two elements");
+    srs.update(private_key_from_secret(secret));
+    srs
+}
+
+// The proof of an update to the starting SRS using `secret`
+fn update_proof_from_secret(secret: [u8; 32]) -> UpdateProof {
+    let mut srs = SRS::new(Parameters::new(2, 2)).expect("parameters have at least two elements");
+    srs.update(private_key_from_secret(secret))
+}
+
+// Generated secrets can be zero or one, which `PrivateKey` refuses, so these
+// are replaced with two rather than discarding the case
+fn private_key_from_secret(secret: [u8; 32]) -> PrivateKey {
+    PrivateKey::from_bytes(&secret).unwrap_or(PrivateKey {
+        tau: Fr::from(2u64),
+    })
+}
+
+pub fn parameters() -> impl Strategy<Value = Parameters> {
+    (2..=MAX_NUM_G1_ELEMENTS, 2..=MAX_NUM_G2_ELEMENTS)
+        .prop_map(|(num_g1, num_g2)| Parameters::new(num_g1, num_g2))
+}
+
+// An SRS which has been updated once, along with its parameters
+pub fn srs() -> impl Strategy<Value = (Parameters, SRS)> {
+    (parameters(), any::<[u8; 32]>())
+        .prop_map(|(parameters, secret)| (parameters, updated_srs(parameters, secret)))
+}
+
+pub fn update_proof() -> impl Strategy<Value = UpdateProof> {
+    any::<[u8; 32]>().prop_map(update_proof_from_secret)
+}
+
+// The bytes of an SRS, as serialised by `SRS::serialise_bytes`
+pub fn srs_bytes() -> impl Strategy<Value = (Parameters, Vec<u8>)> {
+    srs().prop_map(|(parameters, srs)| (parameters, srs.serialise_bytes()))
+}
+
+// The bytes of an SRS with a single bit flipped. These should either fail to
+// deserialise, or deserialise into an SRS which fails the structure check
+pub fn corrupted_srs_bytes() -> impl Strategy<Value = (Parameters, Vec<u8>)> {
+    (srs_bytes(), any::<prop::sample::Index>(), 0..8u8).prop_map(
+        |((parameters, mut bytes), index, bit)| {
+            let index = index.index(bytes.len());
+            bytes[index] ^= 1 << bit;
+            (parameters, bytes)
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialisation::SubgroupCheck;
+    use ark_bls12_381::Fr;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn srs_bytes_roundtrip((parameters, bytes) in srs_bytes()) {
+            let srs = SRS::deserialise_bytes(&bytes, parameters, SubgroupCheck::Full).unwrap();
+            prop_assert_eq!(srs.serialise_bytes(), bytes);
+        }
+
+        #[test]
+        fn corrupted_srs_bytes_are_rejected((parameters, bytes) in corrupted_srs_bytes()) {
+            if let Ok(srs) = SRS::deserialise_bytes(&bytes, parameters, SubgroupCheck::Full) {
+                prop_assert!(!srs.structure_check_opt(Fr::from(123456789u64)));
+            }
+        }
+    }
+}
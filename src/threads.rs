@@ -0,0 +1,80 @@
+// Controls which threads are used by the parallel updates and checks.
+//
+// By default, the parallel iterators in this crate use the global rayon
+// thread pool. Applications which manage their own pools, or which need to
+// run on a single thread, can run the work on other threads using `Threads::install`.
+//
+// Without the `parallel` feature, all of the work runs on the calling thread.
+#[derive(Debug, Clone)]
+pub enum Threads {
+    // Use the global rayon thread pool
+    Global,
+    // Use a new thread pool with the given number of threads.
+    // A single thread runs all of the work sequentially
+    Count(usize),
+    // Use a thread pool managed by the caller
+    #[cfg(feature = "parallel")]
+    Pool(std::sync::Arc<rayon::ThreadPool>),
+}
+
+impl Default for Threads {
+    fn default() -> Self {
+        Threads::Global
+    }
+}
+
+impl Threads {
+    // Runs `op` so that any parallel work within it uses these threads, for example
+    //
+    // Threads::Count(4).install(|| srs.update(private_key))
+    #[cfg(feature = "parallel")]
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match self {
+            Threads::Global => op(),
+            Threads::Count(num_threads) => rayon::ThreadPoolBuilder::new()
+                .num_threads(std::cmp::max(*num_threads, 1))
+                .build()
+                .expect("failed to build the thread pool")
+                .install(op),
+            Threads::Pool(pool) => pool.install(op),
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        op()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        keypair::PrivateKey,
+        srs::{Parameters, SRS},
+    };
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn update_on_a_single_thread() {
+        let srs = SRS::new(Parameters::new(100, 25)).unwrap();
+
+        let mut global_srs = srs.clone();
+        let global_proof = global_srs.update(PrivateKey::from_u64(252));
+
+        let mut single_thread_srs = srs.clone();
+        let single_thread_proof =
+            Threads::Count(1).install(|| single_thread_srs.update(PrivateKey::from_u64(252)));
+
+        assert_eq!(global_srs, single_thread_srs);
+        assert_eq!(global_proof, single_thread_proof);
+        assert!(Threads::Count(1)
+            .install(|| SRS::verify_update(
+                &srs,
+                &single_thread_srs,
+                &single_thread_proof,
+                Fr::from(123456789u64)
+            ))
+            .is_ok());
+    }
+}
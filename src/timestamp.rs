@@ -0,0 +1,530 @@
+// OpenTimestamps proofs over the transcript digest, which anchor the existence
+// of the transcript in a Bitcoin block, see https://opentimestamps.org.
+//
+// This module reads and writes the `.ots` format but does not talk to the
+// network. To create a proof, submit the digest to one or more calendars and
+// pass their responses to `DetachedTimestamp::from_calendar_responses`. Once
+// the calendars have committed to Bitcoin, fetch the timestamp of each
+// `pending` commitment and pass it to `upgrade`. An auditor then checks the
+// proof against the block headers from their own node using `verify`.
+//
+// Only the operations which the calendars use are supported; sha256, append,
+// prepend and reverse.
+use crate::error::TimestampError;
+use sha2::{Digest, Sha256};
+
+const HEADER_MAGIC: &[u8] = b"\x00OpenTimestamps\x00\x00Proof\x00\xbf\x89\xe2\xe8\x84\xe8\x92\x94";
+const VERSION: u64 = 1;
+
+// Limits from the reference implementation, which stop a malicious proof from
+// using unbounded memory or stack
+const MAX_MESSAGE_LENGTH: usize = 4096;
+const MAX_PAYLOAD_LENGTH: usize = 8192;
+const MAX_DEPTH: usize = 256;
+
+const TAG_ATTESTATION: u8 = 0x00;
+const TAG_FORK: u8 = 0xff;
+const OP_SHA256: u8 = 0x08;
+const OP_APPEND: u8 = 0xf0;
+const OP_PREPEND: u8 = 0xf1;
+const OP_REVERSE: u8 = 0xf2;
+
+const BITCOIN_TAG: [u8; 8] = [0x05, 0x88, 0x96, 0x0d, 0x73, 0xd7, 0x19, 0x01];
+const PENDING_TAG: [u8; 8] = [0x83, 0xdf, 0xe3, 0x0d, 0x2e, 0xf9, 0x0c, 0x8e];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Sha256,
+    Append(Vec<u8>),
+    Prepend(Vec<u8>),
+    Reverse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attestation {
+    // The message is the merkle root of the block at this height
+    Bitcoin { height: u64 },
+    // The calendar at `uri` has not yet committed the message to Bitcoin
+    Pending { uri: String },
+    // An attestation to another chain, which is kept but not checked
+    Unknown { tag: [u8; 8], payload: Vec<u8> },
+}
+
+// A tree of operations starting from `message`, whose leaves are attestations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timestamp {
+    pub message: Vec<u8>,
+    pub attestations: Vec<Attestation>,
+    pub operations: Vec<(Operation, Timestamp)>,
+}
+
+// A timestamp of a SHA-256 digest, which is what an `.ots` file holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetachedTimestamp {
+    pub timestamp: Timestamp,
+}
+
+impl DetachedTimestamp {
+    // Combines the responses of the calendars to which `digest` was submitted
+    pub fn from_calendar_responses(
+        digest: [u8; 32],
+        responses: &[&[u8]],
+    ) -> Result<Self, TimestampError> {
+        if responses.is_empty() {
+            return Err(TimestampError::NoCalendarResponses);
+        }
+        let mut timestamp = Timestamp::new(digest.to_vec());
+        for response in responses {
+            timestamp.merge(read_response(response, digest.to_vec())?);
+        }
+        Ok(DetachedTimestamp { timestamp })
+    }
+
+    pub fn digest(&self) -> [u8; 32] {
+        // The digest is always 32 bytes, since it is read as such
+        self.timestamp.message.as_slice().try_into().unwrap()
+    }
+
+    pub fn serialise(&self) -> Vec<u8> {
+        let mut bytes = HEADER_MAGIC.to_vec();
+        write_varuint(VERSION, &mut bytes);
+        bytes.push(OP_SHA256);
+        bytes.extend_from_slice(&self.timestamp.message);
+        self.timestamp.write(&mut bytes);
+        bytes
+    }
+
+    pub fn deserialise(bytes: &[u8]) -> Result<Self, TimestampError> {
+        let mut reader = Reader::new(bytes);
+        if reader.read_bytes(HEADER_MAGIC.len())? != HEADER_MAGIC {
+            return Err(TimestampError::BadMagic);
+        }
+        let version = reader.read_varuint()?;
+        if version != VERSION {
+            return Err(TimestampError::UnsupportedVersion(version));
+        }
+        // The operation which was used to hash the file
+        let file_hash = reader.read_u8()?;
+        if file_hash != OP_SHA256 {
+            return Err(TimestampError::UnsupportedOperation(file_hash));
+        }
+        let digest = reader.read_bytes(32)?.to_vec();
+
+        let timestamp = Timestamp::read(&mut reader, digest, 0)?;
+        reader.finish()?;
+        Ok(DetachedTimestamp { timestamp })
+    }
+
+    // The commitments which calendars have not yet anchored in Bitcoin, along
+    // with the uri of the calendar to ask for the upgraded timestamp
+    pub fn pending(&self) -> Vec<(String, Vec<u8>)> {
+        let mut pending = Vec::new();
+        self.timestamp
+            .for_each_attestation(&mut |message, attestation| {
+                if let Attestation::Pending { uri } = attestation {
+                    pending.push((uri.clone(), message.to_vec()));
+                }
+            });
+        pending
+    }
+
+    // Adds the timestamp which a calendar returned for `commitment`
+    pub fn upgrade(&mut self, commitment: &[u8], response: &[u8]) -> Result<(), TimestampError> {
+        let upgraded = read_response(response, commitment.to_vec())?;
+        let timestamp = self
+            .timestamp
+            .find_mut(commitment)
+            .ok_or(TimestampError::UnknownCommitment)?;
+        timestamp.merge(upgraded);
+        Ok(())
+    }
+
+    // Checks that the proof is for `digest` and that its Bitcoin attestations
+    // match the merkle roots of the blocks, in the byte order of the block
+    // header. `merkle_root_at` returns `None` for blocks the caller does not
+    // have, whose attestations are skipped. Returns the heights of the blocks
+    // which attest to the digest, lowest first
+    pub fn verify(
+        &self,
+        digest: &[u8; 32],
+        merkle_root_at: impl Fn(u64) -> Option<[u8; 32]>,
+    ) -> Result<Vec<u64>, TimestampError> {
+        if self.timestamp.message != digest {
+            return Err(TimestampError::DigestMismatch);
+        }
+
+        let mut attestations = Vec::new();
+        self.timestamp
+            .for_each_attestation(&mut |message, attestation| {
+                if let Attestation::Bitcoin { height } = attestation {
+                    attestations.push((message.to_vec(), *height));
+                }
+            });
+
+        let mut heights = Vec::new();
+        for (message, height) in attestations {
+            if let Some(merkle_root) = merkle_root_at(height) {
+                if message != merkle_root {
+                    return Err(TimestampError::BitcoinMismatch { height });
+                }
+                heights.push(height);
+            }
+        }
+        if heights.is_empty() {
+            return Err(TimestampError::NotAttested);
+        }
+        heights.sort_unstable();
+        heights.dedup();
+        Ok(heights)
+    }
+}
+
+impl Timestamp {
+    pub fn new(message: Vec<u8>) -> Self {
+        Timestamp {
+            message,
+            attestations: Vec::new(),
+            operations: Vec::new(),
+        }
+    }
+
+    fn read(reader: &mut Reader, message: Vec<u8>, depth: usize) -> Result<Self, TimestampError> {
+        if depth > MAX_DEPTH {
+            return Err(TimestampError::TooLarge);
+        }
+        let mut timestamp = Timestamp::new(message);
+
+        // Every branch but the last is preceded by a fork
+        let mut tag = reader.read_u8()?;
+        while tag == TAG_FORK {
+            let branch = reader.read_u8()?;
+            timestamp.read_branch(reader, branch, depth)?;
+            tag = reader.read_u8()?;
+        }
+        timestamp.read_branch(reader, tag, depth)?;
+        Ok(timestamp)
+    }
+
+    fn read_branch(
+        &mut self,
+        reader: &mut Reader,
+        tag: u8,
+        depth: usize,
+    ) -> Result<(), TimestampError> {
+        if tag == TAG_ATTESTATION {
+            self.attestations.push(Attestation::read(reader)?);
+            return Ok(());
+        }
+        let operation = Operation::read(reader, tag)?;
+        let result = operation.apply(&self.message);
+        if result.len() > MAX_MESSAGE_LENGTH {
+            return Err(TimestampError::TooLarge);
+        }
+        let timestamp = Timestamp::read(reader, result, depth + 1)?;
+        self.operations.push((operation, timestamp));
+        Ok(())
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        let mut remaining = self.attestations.len() + self.operations.len();
+        for attestation in &self.attestations {
+            remaining -= 1;
+            if remaining > 0 {
+                bytes.push(TAG_FORK);
+            }
+            bytes.push(TAG_ATTESTATION);
+            attestation.write(bytes);
+        }
+        for (operation, timestamp) in &self.operations {
+            remaining -= 1;
+            if remaining > 0 {
+                bytes.push(TAG_FORK);
+            }
+            operation.write(bytes);
+            timestamp.write(bytes);
+        }
+    }
+
+    fn merge(&mut self, other: Timestamp) {
+        for attestation in other.attestations {
+            if !self.attestations.contains(&attestation) {
+                self.attestations.push(attestation);
+            }
+        }
+        for (operation, timestamp) in other.operations {
+            match self.operations.iter_mut().find(|(op, _)| *op == operation) {
+                Some((_, existing)) => existing.merge(timestamp),
+                None => self.operations.push((operation, timestamp)),
+            }
+        }
+    }
+
+    fn find_mut(&mut self, message: &[u8]) -> Option<&mut Timestamp> {
+        if self.message == message {
+            return Some(self);
+        }
+        self.operations
+            .iter_mut()
+            .find_map(|(_, timestamp)| timestamp.find_mut(message))
+    }
+
+    fn for_each_attestation<'a>(&'a self, f: &mut impl FnMut(&'a [u8], &'a Attestation)) {
+        for attestation in &self.attestations {
+            f(&self.message, attestation);
+        }
+        for (_, timestamp) in &self.operations {
+            timestamp.for_each_attestation(f);
+        }
+    }
+}
+
+impl Operation {
+    pub fn apply(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Operation::Sha256 => Sha256::digest(message).to_vec(),
+            Operation::Append(suffix) => [message, suffix.as_slice()].concat(),
+            Operation::Prepend(prefix) => [prefix.as_slice(), message].concat(),
+            Operation::Reverse => message.iter().rev().copied().collect(),
+        }
+    }
+
+    fn read(reader: &mut Reader, tag: u8) -> Result<Self, TimestampError> {
+        match tag {
+            OP_SHA256 => Ok(Operation::Sha256),
+            OP_APPEND => Ok(Operation::Append(reader.read_varbytes(MAX_MESSAGE_LENGTH)?)),
+            OP_PREPEND => Ok(Operation::Prepend(
+                reader.read_varbytes(MAX_MESSAGE_LENGTH)?,
+            )),
+            OP_REVERSE => Ok(Operation::Reverse),
+            tag => Err(TimestampError::UnsupportedOperation(tag)),
+        }
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        match self {
+            Operation::Sha256 => bytes.push(OP_SHA256),
+            Operation::Append(suffix) => {
+                bytes.push(OP_APPEND);
+                write_varbytes(suffix, bytes);
+            }
+            Operation::Prepend(prefix) => {
+                bytes.push(OP_PREPEND);
+                write_varbytes(prefix, bytes);
+            }
+            Operation::Reverse => bytes.push(OP_REVERSE),
+        }
+    }
+}
+
+impl Attestation {
+    fn read(reader: &mut Reader) -> Result<Self, TimestampError> {
+        let tag: [u8; 8] = reader.read_bytes(8)?.try_into().unwrap();
+        let payload = reader.read_varbytes(MAX_PAYLOAD_LENGTH)?;
+
+        let mut payload_reader = Reader::new(&payload);
+        let attestation = match tag {
+            BITCOIN_TAG => Attestation::Bitcoin {
+                height: payload_reader.read_varuint()?,
+            },
+            PENDING_TAG => {
+                let uri = payload_reader.read_varbytes(MAX_PAYLOAD_LENGTH)?;
+                Attestation::Pending {
+                    uri: String::from_utf8(uri).map_err(|_| TimestampError::InvalidAttestation)?,
+                }
+            }
+            _ => return Ok(Attestation::Unknown { tag, payload }),
+        };
+        payload_reader
+            .finish()
+            .map_err(|_| TimestampError::InvalidAttestation)?;
+        Ok(attestation)
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        let mut payload = Vec::new();
+        let tag = match self {
+            Attestation::Bitcoin { height } => {
+                write_varuint(*height, &mut payload);
+                BITCOIN_TAG
+            }
+            Attestation::Pending { uri } => {
+                write_varbytes(uri.as_bytes(), &mut payload);
+                PENDING_TAG
+            }
+            Attestation::Unknown {
+                tag,
+                payload: unknown,
+            } => {
+                payload.extend_from_slice(unknown);
+                *tag
+            }
+        };
+        bytes.extend_from_slice(&tag);
+        write_varbytes(&payload, bytes);
+    }
+}
+
+// A calendar responds with the serialised timestamp of the submitted message
+fn read_response(response: &[u8], message: Vec<u8>) -> Result<Timestamp, TimestampError> {
+    let mut reader = Reader::new(response);
+    let timestamp = Timestamp::read(&mut reader, message, 0)?;
+    reader.finish()?;
+    Ok(timestamp)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], TimestampError> {
+        if self.bytes.len() < len {
+            return Err(TimestampError::UnexpectedEnd);
+        }
+        let (read, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(read)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, TimestampError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    // Unsigned LEB128
+    fn read_varuint(&mut self) -> Result<u64, TimestampError> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            if shift >= 64 {
+                return Err(TimestampError::TooLarge);
+            }
+            let byte = self.read_u8()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_varbytes(&mut self, max_len: usize) -> Result<Vec<u8>, TimestampError> {
+        let len = self.read_varuint()?;
+        if len > max_len as u64 {
+            return Err(TimestampError::TooLarge);
+        }
+        Ok(self.read_bytes(len as usize)?.to_vec())
+    }
+
+    fn finish(&self) -> Result<(), TimestampError> {
+        if !self.bytes.is_empty() {
+            return Err(TimestampError::TrailingBytes);
+        }
+        Ok(())
+    }
+}
+
+fn write_varuint(mut value: u64, bytes: &mut Vec<u8>) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_varbytes(value: &[u8], bytes: &mut Vec<u8>) {
+    write_varuint(value.len() as u64, bytes);
+    bytes.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialise_timestamp(timestamp: &Timestamp) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        timestamp.write(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn create_upgrade_and_verify() {
+        let digest = [3u8; 32];
+        let uri = "https://alice.btc.calendar.opentimestamps.org".to_string();
+
+        // The calendar commits to the digest with a nonce
+        let commitment = Operation::Sha256.apply(&Operation::Append(vec![1, 2]).apply(&digest));
+        let mut calendar = Timestamp::new(commitment.clone());
+        calendar
+            .attestations
+            .push(Attestation::Pending { uri: uri.clone() });
+        let mut appended = Timestamp::new(Operation::Append(vec![1, 2]).apply(&digest));
+        appended.operations.push((Operation::Sha256, calendar));
+        let mut response = Timestamp::new(digest.to_vec());
+        response
+            .operations
+            .push((Operation::Append(vec![1, 2]), appended));
+
+        let mut proof =
+            DetachedTimestamp::from_calendar_responses(digest, &[&serialise_timestamp(&response)])
+                .unwrap();
+        assert_eq!(proof.pending(), vec![(uri, commitment.clone())]);
+        assert_eq!(
+            proof.verify(&digest, |_| None),
+            Err(TimestampError::NotAttested)
+        );
+
+        // Later, the calendar has included the commitment in block 100
+        let merkle_root = Operation::Sha256.apply(&Operation::Prepend(vec![9]).apply(&commitment));
+        let mut attested = Timestamp::new(merkle_root.clone());
+        attested
+            .attestations
+            .push(Attestation::Bitcoin { height: 100 });
+        let mut upgraded = Timestamp::new(Operation::Prepend(vec![9]).apply(&commitment));
+        upgraded.operations.push((Operation::Sha256, attested));
+        let mut upgrade = Timestamp::new(commitment.clone());
+        upgrade
+            .operations
+            .push((Operation::Prepend(vec![9]), upgraded));
+        proof
+            .upgrade(&commitment, &serialise_timestamp(&upgrade))
+            .unwrap();
+
+        let proof = DetachedTimestamp::deserialise(&proof.serialise()).unwrap();
+        let merkle_root: [u8; 32] = merkle_root.try_into().unwrap();
+        assert_eq!(
+            proof.verify(&digest, |height| (height == 100).then(|| merkle_root)),
+            Ok(vec![100])
+        );
+        assert_eq!(
+            proof.verify(&digest, |_| Some([0u8; 32])),
+            Err(TimestampError::BitcoinMismatch { height: 100 })
+        );
+        assert_eq!(
+            proof.verify(&[4u8; 32], |_| Some(merkle_root)),
+            Err(TimestampError::DigestMismatch)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_proofs() {
+        assert_eq!(
+            DetachedTimestamp::deserialise(b"not a proof"),
+            Err(TimestampError::UnexpectedEnd)
+        );
+
+        let mut bytes = HEADER_MAGIC.to_vec();
+        write_varuint(VERSION, &mut bytes);
+        bytes.push(OP_SHA256);
+        bytes.extend_from_slice(&[0u8; 32]);
+        // Keccak256, which calendars do not use
+        bytes.push(0x67);
+        assert_eq!(
+            DetachedTimestamp::deserialise(&bytes),
+            Err(TimestampError::UnsupportedOperation(0x67))
+        );
+    }
+}
@@ -0,0 +1,100 @@
+// Swift and Kotlin bindings for mobile wallets, generated by UniFFI from
+// `src/small_powers_of_tau.udl`.
+use ark_bls12_381::Fr;
+use ark_ff::UniformRand;
+use zeroize::Zeroize;
+
+use crate::{
+    keypair::PrivateKey,
+    serialisation::{SubgroupCheck, UPDATE_PROOF_SERIALISED_SIZE},
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+
+#[derive(Debug)]
+pub enum CeremonyError {
+    // The SRS bytes did not match the parameters or contained invalid points
+    Deserialisation,
+    // The update proof bytes were not a valid update proof
+    InvalidUpdateProof,
+    // The secret was zero or one modulo the group order
+    InvalidSecret,
+}
+
+impl std::fmt::Display for CeremonyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CeremonyError::Deserialisation => write!(f, "could not deserialise the SRS"),
+            CeremonyError::InvalidUpdateProof => {
+                write!(f, "could not deserialise the update proof")
+            }
+            CeremonyError::InvalidSecret => write!(f, "the secret must not be zero or one"),
+        }
+    }
+}
+
+impl std::error::Error for CeremonyError {}
+
+pub struct ContributeResult {
+    pub srs: Vec<u8>,
+    pub update_proof: Vec<u8>,
+}
+
+fn deserialise_srs(srs: &[u8], num_g1: u32, num_g2: u32) -> Result<SRS, CeremonyError> {
+    let parameters = Parameters::new(num_g1 as usize, num_g2 as usize);
+    SRS::deserialise_bytes(srs, parameters, SubgroupCheck::Full)
+        .map_err(|_| CeremonyError::Deserialisation)
+}
+
+// Updates the SRS using the secret, returning the updated SRS and the update proof
+pub fn contribute(
+    srs: Vec<u8>,
+    num_g1: u32,
+    num_g2: u32,
+    mut secret: Vec<u8>,
+) -> Result<ContributeResult, CeremonyError> {
+    let mut srs = deserialise_srs(&srs, num_g1, num_g2)?;
+
+    let private_key = PrivateKey::from_bytes(&secret);
+    secret.zeroize();
+
+    let update_proof = srs.update(private_key.map_err(|_| CeremonyError::InvalidSecret)?);
+
+    Ok(ContributeResult {
+        srs: srs.serialise_bytes(),
+        update_proof: update_proof.serialise_bytes().to_vec(),
+    })
+}
+
+// Verifies that `after` was obtained by updating `before` using the update proof
+pub fn verify_update(
+    before: Vec<u8>,
+    after: Vec<u8>,
+    num_g1: u32,
+    num_g2: u32,
+    update_proof: Vec<u8>,
+) -> Result<bool, CeremonyError> {
+    let before = deserialise_srs(&before, num_g1, num_g2)?;
+    let after = deserialise_srs(&after, num_g1, num_g2)?;
+
+    let update_proof: [u8; UPDATE_PROOF_SERIALISED_SIZE] = update_proof
+        .try_into()
+        .map_err(|_| CeremonyError::InvalidUpdateProof)?;
+    let update_proof = UpdateProof::deserialise_bytes(&update_proof)
+        .map_err(|_| CeremonyError::InvalidUpdateProof)?;
+
+    let random_element = Fr::rand(&mut rand::thread_rng());
+    Ok(SRS::verify_update(&before, &after, &update_proof, random_element).is_ok())
+}
+
+// Returns the hex encoded pot pubkey for the secret
+pub fn get_pot_pubkey(mut secret: Vec<u8>) -> Result<String, CeremonyError> {
+    let private_key = PrivateKey::from_bytes(&secret);
+    secret.zeroize();
+
+    Ok(private_key
+        .map_err(|_| CeremonyError::InvalidSecret)?
+        .to_pot_pubkey())
+}
+
+uniffi::include_scaffolding!("small_powers_of_tau");
@@ -1,13 +1,22 @@
 // An update proof shows two things:
 // - One knows the discrete log to a secret `p` via KoE
 // - `p` was used to update an existing point A to a new point A'
+//
+// `UpdateProof` commits to `p` in G2 and follows the degree-1 G1 element.
+// `G1UpdateProof` swaps the groups, committing to `p` in G1 and following the
+// degree-1 G2 element, which is cheaper to check using the EIP-2537 precompiles.
 
+use crate::interop_point_encoding::serialize_g2;
 use crate::shared_secret::SharedSecretChain;
+use crate::verification;
 use ark_bls12_381::{G1Projective, G2Projective};
-use crate::interop_point_encoding::serialize_g2;
 use ark_ec::ProjectiveCurve;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy)]
 pub struct UpdateProof {
     // A commitment to the secret scalar `p`
     pub(crate) commitment_to_secret: G2Projective,
@@ -18,6 +27,14 @@ pub struct UpdateProof {
 
 impl UpdateProof {
     // Verifies a list of update of update proofs using `SharedSecretChain` as a subroutine
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "verify_chain",
+            skip_all,
+            fields(num_updates = update_proofs.len())
+        )
+    )]
     pub(crate) fn verify_chain(
         starting_point: G1Projective,
         update_proofs: &[UpdateProof],
@@ -35,10 +52,228 @@ impl UpdateProof {
 
         chain.verify()
     }
+    // Returns the index of the first update which did not change the accumulated
+    // point, which happens when the secret is one
+    pub(crate) fn first_no_op(
+        starting_point: G1Projective,
+        update_proofs: &[UpdateProof],
+    ) -> Option<usize> {
+        let mut previous_point = starting_point;
+
+        for (index, update_proof) in update_proofs.iter().enumerate() {
+            if update_proof.new_accumulated_point == previous_point {
+                return Some(index);
+            }
+            previous_point = update_proof.new_accumulated_point;
+        }
+        None
+    }
     // Returns commitment_to_secret (g2)
     pub fn get_commitment_to_secret(&self) -> String {
         let mut commitment = hex::encode(serialize_g2(&self.commitment_to_secret.into_affine()));
         commitment.insert_str(0, "0x");
         commitment
     }
+
+    // The sha256 hash of `serialise_bytes`, which identifies this update proof
+    pub fn digest(&self) -> [u8; 32] {
+        Sha256::digest(&self.serialise_bytes()).into()
+    }
+    // The first 8 bytes of the digest as hex, for receipts and logs
+    pub fn short_id(&self) -> String {
+        hex::encode(&self.digest()[..8])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct G1UpdateProof {
+    // A commitment to the secret scalar `p` in G1
+    pub(crate) commitment_to_secret: G1Projective,
+    // The degree-1 G2 element of the SRS after it has been updated
+    pub(crate) new_accumulated_point: G2Projective,
+}
+
+impl G1UpdateProof {
+    // Verifies a chain of updates starting from the degree-1 G2 element
+    pub(crate) fn verify_chain(
+        starting_point: G2Projective,
+        update_proofs: &[G1UpdateProof],
+    ) -> bool {
+        let mut accumulated_points = Vec::with_capacity(update_proofs.len() + 1);
+        accumulated_points.push(starting_point);
+        accumulated_points.extend(
+            update_proofs
+                .iter()
+                .map(|proof| proof.new_accumulated_point),
+        );
+        let witnesses: Vec<_> = update_proofs
+            .iter()
+            .map(|proof| proof.commitment_to_secret)
+            .collect();
+
+        verification::verify_shared_secret_chain_g1(&accumulated_points, &witnesses)
+    }
+    pub(crate) fn first_no_op(
+        starting_point: G2Projective,
+        update_proofs: &[G1UpdateProof],
+    ) -> Option<usize> {
+        let mut previous_point = starting_point;
+
+        for (index, update_proof) in update_proofs.iter().enumerate() {
+            if update_proof.new_accumulated_point == previous_point {
+                return Some(index);
+            }
+            previous_point = update_proof.new_accumulated_point;
+        }
+        None
+    }
+
+    // The sha256 hash of `serialise_bytes`
+    pub fn digest(&self) -> [u8; 32] {
+        Sha256::digest(&self.serialise_bytes()).into()
+    }
+}
+
+// Which group the commitment to the secret of an update is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProofMode {
+    G2Commitment,
+    G1Commitment,
+}
+
+// One link of a chain of updates, which records the mode of its proof. Every
+// link of a chain must use the same mode, since a G1 commitment cannot be
+// checked against the degree-1 G1 element that a G2 commitment follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateLink {
+    G2Commitment(UpdateProof),
+    G1Commitment(G1UpdateProof),
+}
+
+impl UpdateLink {
+    pub fn mode(&self) -> ProofMode {
+        match self {
+            UpdateLink::G2Commitment(_) => ProofMode::G2Commitment,
+            UpdateLink::G1Commitment(_) => ProofMode::G1Commitment,
+        }
+    }
+
+    // The sha256 hash of `serialise_bytes`, which includes the mode, so that
+    // transcripts committing to the digest also commit to the mode
+    pub fn digest(&self) -> [u8; 32] {
+        Sha256::digest(&self.serialise_bytes()).into()
+    }
+}
+
+impl From<UpdateProof> for UpdateLink {
+    fn from(update_proof: UpdateProof) -> Self {
+        UpdateLink::G2Commitment(update_proof)
+    }
+}
+
+impl From<G1UpdateProof> for UpdateLink {
+    fn from(update_proof: G1UpdateProof) -> Self {
+        UpdateLink::G1Commitment(update_proof)
+    }
+}
+
+// The projective coordinates of a point are not unique, so equality, hashing
+// and ordering use the compressed encoding of the points instead
+impl PartialEq for UpdateProof {
+    fn eq(&self, other: &Self) -> bool {
+        self.serialise_bytes() == other.serialise_bytes()
+    }
+}
+
+impl Eq for UpdateProof {}
+
+impl Hash for UpdateProof {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.serialise_bytes().hash(state);
+    }
+}
+
+impl Ord for UpdateProof {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.serialise_bytes().cmp(&other.serialise_bytes())
+    }
+}
+
+impl PartialOrd for UpdateProof {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::VerificationError,
+        keypair::PrivateKey,
+        srs::{Parameters, SRS},
+    };
+    use ark_bls12_381::Fr;
+    use std::collections::HashSet;
+
+    #[test]
+    fn identical_proofs_have_the_same_identity() {
+        let mut srs = SRS::new(Parameters::new(10, 2)).unwrap();
+        let first = srs.update(PrivateKey::from_u64(252));
+        let second = srs.update(PrivateKey::from_u64(253));
+
+        // The same point with different projective coordinates
+        let point = first.new_accumulated_point;
+        let mut rescaled = first;
+        rescaled.new_accumulated_point = point.double() - point;
+        assert_eq!(rescaled, first);
+        assert_eq!(rescaled.digest(), first.digest());
+        assert_ne!(first.digest(), second.digest());
+        assert_eq!(first.short_id().len(), 16);
+
+        let unique: HashSet<_> = vec![first, second, rescaled].into_iter().collect();
+        assert_eq!(unique.len(), 2);
+
+        let mut sorted = vec![second, first, rescaled];
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 2);
+        assert!(sorted[0] < sorted[1]);
+    }
+
+    #[test]
+    fn g1_commitment_links() {
+        let before = SRS::new(Parameters::new(10, 4)).unwrap();
+        let mut after = before.clone();
+        let links: Vec<UpdateLink> = vec![
+            after
+                .update_with_g1_commitment(PrivateKey::from_u64(252))
+                .into(),
+            after
+                .update_with_g1_commitment(PrivateKey::from_u64(253))
+                .into(),
+        ];
+        let random_element = Fr::from(100u64);
+        assert!(SRS::verify_links(&before, &after, &links, random_element).is_ok());
+
+        // The mode is part of the encoding, and so of the digest
+        let bytes = links[0].serialise_bytes();
+        assert_eq!(bytes[0], 1);
+        assert_eq!(UpdateLink::deserialise_bytes(&bytes).unwrap(), links[0]);
+        assert_eq!(links[0].mode(), ProofMode::G1Commitment);
+
+        // Swapping the order of the proofs breaks the chain
+        let swapped = [links[1], links[0]];
+        assert_eq!(
+            SRS::verify_links(&before, &after, &swapped, random_element),
+            Err(VerificationError::UpdateDoesNotEndAtSRS)
+        );
+
+        let mut mixed = links;
+        mixed.push(after.update(PrivateKey::from_u64(254)).into());
+        assert_eq!(
+            SRS::verify_links(&before, &after, &mixed, random_element),
+            Err(VerificationError::MixedProofModes)
+        );
+    }
 }
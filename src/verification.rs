@@ -0,0 +1,243 @@
+// The pairing based verification checks, which only depend on `core` and
+// `alloc`. This allows them to be used in constrained environments, such as
+// embedded verifiers and zkVM guests, by disabling the default features.
+use crate::metrics;
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, Zero};
+use ark_std::vec::Vec;
+
+// The inputs to the Miller loop. Preparing a G2 point computes the line
+// functions of the Miller loop, which is most of the cost of a pairing, so
+// points which are paired against repeatedly are only prepared once
+type G1Prepared = <Bls12_381 as PairingEngine>::G1Prepared;
+type G2Prepared = <Bls12_381 as PairingEngine>::G2Prepared;
+
+// The prepared degree-0 and degree-1 G2 elements of an SRS, which every range
+// of G1 powers is checked against, see `g1_powers_check_prepared`
+#[derive(Debug, Clone)]
+pub struct PreparedG2Tau {
+    tau_0: G2Prepared,
+    tau_1: G2Prepared,
+}
+
+impl PreparedG2Tau {
+    pub fn new(g2_tau: [G2Affine; 2]) -> Self {
+        PreparedG2Tau {
+            tau_0: g2_tau[0].into(),
+            tau_1: g2_tau[1].into(),
+        }
+    }
+}
+
+// Checks that the product of the pairings is one, which needs a single final
+// exponentiation rather than one for each side of an equality
+pub(crate) fn pairing_product_is_one(pairs: [&(G1Prepared, G2Prepared); 2]) -> bool {
+    metrics::record_pairings(2);
+    let product = Bls12_381::miller_loop(pairs);
+    matches!(Bls12_381::final_exponentiation(&product), Some(product) if product.is_one())
+}
+
+// Verifies a chain of accumulated points, where each point was obtained by
+// multiplying the previous point by the discrete log of the corresponding witness.
+//
+// `accumulated_points` includes the starting point, so there should be one
+// more accumulated point than there are witnesses
+pub fn verify_shared_secret_chain(
+    accumulated_points: &[G1Projective],
+    witnesses: &[G2Projective],
+) -> bool {
+    // Each link checks e(next_acc, g2) * e(-prev_acc, witness) = 1. The G2
+    // generator is prepared once for the whole chain, and only the G1 side
+    // of its pair is replaced for each link
+    let mut generator_pair = (
+        G1Prepared::from(G1Affine::prime_subgroup_generator()),
+        G2Prepared::from(G2Affine::prime_subgroup_generator()),
+    );
+
+    // Overlapping window of two; see example: https://gist.github.com/rust-play/d83ae8ffdbf24f17612e05dc75c2ee06
+    for (acc_pair, witness) in accumulated_points.windows(2).zip(witnesses) {
+        let prev_acc = acc_pair[0];
+        let next_acc = acc_pair[1];
+        generator_pair.0 = next_acc.into_affine().into();
+        let witness_pair = (
+            (-prev_acc).into_affine().into(),
+            witness.into_affine().into(),
+        );
+        if !pairing_product_is_one([&generator_pair, &witness_pair]) {
+            return false;
+        }
+    }
+    true
+}
+
+// Verifies a chain in the same way as `verify_shared_secret_chain`, with the
+// groups swapped, so that the witnesses are in G1 and the accumulated points
+// are in G2
+pub fn verify_shared_secret_chain_g1(
+    accumulated_points: &[G2Projective],
+    witnesses: &[G1Projective],
+) -> bool {
+    let (starting_point, accumulated_points) = match accumulated_points.split_first() {
+        Some(points) => points,
+        None => return true,
+    };
+    let gen_g1 = G1Prepared::from(G1Affine::prime_subgroup_generator());
+
+    // Each link checks e(g1, next_acc) * e(-witness, prev_acc) = 1. Every
+    // accumulated point is the next point of one link and the previous point
+    // of the link after it, so it is only prepared once
+    let mut prev_acc = G2Prepared::from(starting_point.into_affine());
+    for (next_acc, witness) in accumulated_points.iter().zip(witnesses) {
+        let next_pair = (gen_g1.clone(), next_acc.into_affine().into());
+        let witness_pair = ((-*witness).into_affine().into(), prev_acc);
+        if !pairing_product_is_one([&next_pair, &witness_pair]) {
+            return false;
+        }
+        prev_acc = next_pair.1;
+    }
+    true
+}
+
+// We detail the algorithm here: https://hackmd.io/C0lk1xyWQryGggRlNYDqZw#Appendix-1---Incremental-powers-of-tau-check-Batching
+// This allows us to check that the SRS has the correct structure using only 1 pairing
+// for each group.
+//
+// Both `tau_g1` and `tau_g2` must contain at least two elements
+pub fn structure_check(tau_g1: &[G1Affine], tau_g2: &[G2Affine], random_element: Fr) -> bool {
+    powers_check(
+        tau_g1,
+        tau_g2,
+        [tau_g1[0], tau_g1[1]],
+        [tau_g2[0], tau_g2[1]],
+        random_element,
+    )
+}
+
+// Checks that each element of `g1` and `g2` is the previous element multiplied by tau,
+// where `g1_tau` and `g2_tau` are the degree-0 and degree-1 elements of the SRS.
+//
+// Unlike `structure_check`, the points do not need to start at the degree-0 element,
+// so that a range of the SRS can be checked on its own
+pub fn powers_check(
+    g1: &[G1Affine],
+    g2: &[G2Affine],
+    g1_tau: [G1Affine; 2],
+    g2_tau: [G2Affine; 2],
+    random_element: Fr,
+) -> bool {
+    g1_powers_check(g1, g2_tau, random_element) && g2_powers_check(g2, g1_tau, random_element)
+}
+
+// Checks that each element of `g1` is the previous element multiplied by tau,
+// where `g2_tau` are the degree-0 and degree-1 elements of the SRS in G2.
+//
+// Only the first two G2 elements are needed, so the G1 elements can be
+// checked without loading the rest of the G2 elements
+pub fn g1_powers_check(g1: &[G1Affine], g2_tau: [G2Affine; 2], random_element: Fr) -> bool {
+    g1_powers_check_prepared(g1, &PreparedG2Tau::new(g2_tau), random_element)
+}
+
+// Checks the G1 elements in the same way as `g1_powers_check`, for when many
+// ranges of the same SRS are checked
+pub fn g1_powers_check_prepared(
+    g1: &[G1Affine],
+    g2_tau: &PreparedG2Tau,
+    random_element: Fr,
+) -> bool {
+    // Check will always pass if the random element is zero
+    // We return false in this case
+    if random_element.is_zero() {
+        return false;
+    }
+    // There is nothing to check if there is at most one element
+    if g1.len() < 2 {
+        return true;
+    }
+
+    #[allow(non_snake_case)]
+    let (L_comm, R_comm) = combine_consecutive(g1, random_element);
+    metrics::record_points_validated(g1.len());
+
+    // e(L, tau_1) * e(-R, tau_0) = 1
+    pairing_product_is_one([
+        &(L_comm.into_affine().into(), g2_tau.tau_1.clone()),
+        &((-R_comm).into_affine().into(), g2_tau.tau_0.clone()),
+    ])
+}
+
+// Checks that each element of `g2` is the previous element multiplied by tau,
+// where `g1_tau` are the degree-0 and degree-1 elements of the SRS in G1
+pub fn g2_powers_check(g2: &[G2Affine], g1_tau: [G1Affine; 2], random_element: Fr) -> bool {
+    // Check will always pass if the random element is zero
+    // We return false in this case
+    if random_element.is_zero() {
+        return false;
+    }
+    // There is nothing to check if there is at most one element
+    if g2.len() < 2 {
+        return true;
+    }
+    let [tau_g1_0, tau_g1_1] = g1_tau;
+
+    #[allow(non_snake_case)]
+    let (L_comm, R_comm) = combine_consecutive(g2, random_element);
+    metrics::record_points_validated(g2.len());
+
+    // e(tau_1, L) * e(-tau_0, R) = 1
+    pairing_product_is_one([
+        &(tau_g1_1.into(), L_comm.into_affine().into()),
+        &((-tau_g1_0).into(), R_comm.into_affine().into()),
+    ])
+}
+
+// Checks that `g1[1]` is `g1[0]` multiplied by the same scalar as `g2[1]` is `g2[0]`.
+//
+// When these are the degree-0 and degree-1 powers of two SRS, this checks
+// that they were created using the same tau
+pub fn same_ratio(g1: [G1Affine; 2], g2: [G2Affine; 2]) -> bool {
+    metrics::record_pairings(2);
+    Bls12_381::pairing(g1[1], g2[0]) == Bls12_381::pairing(g1[0], g2[1])
+}
+
+// Combines the pairs of consecutive elements (p_i, p_{i+1}) using the powers
+// of the random element r, returning
+//
+// L = sum_i r^(i+1) * p_i and R = sum_i r^(i+1) * p_(i+1), for i from 0 to n - 2.
+//
+// Both are computed from the single combination C = sum_i r^i * p_i, since
+// L = r * (C - r^(n-1) * p_(n-1)) and R = C - p_0. This halves the number of
+// scalar multiplications, which matters most when there are as many G2
+// elements as G1 elements.
+//
+// `points` must contain at least two elements
+fn combine_consecutive<A: AffineCurve<ScalarField = Fr>>(
+    points: &[A],
+    random_element: Fr,
+) -> (A::Projective, A::Projective) {
+    let n = points.len();
+
+    let mut powers = Vec::with_capacity(n);
+    powers.push(Fr::one());
+    powers.extend(vandemonde_challenge(random_element, n - 1));
+    let scalars: Vec<_> = powers.iter().map(|power| power.into_repr()).collect();
+
+    let combination: A::Projective = VariableBaseMSM::multi_scalar_mul(points, &scalars);
+
+    let mut left = combination - points[n - 1].mul(powers[n - 1].into_repr());
+    left = left.mul(random_element.into_repr());
+    let mut right = combination;
+    right.add_assign_mixed(&-points[0]);
+
+    (left, right)
+}
+
+// Returns [x, x^2, ..., x^n]
+pub(crate) fn vandemonde_challenge(x: Fr, n: usize) -> Vec<Fr> {
+    let mut challenges: Vec<Fr> = Vec::with_capacity(n);
+    challenges.push(x);
+    for i in 0..n - 1 {
+        challenges.push(challenges[i] * x);
+    }
+    challenges
+}
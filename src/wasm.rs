@@ -0,0 +1,737 @@
+// Bindings for browser-based clients of the Ethereum KZG ceremony.
+//
+// The functions exposed here accept and return the same json encoding
+// that the sequencer uses, so that the javascript side never has to
+// deal with curve points directly.
+use ark_ec::AffineCurve;
+use js_sys::Uint8Array;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::{
+    error::{ParameterError, SerialisationError},
+    identity::Identity,
+    interop_point_encoding::{G1_SERIALISED_SIZE, G2_SERIALISED_SIZE},
+    keypair::PrivateKey,
+    memory::{estimate_update, section_size_for_budget},
+    progress::{Phase, ProgressSink},
+    sdk::{
+        contribution::{Contribution, ContributionJSON},
+        precheck::precheck_contribution,
+        CEREMONIES, NUM_CEREMONIES,
+    },
+    section::{G1Section, G2Section},
+    serialisation::{
+        bytes_to_hex_string, check_bounds, g1s_from_bytes, g1s_to_bytes, g2s_from_bytes,
+        g2s_to_bytes, SRSJson, SubgroupCheck,
+    },
+    srs::{Parameters, SRS},
+    update_proof::UpdateProof,
+};
+
+// The thread pool is only available in multi-threaded builds, which
+// require the page to be cross-origin isolated
+#[cfg(feature = "wasm-threads")]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+// The number of points which are updated in between yielding to the
+// event loop, when contributing asynchronously
+const ASYNC_CHUNK_SIZE: usize = 1024;
+
+// The number of points which are copied across the wasm boundary at a time
+// when reading and writing byte buffers
+const BOUNDARY_CHUNK_SIZE: usize = 4096;
+
+// The memory used by the buffers which copy points across the wasm boundary,
+// when reading and writing G2 points
+const BOUNDARY_BUFFER_SIZE: usize = 2 * BOUNDARY_CHUNK_SIZE * G2_SERIALISED_SIZE;
+
+// The memory budget for `contribute_srs` in bytes, or zero if there is no budget
+static MEMORY_BUDGET: AtomicUsize = AtomicUsize::new(0);
+
+// These are bound to the globals rather than to `window`, so that they
+// are available in browsers, web workers and node.js
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout: i32) -> JsValue;
+
+    #[cfg(not(feature = "wasm-small"))]
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn console_log(message: &str);
+}
+
+// TypeScript definitions for the json and javascript values which cross
+// the wasm boundary. These need to be kept in sync with `ContributeResponse`,
+// `ContributionJSON`, `SRSJson` and `Progress`.
+#[wasm_bindgen(typescript_custom_section)]
+const TS_DEFINITIONS: &str = r#"
+export type ContributionPhase = "deserialise" | "validate" | "update" | "serialise";
+export type ProgressCallback = (phase: ContributionPhase, percentage: number) => void;
+
+export type UpdateProof = [commitmentToSecret: string, newAccumulatedPoint: string];
+
+export interface PowersOfTau {
+    G1Powers: string[];
+    G2Powers: string[];
+}
+export interface SRSJson {
+    numG1Powers: number;
+    numG2Powers: number;
+    powersOfTau: PowersOfTau;
+    potPubkey: string;
+    blsSignature?: string;
+}
+export interface BatchContribution {
+    contributions: [SRSJson, SRSJson, SRSJson, SRSJson];
+    ecdsaSignature?: string;
+}
+
+// The json returned by `contribute`, `contribute_json` and `contribute_async`
+export interface ContributeResponse {
+    contribution: BatchContribution;
+    updateProofs: [UpdateProof, UpdateProof, UpdateProof, UpdateProof];
+}
+
+// Errors are thrown as strings describing the failure
+export type ContributionError = string;
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "ProgressCallback")]
+    pub type ProgressCallback;
+
+    #[wasm_bindgen(typescript_type = "UpdateProof")]
+    pub type JsUpdateProof;
+}
+
+// Routes the records from the `log` facade to the javascript console
+#[cfg(not(feature = "wasm-small"))]
+struct ConsoleLogger;
+
+#[cfg(not(feature = "wasm-small"))]
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            console_log(&format!(
+                "{} {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            ));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(not(feature = "wasm-small"))]
+static CONSOLE_LOGGER: ConsoleLogger = ConsoleLogger;
+
+// Logs the crate's records to the console, up to the given level; one of
+// "off", "error", "warn", "info", "debug" or "trace". Nothing is logged
+// until this has been called.
+//
+// The logs are compiled out of `wasm-small` builds, so this only checks the level
+#[wasm_bindgen]
+pub fn set_log_level(level: &str) -> Result<(), JsValue> {
+    let level: log::LevelFilter = level.parse().map_err(to_js_error)?;
+
+    // The logger can only be set once, so later calls only change the level.
+    // If another logger has already been installed, it will be used instead
+    #[cfg(not(feature = "wasm-small"))]
+    let _ = log::set_logger(&CONSOLE_LOGGER);
+    log::set_max_level(level);
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContributeResponse {
+    pub contribution: ContributionJSON,
+    // The update proofs for each of the sub-ceremonies, in the same
+    // order as the SRS in the contribution
+    #[serde(rename = "updateProofs")]
+    pub update_proofs: [[String; 2]; NUM_CEREMONIES],
+}
+
+fn to_js_error<E: ToString>(err: E) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+// Reports the progress of a contribution back to javascript.
+//
+// The callback is invoked as `callback(phase, percentage)` after each
+// sub-ceremony has finished a phase.
+struct Progress {
+    callback: Option<js_sys::Function>,
+    steps_done: usize,
+    total_steps: usize,
+}
+
+impl Progress {
+    fn new(callback: Option<ProgressCallback>, total_steps: usize) -> Self {
+        Progress {
+            callback: callback.map(|callback| callback.unchecked_into()),
+            steps_done: 0,
+            total_steps,
+        }
+    }
+
+    fn step(&mut self, phase: Phase) {
+        self.steps_done += 1;
+
+        let callback = match &self.callback {
+            Some(callback) => callback,
+            None => return,
+        };
+        let percentage = (self.steps_done * 100 / self.total_steps) as u32;
+
+        // An exception thrown by the callback should not abort the contribution
+        let _ = callback.call2(
+            &JsValue::NULL,
+            &JsValue::from_str(phase.as_str()),
+            &JsValue::from(percentage),
+        );
+    }
+}
+
+// Each sub-ceremony counts as one step of its phase, so only the end of an
+// update is reported to javascript
+impl ProgressSink for Progress {
+    fn progress(&mut self, phase: Phase, items_done: usize, items_total: usize) {
+        if items_done == items_total {
+            self.step(phase);
+        }
+    }
+}
+
+fn deserialise_contribution(
+    contribution_json: &str,
+    progress: &mut Progress,
+) -> Result<Contribution, JsValue> {
+    let contribution_json: ContributionJSON =
+        serde_json::from_str(contribution_json).map_err(to_js_error)?;
+
+    log::info!("deserialising contribution");
+    let mut contributions = Vec::with_capacity(NUM_CEREMONIES);
+    for srs_json in &contribution_json.contributions {
+        contributions.push(
+            SRS::deserialise(
+                (
+                    &srs_json.powers_of_tau.g1_powers,
+                    &srs_json.powers_of_tau.g2_powers,
+                ),
+                srs_json.parameters(),
+                SubgroupCheck::None,
+            )
+            .map_err(to_js_error)?,
+        );
+        progress.step(Phase::Deserialise);
+    }
+    let contribution = Contribution {
+        contributions: contributions.try_into().unwrap(),
+    };
+    contribution.check_parameters().map_err(to_js_error)?;
+
+    // The points are subgroup checked as a phase of their own, since this
+    // takes about as long as the update
+    log::info!("validating contribution");
+    for srs in &contribution.contributions {
+        srs.subgroup_check().map_err(to_js_error)?;
+        progress.step(Phase::Validate);
+    }
+
+    Ok(contribution)
+}
+
+// Builds the response for the updated contribution. The pot pubkey of each
+// SRS is set to the commitment to the secret that was used to update it
+fn build_response(
+    contribution: &Contribution,
+    update_proofs: Vec<UpdateProof>,
+    progress: &mut Progress,
+) -> ContributeResponse {
+    log::info!("serialising contribution");
+    let mut contributions_json = Vec::with_capacity(NUM_CEREMONIES);
+    for (srs, update_proof) in contribution.contributions.iter().zip(&update_proofs) {
+        let mut srs_json = SRSJson::from(srs);
+        srs_json.pot_pubkey = update_proof.get_commitment_to_secret();
+        contributions_json.push(srs_json);
+        progress.step(Phase::Serialise);
+    }
+
+    let update_proofs: Vec<_> = update_proofs
+        .iter()
+        .map(|update_proof| update_proof.serialise())
+        .collect();
+
+    ContributeResponse {
+        contribution: ContributionJSON {
+            contributions: contributions_json.try_into().unwrap(),
+            ecdsa_signature: None,
+        },
+        update_proofs: update_proofs.try_into().unwrap(),
+    }
+}
+
+fn serialise_response(response: &ContributeResponse) -> Result<String, JsValue> {
+    serde_json::to_string(response).map_err(to_js_error)
+}
+
+// Updates all four SRS in the batch contribution, each with its own
+// freshly generated secret, and returns the updated contribution along
+// with the four update proofs.
+//
+// `progress` is an optional javascript function which will be called with
+// the current phase and the percentage of the work completed
+#[wasm_bindgen]
+pub fn contribute(
+    contribution_json: &str,
+    progress: Option<ProgressCallback>,
+) -> Result<String, JsValue> {
+    // There are four phases for each sub-ceremony; deserialisation, validation,
+    // update and serialisation
+    let mut progress = Progress::new(progress, 4 * NUM_CEREMONIES);
+
+    let mut contribution = deserialise_contribution(contribution_json, &mut progress)?;
+
+    log::info!("updating contribution");
+    let mut update_proofs = Vec::with_capacity(NUM_CEREMONIES);
+    for srs in contribution.contributions.iter_mut() {
+        update_proofs.push(srs.update_with_progress(fresh_private_key()?, &mut progress));
+    }
+
+    let response = build_response(&contribution, update_proofs, &mut progress);
+    serialise_response(&response)
+}
+
+// Performs a contribution on the sequencer's `BatchContribution` json, using
+// a fresh secret for each SRS. The returned contribution has the pot pubkey of
+// each SRS set and, if an identity is given, the identity signed with each secret.
+#[wasm_bindgen]
+pub fn contribute_json(
+    batch_contribution_json: &str,
+    identity: Option<String>,
+    progress: Option<ProgressCallback>,
+) -> Result<String, JsValue> {
+    let identity = identity
+        .map(|identity| Identity::parse(&identity))
+        .transpose()
+        .map_err(to_js_error)?;
+    let mut progress = Progress::new(progress, 4 * NUM_CEREMONIES);
+
+    let mut contribution = deserialise_contribution(batch_contribution_json, &mut progress)?;
+
+    log::info!("updating contribution");
+    let mut update_proofs = Vec::with_capacity(NUM_CEREMONIES);
+    let mut bls_signatures = Vec::with_capacity(NUM_CEREMONIES);
+    for srs in contribution.contributions.iter_mut() {
+        let priv_key = fresh_private_key()?;
+        bls_signatures.push(
+            identity
+                .as_ref()
+                .map(|identity| priv_key.sign_hex(identity.encode().as_bytes())),
+        );
+        update_proofs.push(srs.update_with_progress(priv_key, &mut progress));
+    }
+
+    let mut response = build_response(&contribution, update_proofs, &mut progress);
+    for (srs_json, bls_signature) in response
+        .contribution
+        .contributions
+        .iter_mut()
+        .zip(bls_signatures)
+    {
+        srs_json.bls_signature = bls_signature;
+    }
+
+    serialise_response(&response)
+}
+
+thread_local! {
+    // The hash of the entropy passed to `add_entropy`. This is only read on
+    // the thread that the contribution functions are called from
+    static USER_ENTROPY: RefCell<Zeroizing<[u8; 32]>> = RefCell::new(Zeroizing::new([0u8; 32]));
+}
+
+// Adds entropy from the participant, for example the timings of their mouse
+// movements and key presses, which is mixed into every secret generated after
+// this call. Calling it again adds to the entropy rather than replacing it.
+//
+// The entropy belongs to this instance of the module, so a Web Worker running
+// its own instance needs the entropy passed to it, see `contribute_in_worker.js`
+#[wasm_bindgen]
+pub fn add_entropy(entropy: &[u8]) {
+    USER_ENTROPY.with(|user_entropy| {
+        let mut user_entropy = user_entropy.borrow_mut();
+
+        let mut hasher = Sha256::new();
+        hasher.update(&**user_entropy);
+        hasher.update((entropy.len() as u64).to_le_bytes());
+        hasher.update(entropy);
+        user_entropy.copy_from_slice(&hasher.finalize());
+    });
+}
+
+// Generates the secret for a contribution, see `PrivateKey::try_from_mixed_entropy`.
+//
+// The randomness is drawn from `crypto.getRandomValues` through getrandom's js
+// support, and mixed with the entropy from `add_entropy`. If the environment does
+// not provide `crypto.getRandomValues`, this fails rather than falling back to
+// a weaker source
+fn fresh_private_key() -> Result<PrivateKey, JsValue> {
+    USER_ENTROPY.with(|user_entropy| {
+        let user_entropy = user_entropy.borrow();
+        PrivateKey::try_from_mixed_entropy(&[&**user_entropy], OsRng).map_err(to_js_error)
+    })
+}
+
+// Checks the sequencer's `BatchContribution` json in a few seconds, before the
+// participant commits to contributing, see `precheck_contribution`. Throws an
+// error naming the SRS and the power which failed, if any.
+//
+// Passing this check does not mean that the contribution is valid
+#[wasm_bindgen]
+pub fn precheck(batch_contribution_json: &str) -> Result<(), JsValue> {
+    let contribution_json: ContributionJSON =
+        serde_json::from_str(batch_contribution_json).map_err(to_js_error)?;
+    precheck_contribution(&contribution_json, &mut OsRng).map_err(to_js_error)
+}
+
+// Returns the 0x prefixed hex digest of an updated batch contribution, see
+// `Contribution::digest`, for the participant to sign. `bytes` are the SRS of
+// each ceremony in order, each encoded as the compressed G1 points followed by
+// the compressed G2 points, as written by `contribute_srs`.
+//
+// The points are decoded and re-encoded, so the digest only depends on the
+// points. They are not subgroup checked, since the participant computed them
+#[wasm_bindgen]
+pub fn digest_contribution(bytes: &[u8]) -> Result<String, JsValue> {
+    let contribution =
+        Contribution::deserialise_bytes(bytes, SubgroupCheck::None).map_err(to_js_error)?;
+    Ok(bytes_to_hex_string(&contribution.digest()))
+}
+
+// Returns the same digest as `digest_contribution`, for a contribution in the
+// `BatchContribution` json format, such as the one returned by `contribute_json`
+#[wasm_bindgen]
+pub fn digest_contribution_json(batch_contribution_json: &str) -> Result<String, JsValue> {
+    let contribution_json: ContributionJSON =
+        serde_json::from_str(batch_contribution_json).map_err(to_js_error)?;
+    let contribution = Contribution::from_json_with(&contribution_json, SubgroupCheck::None)
+        .map_err(to_js_error)?;
+    contribution.check_parameters().map_err(to_js_error)?;
+    Ok(bytes_to_hex_string(&contribution.digest()))
+}
+
+// Returns the pot pubkey for a 0x prefixed hex encoded secret, without
+// updating any SRS. This allows the frontend to show the participant their
+// pubkey, before the contribution has been computed.
+#[wasm_bindgen]
+pub fn get_pot_pubkey(secret_hex: &str) -> Result<String, JsValue> {
+    let private_key = PrivateKey::from_hex(secret_hex).map_err(to_js_error)?;
+    Ok(private_key.to_pot_pubkey())
+}
+
+// Signs the participant's identity, for example `eth|0x...` or `git|1234|handle`,
+// using the 0x prefixed hex encoded secret. The canonical encoding of the identity
+// is signed, so addresses and handles may be given in any case. Returns the hex
+// encoded BLS signature.
+#[wasm_bindgen]
+pub fn sign_identity(secret_hex: &str, identity: &str) -> Result<String, JsValue> {
+    let private_key = PrivateKey::from_hex(secret_hex).map_err(to_js_error)?;
+    let identity = Identity::parse(identity).map_err(to_js_error)?;
+    Ok(private_key.sign_hex(identity.encode().as_bytes()))
+}
+
+// Returns control to the javascript event loop, so that the page
+// can be re-rendered and handle user input
+async fn yield_to_event_loop() -> Result<(), JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, 0);
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await?;
+    Ok(())
+}
+
+// The number of chunks needed to update an SRS with the given parameters,
+// when contributing asynchronously
+fn num_async_chunks(parameters: &Parameters) -> usize {
+    let num_points =
+        (parameters.num_g1_elements_needed - 1) + (parameters.num_g2_elements_needed - 1);
+    (num_points + ASYNC_CHUNK_SIZE - 1) / ASYNC_CHUNK_SIZE
+}
+
+// The same as `contribute`, however the SRS are updated in chunks and
+// control is given back to the event loop in between each chunk, so that
+// the page stays responsive on slower devices.
+#[wasm_bindgen]
+pub async fn contribute_async(
+    contribution_json: String,
+    progress: Option<ProgressCallback>,
+) -> Result<JsValue, JsValue> {
+    let num_update_chunks: usize = CEREMONIES.iter().map(num_async_chunks).sum();
+    let mut progress = Progress::new(progress, 3 * NUM_CEREMONIES + num_update_chunks);
+
+    let mut contribution = deserialise_contribution(&contribution_json, &mut progress)?;
+    drop(contribution_json);
+
+    log::info!("updating contribution");
+    let mut update_proofs = Vec::with_capacity(NUM_CEREMONIES);
+    for srs in contribution.contributions.iter_mut() {
+        let priv_key = fresh_private_key()?;
+        let mut update = srs.chunked_update(priv_key, ASYNC_CHUNK_SIZE);
+        while update.step() {
+            progress.step(Phase::Update);
+            yield_to_event_loop().await?;
+        }
+        progress.step(Phase::Update);
+        update_proofs.push(update.finish());
+    }
+
+    let response = build_response(&contribution, update_proofs, &mut progress);
+    Ok(JsValue::from_str(&serialise_response(&response)?))
+}
+
+// Reads `num_points` points from the javascript buffer, starting at `offset`.
+//
+// The points are copied into wasm memory a chunk at a time, so that the
+// whole buffer never needs to reside in wasm memory.
+fn read_points<T>(
+    input: &Uint8Array,
+    offset: usize,
+    num_points: usize,
+    point_size: usize,
+    decode: fn(&[u8]) -> Result<Vec<T>, SerialisationError>,
+) -> Result<Vec<T>, SerialisationError> {
+    let mut points = Vec::with_capacity(num_points);
+    let mut buffer = vec![0u8; BOUNDARY_CHUNK_SIZE * point_size];
+
+    let end = offset + num_points * point_size;
+    let mut chunk_start = offset;
+    while chunk_start < end {
+        let chunk_end = std::cmp::min(chunk_start + buffer.len(), end);
+        let chunk = &mut buffer[..chunk_end - chunk_start];
+
+        input
+            .subarray(chunk_start as u32, chunk_end as u32)
+            .copy_to(chunk);
+        points.extend(decode(chunk)?);
+
+        chunk_start = chunk_end;
+    }
+
+    Ok(points)
+}
+
+// Writes the points into the javascript buffer, starting at `offset`.
+// Returns the offset after the last point written.
+fn write_points<T>(
+    output: &Uint8Array,
+    offset: usize,
+    points: &[T],
+    encode: fn(&[T]) -> Vec<u8>,
+) -> usize {
+    let mut chunk_start = offset;
+    for chunk in points.chunks(BOUNDARY_CHUNK_SIZE) {
+        let bytes = encode(chunk);
+        let chunk_end = chunk_start + bytes.len();
+
+        output
+            .subarray(chunk_start as u32, chunk_end as u32)
+            .copy_from(&bytes);
+
+        chunk_start = chunk_end;
+    }
+    chunk_start
+}
+
+// Updates a single SRS which is encoded as the compressed G1 points followed
+// by the compressed G2 points, see `SRS::serialise_bytes`.
+//
+// The points are read from `input` and the updated points are written into
+// `output`, which must have the same length as `input`. The buffers are
+// copied across the wasm boundary in chunks, so unlike passing a `Vec<u8>`,
+// neither of them is ever copied into wasm memory in full.
+//
+// As with `contribute`, every point is checked to be in the prime order
+// subgroup before it is updated.
+//
+// Returns the update proof
+#[wasm_bindgen]
+pub fn contribute_srs(
+    input: &Uint8Array,
+    num_g1: usize,
+    num_g2: usize,
+    output: &Uint8Array,
+) -> Result<JsUpdateProof, JsValue> {
+    let parameters = Parameters::new(num_g1, num_g2);
+    let (g1_len, g2_len) = check_bounds(parameters).map_err(to_js_error)?;
+
+    if input.length() as usize != g1_len + g2_len {
+        return Err(to_js_error("input does not match the parameters"));
+    }
+    if output.length() != input.length() {
+        return Err(to_js_error("output must be the same length as the input"));
+    }
+
+    // `wasm-small` builds always update the SRS in sections, so that the whole
+    // SRS is never held in wasm memory
+    let small = cfg!(feature = "wasm-small");
+    let section_size = match MEMORY_BUDGET.load(Ordering::Relaxed) {
+        0 if small => Some(BOUNDARY_CHUNK_SIZE),
+        0 => None,
+        budget if !small && estimate_memory(num_g1, num_g2) <= budget => None,
+        budget => Some(
+            budget
+                .checked_sub(BOUNDARY_BUFFER_SIZE)
+                .and_then(|budget| section_size_for_budget(parameters, budget))
+                .ok_or_else(|| to_js_error("the memory budget is too small to update the SRS"))?,
+        ),
+    };
+    let update_proof = match section_size {
+        Some(section_size) => {
+            contribute_srs_in_sections(input, num_g1, num_g2, output, section_size)?
+        }
+        None => contribute_srs_at_once(input, num_g1, num_g2, output)?,
+    };
+
+    let [commitment_to_secret, new_accumulated_point] = update_proof.serialise();
+    Ok(js_sys::Array::of2(
+        &JsValue::from_str(&commitment_to_secret),
+        &JsValue::from_str(&new_accumulated_point),
+    )
+    .unchecked_into())
+}
+
+fn contribute_srs_at_once(
+    input: &Uint8Array,
+    num_g1: usize,
+    num_g2: usize,
+    output: &Uint8Array,
+) -> Result<UpdateProof, JsValue> {
+    let g1_len = num_g1 * G1_SERIALISED_SIZE;
+
+    log::info!("deserialising SRS");
+    let g1s =
+        read_points(input, 0, num_g1, G1_SERIALISED_SIZE, g1s_from_bytes).map_err(to_js_error)?;
+    let g2s = read_points(input, g1_len, num_g2, G2_SERIALISED_SIZE, g2s_from_bytes)
+        .map_err(to_js_error)?;
+    let mut srs = SRS::from_vectors(g1s, g2s).map_err(to_js_error)?;
+
+    log::info!("validating SRS");
+    srs.subgroup_check().map_err(to_js_error)?;
+
+    log::info!("updating SRS");
+    let update_proof = srs.update(fresh_private_key()?);
+
+    log::info!("serialising SRS");
+    let offset = write_points(output, 0, srs.g1_elements(), g1s_to_bytes);
+    write_points(output, offset, srs.g2_elements(), g2s_to_bytes);
+
+    Ok(update_proof)
+}
+
+// Updates the SRS `section_size` points at a time, so that only one section
+// is held in wasm memory at once
+fn contribute_srs_in_sections(
+    input: &Uint8Array,
+    num_g1: usize,
+    num_g2: usize,
+    output: &Uint8Array,
+    section_size: usize,
+) -> Result<UpdateProof, JsValue> {
+    if num_g1 < 2 || num_g2 < 2 {
+        return Err(to_js_error(ParameterError::TooFewElements));
+    }
+    let g1_len = num_g1 * G1_SERIALISED_SIZE;
+
+    log::info!("updating SRS in sections of {} points", section_size);
+    let private_key = fresh_private_key()?;
+    let mut new_accumulated_point = None;
+
+    for start in (0..num_g1).step_by(section_size) {
+        let num_points = std::cmp::min(section_size, num_g1 - start);
+        let offset = start * G1_SERIALISED_SIZE;
+        let points = read_points(
+            input,
+            offset,
+            num_points,
+            G1_SERIALISED_SIZE,
+            g1s_from_bytes,
+        )
+        .map_err(to_js_error)?;
+
+        let mut section = G1Section::new(start, points);
+        section.subgroup_check().map_err(to_js_error)?;
+        section.update(&private_key);
+        new_accumulated_point = new_accumulated_point.or_else(|| section.power(1));
+        write_points(output, offset, section.points(), g1s_to_bytes);
+    }
+    for start in (0..num_g2).step_by(section_size) {
+        let num_points = std::cmp::min(section_size, num_g2 - start);
+        let offset = g1_len + start * G2_SERIALISED_SIZE;
+        let points = read_points(
+            input,
+            offset,
+            num_points,
+            G2_SERIALISED_SIZE,
+            g2s_from_bytes,
+        )
+        .map_err(to_js_error)?;
+
+        let mut section = G2Section::new(start, points);
+        section.subgroup_check_g2().map_err(to_js_error)?;
+        section.update(&private_key);
+        write_points(output, offset, section.points(), g2s_to_bytes);
+    }
+
+    Ok(UpdateProof {
+        commitment_to_secret: private_key.to_public(),
+        new_accumulated_point: new_accumulated_point
+            .expect("the first section holds at least two powers")
+            .into_projective(),
+    })
+}
+
+// Returns the estimated peak memory, in bytes, of `contribute_srs` when the
+// whole SRS is updated at once. This does not include the memory of the
+// module itself, or of the javascript buffers
+#[wasm_bindgen]
+pub fn estimate_memory(num_g1: usize, num_g2: usize) -> usize {
+    estimate_update(Parameters::new(num_g1, num_g2))
+        .total()
+        .saturating_add(BOUNDARY_BUFFER_SIZE)
+}
+
+// Limits the memory, in bytes, that `contribute_srs` will use for the SRS.
+// Passing undefined removes the limit.
+//
+// If updating the whole SRS at once would exceed the budget, it is updated
+// in the largest sections which fit instead. This is slower, since each
+// section is copied across the wasm boundary separately. A budget that is
+// too small for any section makes `contribute_srs` throw
+#[wasm_bindgen]
+pub fn set_memory_budget(budget: Option<usize>) {
+    MEMORY_BUDGET.store(budget.unwrap_or(0), Ordering::Relaxed);
+}
+
+// Returns the size of the wasm memory in bytes. The memory of a wasm module
+// never shrinks, so this is the peak usage so far, including the module itself
+#[wasm_bindgen]
+pub fn memory_usage() -> usize {
+    let memory: js_sys::WebAssembly::Memory = wasm_bindgen::memory().unchecked_into();
+    let buffer: js_sys::ArrayBuffer = memory.buffer().unchecked_into();
+    buffer.byte_length() as usize
+}
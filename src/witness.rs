@@ -0,0 +1,357 @@
+// The witness of a ceremony, which lets the chain of contributions be published
+// and checked without the SRS which each contribution produced.
+//
+// As in the Ethereum KZG ceremony specs, the witness of an SRS is its degree-1
+// G1 power before the first contribution and after each one, which are the
+// running products of the secrets, along with the pot pubkey and the BLS
+// signature of each contribution.
+//
+// The witness and the final SRS are enough to verify a ceremony, so a
+// verifier never needs to hold more than one accumulator.
+use crate::{
+    accumulator::{self, Accumulator},
+    error::{Error, SerialisationError, VerificationError},
+    interop_point_encoding::{serialize_g1, serialize_g2},
+    report::ContributionRecord,
+    sdk::{check_each_ceremony, transcript::Transcript, CEREMONIES, NUM_CEREMONIES},
+    serialisation::{bytes_to_hex_string, hex_string_to_g1, hex_string_to_g2},
+    srs::{Parameters, VerificationLevel, SRS},
+    update_proof::UpdateProof,
+    verification,
+};
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::Zero;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    // There is one more running product than there are contributions
+    pub running_products: Vec<G1Projective>,
+    pub pot_pubkeys: Vec<G2Projective>,
+    pub bls_signatures: Vec<Option<G1Projective>>,
+}
+
+// The json representation of a witness, where points are 0x prefixed hex
+// strings and a missing signature is an empty string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WitnessJson {
+    pub running_products: Vec<String>,
+    pub pot_pubkeys: Vec<String>,
+    pub bls_signatures: Vec<String>,
+}
+
+impl Default for Witness {
+    // The witness of a fresh SRS, see `SRS::new`
+    fn default() -> Self {
+        Witness::new(G1Projective::prime_subgroup_generator())
+    }
+}
+
+impl Witness {
+    // The witness of an SRS whose degree-1 G1 power is `starting_point`, before
+    // any contributions
+    pub fn new(starting_point: G1Projective) -> Self {
+        Witness {
+            running_products: vec![starting_point],
+            pot_pubkeys: Vec::new(),
+            bls_signatures: Vec::new(),
+        }
+    }
+
+    pub fn from_update_proofs(starting_point: G1Projective, update_proofs: &[UpdateProof]) -> Self {
+        let mut witness = Witness::new(starting_point);
+        for update_proof in update_proofs {
+            witness.push(update_proof, None);
+        }
+        witness
+    }
+
+    // Records a contribution, whose update proof follows on from the last running product
+    pub fn push(&mut self, update_proof: &UpdateProof, bls_signature: Option<G1Projective>) {
+        self.running_products
+            .push(update_proof.new_accumulated_point);
+        self.pot_pubkeys.push(update_proof.commitment_to_secret);
+        self.bls_signatures.push(bls_signature);
+    }
+
+    pub fn num_contributions(&self) -> usize {
+        self.pot_pubkeys.len()
+    }
+
+    // The update proof of each contribution, in order
+    pub fn update_proofs(&self) -> Vec<UpdateProof> {
+        self.running_products[1..]
+            .iter()
+            .zip(&self.pot_pubkeys)
+            .map(
+                |(new_accumulated_point, commitment_to_secret)| UpdateProof {
+                    commitment_to_secret: *commitment_to_secret,
+                    new_accumulated_point: *new_accumulated_point,
+                },
+            )
+            .collect()
+    }
+
+    // Verifies that the contributions in the witness form a chain which ends
+    // at `final_srs`, and checks the structure of `final_srs` at `level`, in
+    // the same way as `SRS::verify_updates_at_level`.
+    //
+    // The chain starts from the first running product, so the caller should
+    // check that it is the degree-1 power of the SRS which the ceremony
+    // started from. The signatures are not checked, since the witness does
+    // not include the identities
+    pub fn verify(
+        &self,
+        final_srs: &SRS,
+        random_element: Fr,
+        level: VerificationLevel,
+    ) -> Result<(), VerificationError> {
+        self.check_chain(final_srs.g1_elements()[1], final_srs.g2_elements()[1])?;
+        final_srs.check_at_level(level, random_element)
+    }
+
+    // Verifies the witness in the same way as `verify`, against an accumulator
+    // which is checked `chunk_size` powers at a time, see `accumulator::verify`
+    pub fn verify_accumulator(
+        &self,
+        final_accumulator: &mut impl Accumulator,
+        random_element: Fr,
+        chunk_size: usize,
+    ) -> Result<(), Error> {
+        let g1_tau = final_accumulator.read_g1(1..2)?.power(1);
+        let g2_tau = final_accumulator.read_g2(1..2)?.power(1);
+        match (g1_tau, g2_tau) {
+            (Some(g1_tau), Some(g2_tau)) => self.check_chain(g1_tau, g2_tau)?,
+            _ => return Err(VerificationError::InvalidStructure.into()),
+        }
+        accumulator::verify(final_accumulator, random_element, chunk_size)
+    }
+
+    fn check_chain(&self, g1_tau: G1Affine, g2_tau: G2Affine) -> Result<(), VerificationError> {
+        if self.num_contributions() == 0 {
+            return Err(VerificationError::NoUpdateProofs);
+        }
+        if self.running_products.len() != self.num_contributions() + 1 {
+            return Err(VerificationError::InvalidUpdateChain);
+        }
+
+        if self.running_products[self.num_contributions()] != g1_tau.into_projective() {
+            return Err(VerificationError::UpdateDoesNotEndAtSRS);
+        }
+        if self
+            .running_products
+            .windows(2)
+            .any(|pair| pair[0] == pair[1])
+        {
+            return Err(VerificationError::NoOpUpdate);
+        }
+        if !verification::verify_shared_secret_chain(&self.running_products, &self.pot_pubkeys) {
+            return Err(VerificationError::InvalidUpdateChain);
+        }
+        if g1_tau.is_zero() || g2_tau.is_zero() {
+            return Err(VerificationError::IdentityElement);
+        }
+        Ok(())
+    }
+}
+
+// Verifies a transcript using only the witness of each ceremony, which must
+// start from the initial transcript, see `Witness::verify`
+pub fn verify_transcript_witnesses(
+    witnesses: &[Witness; NUM_CEREMONIES],
+    transcript: &Transcript,
+    random_element: Fr,
+    level: VerificationLevel,
+) -> Result<(), VerificationError> {
+    // Every contribution updates every ceremony
+    let num_contributions = witnesses[0].num_contributions();
+    if witnesses
+        .iter()
+        .any(|witness| witness.num_contributions() != num_contributions)
+    {
+        return Err(VerificationError::InvalidUpdateChain);
+    }
+
+    check_each_ceremony(|i| {
+        let srs = &transcript.transcripts[i];
+        let parameters = Parameters::new(srs.g1_elements().len(), srs.g2_elements().len());
+        if parameters != CEREMONIES[i] {
+            return Err(VerificationError::InvalidStructure);
+        }
+        if witnesses[i].running_products[0] != G1Projective::prime_subgroup_generator() {
+            return Err(VerificationError::InvalidUpdateChain);
+        }
+        witnesses[i].verify(srs, random_element, level)
+    })
+}
+
+// Exports the witness of each ceremony of a transcript from its contributions,
+// starting from the initial transcript
+pub fn transcript_witnesses(contributions: &[ContributionRecord]) -> [Witness; NUM_CEREMONIES] {
+    let mut witnesses: [Witness; NUM_CEREMONIES] = Default::default();
+    for contribution in contributions {
+        for (i, witness) in witnesses.iter_mut().enumerate() {
+            witness.push(&contribution.update_proofs[i], contribution.signatures[i]);
+        }
+    }
+    witnesses
+}
+
+impl From<&Witness> for WitnessJson {
+    fn from(witness: &Witness) -> Self {
+        WitnessJson {
+            running_products: witness
+                .running_products
+                .iter()
+                .map(|point| bytes_to_hex_string(&serialize_g1(&point.into_affine())))
+                .collect(),
+            pot_pubkeys: witness
+                .pot_pubkeys
+                .iter()
+                .map(|point| bytes_to_hex_string(&serialize_g2(&point.into_affine())))
+                .collect(),
+            bls_signatures: witness
+                .bls_signatures
+                .iter()
+                .map(|signature| match signature {
+                    Some(signature) => bytes_to_hex_string(&serialize_g1(&signature.into_affine())),
+                    None => String::new(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<&WitnessJson> for Witness {
+    type Error = Error;
+
+    // The points are checked to be on the curve, but not to be in the prime
+    // order subgroup, in the same way as `UpdateProof::deserialise`
+    fn try_from(witness: &WitnessJson) -> Result<Self, Self::Error> {
+        let num_contributions = witness.pot_pubkeys.len();
+        if witness.running_products.len() != num_contributions + 1 {
+            return Err(SerialisationError::InvalidLength {
+                expected: num_contributions + 1,
+                got: witness.running_products.len(),
+            }
+            .into());
+        }
+        if witness.bls_signatures.len() != num_contributions {
+            return Err(SerialisationError::InvalidLength {
+                expected: num_contributions,
+                got: witness.bls_signatures.len(),
+            }
+            .into());
+        }
+
+        let running_products = witness
+            .running_products
+            .iter()
+            .map(|point| Ok(hex_string_to_g1(point)?.into_projective()))
+            .collect::<Result<_, SerialisationError>>()?;
+        let pot_pubkeys = witness
+            .pot_pubkeys
+            .iter()
+            .map(|point| Ok(hex_string_to_g2(point)?.into_projective()))
+            .collect::<Result<_, SerialisationError>>()?;
+        let bls_signatures = witness
+            .bls_signatures
+            .iter()
+            .map(|signature| match signature.as_str() {
+                "" => Ok(None),
+                signature => Ok(Some(hex_string_to_g1(signature)?.into_projective())),
+            })
+            .collect::<Result<_, SerialisationError>>()?;
+
+        Ok(Witness {
+            running_products,
+            pot_pubkeys,
+            bls_signatures,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        keypair::PrivateKey,
+        srs::{Parameters, SRS},
+    };
+
+    #[test]
+    fn export_witness() {
+        let mut srs = SRS::new(Parameters::new(8, 4)).unwrap();
+        let update_proofs = [
+            srs.update(PrivateKey::from_u64(252)),
+            srs.update(PrivateKey::from_u64(789)),
+        ];
+        let witness =
+            Witness::from_update_proofs(G1Projective::prime_subgroup_generator(), &update_proofs);
+        assert_eq!(witness.num_contributions(), 2);
+        assert_eq!(witness.update_proofs(), update_proofs);
+        assert_eq!(
+            witness.running_products[2],
+            srs.g1_elements()[1].into_projective()
+        );
+
+        let mut signed = witness;
+        signed.bls_signatures[1] = Some(PrivateKey::from_u64(789).sign(b"git|1234|alice"));
+        let json = WitnessJson::from(&signed);
+        assert_eq!(json.bls_signatures[0], "");
+        assert_eq!(Witness::try_from(&json).unwrap(), signed);
+
+        let mut truncated = json;
+        truncated.running_products.pop();
+        assert!(matches!(
+            Witness::try_from(&truncated),
+            Err(Error::Serialisation(
+                SerialisationError::InvalidLength { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn verify_with_witness() {
+        let parameters = Parameters::new(8, 4);
+        let mut srs = SRS::new(parameters).unwrap();
+        let update_proofs = [
+            srs.update(PrivateKey::from_u64(252)),
+            srs.update(PrivateKey::from_u64(789)),
+        ];
+        let witness =
+            Witness::from_update_proofs(G1Projective::prime_subgroup_generator(), &update_proofs);
+        let random_element = Fr::from(123456789u64);
+
+        assert!(witness
+            .verify(&srs, random_element, VerificationLevel::Standard)
+            .is_ok());
+        assert!(witness
+            .verify_accumulator(&mut srs.clone(), random_element, 3)
+            .is_ok());
+
+        // The witness of the first contribution does not end at the final SRS
+        let partial = Witness::from_update_proofs(
+            G1Projective::prime_subgroup_generator(),
+            &update_proofs[..1],
+        );
+        assert_eq!(
+            partial.verify(&srs, random_element, VerificationLevel::Standard),
+            Err(VerificationError::UpdateDoesNotEndAtSRS)
+        );
+
+        let mut tampered = witness;
+        tampered.pot_pubkeys[0] = PrivateKey::from_u64(253).to_public();
+        assert_eq!(
+            tampered.verify(&srs, random_element, VerificationLevel::Standard),
+            Err(VerificationError::InvalidUpdateChain)
+        );
+
+        assert_eq!(
+            Witness::default().verify(&srs, random_element, VerificationLevel::Standard),
+            Err(VerificationError::NoUpdateProofs)
+        );
+    }
+}